@@ -26,6 +26,15 @@ fn brainfuck_bench(c: &mut Criterion) {
         |b| b.iter(|| brainfuck.execute().ok())
     );
 
+    brainfuck = black_box(
+        Brainfuck::new(brainfuck.code.clone())
+            .with_no_output()
+    );
+    c.bench_function(
+        "(no output) brainfuck_hello_world",
+        |b| b.iter(|| brainfuck.execute().ok())
+    );
+
     brainfuck = black_box(
         Brainfuck::new(",+>,++>,+>,++>,+>")
             .with_input(Cursor::new("12345".as_bytes().to_vec()))
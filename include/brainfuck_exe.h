@@ -0,0 +1,93 @@
+#ifndef BRAINFUCK_EXE_H
+#define BRAINFUCK_EXE_H
+
+/* generated by cbindgen from `src/ffi.rs`, do not edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// the status returned by the functions of the C ABI
+typedef enum BfStatus {
+  // the call succeeded
+  BF_STATUS_OK = 0,
+  // a pointer that must not be null was null, or a string was not valid UTF-8
+  BF_STATUS_INVALID_ARGUMENT = 1,
+  // the name passed to [`bf_set_option`] is not an option
+  BF_STATUS_UNKNOWN_OPTION = 2,
+  // the value passed to [`bf_set_option`] is invalid for the option
+  BF_STATUS_INVALID_VALUE = 3,
+  // the execution failed, see [`bf_get_error`] for the reason
+  BF_STATUS_EXECUTION_FAILED = 4,
+} BfStatus;
+
+// an interpreter owned by C code, created with [`bf_new`] and destroyed with [`bf_free`]
+//
+// it keeps the output of the last execution and the message of the last error
+typedef struct BfInterpreter BfInterpreter;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// creates an interpreter of the provided NUL-terminated code, returning null if it is null or not valid UTF-8
+//
+// # Safety
+// `code` must be null or point to a valid NUL-terminated string
+struct BfInterpreter *bf_new(const char *code);
+
+// sets an option of the interpreter from its NUL-terminated name and value,
+// named after the keys of the configuration file of the CLI, such as `max-cell-value` set to `65535`:
+// - `max-cell-value`, `memory-size`, `instructions-limit`, `timeout` (in milliseconds) and `seed` as integers
+// - `fallback-char` as a single character
+// - `no-wrap`, `strict` and `detect-infinite-loops` as `true` or `false`
+// - `eof` as one of `fallback`, `max` or `unchanged`
+// - `opt-level` as one of `0`, `1` or `2`
+// - `dialect` as one of `brainfuck`, `pbrain`, `brainfork`, `extended-type-i` or `boolfuck`
+//
+// # Safety
+// `interp` must be null or have been returned by [`bf_new`] and not yet freed,
+// and `name` and `value` must be null or point to valid NUL-terminated strings
+enum BfStatus bf_set_option(struct BfInterpreter *interp,
+                            const char *name,
+                            const char *value);
+
+// executes the program with the provided input of `input_len` bytes, replacing the output of the last execution
+//
+// # Safety
+// `interp` must be null or have been returned by [`bf_new`] and not yet freed,
+// and `input` must be null or point to `input_len` readable bytes
+enum BfStatus bf_execute(struct BfInterpreter *interp,
+                         const uint8_t *input,
+                         size_t input_len);
+
+// returns the output of the last execution, writing its length into `len`,
+// the bytes being valid until the next execution or until the interpreter is freed
+//
+// returns null, and writes `0` into `len`, if `interp` is null
+//
+// # Safety
+// `interp` must be null or have been returned by [`bf_new`] and not yet freed,
+// and `len` must be null or point to a writable `size_t`
+const uint8_t *bf_get_output(const struct BfInterpreter *interp, size_t *len);
+
+// returns the NUL-terminated message of the error of the last call, or null if it succeeded,
+// the message being valid until the next call or until the interpreter is freed
+//
+// # Safety
+// `interp` must be null or have been returned by [`bf_new`] and not yet freed
+const char *bf_get_error(const struct BfInterpreter *interp);
+
+// frees the interpreter, doing nothing if it is null
+//
+// # Safety
+// `interp` must be null or have been returned by [`bf_new`] and not yet freed
+void bf_free(struct BfInterpreter *interp);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BRAINFUCK_EXE_H */
@@ -0,0 +1,4 @@
+fn main() {
+    // sets up the linking against Node.js, which provides the symbols of Node-API at runtime
+    napi_build::setup();
+}
@@ -0,0 +1,199 @@
+//! the [`napi`](https://docs.rs/napi) bindings of [`brainfuck_exe`] for Node.js, see [`execute`]
+//!
+//! the library is built into a `.node` addon, kept apart from the main crate so that the CLI never loads Node-API
+
+use std::{io::Cursor, time::Duration};
+use napi::{bindgen_prelude::AsyncTask, Env, Error as NapiError, Result as NapiResult, Task};
+use napi_derive::napi;
+use brainfuck_exe::{Brainfuck, Dialect, EofBehavior, ExecutionInfo, OptimizationLevel};
+
+/// the options taken by [`execute`], every one of them being optional
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ExecuteOptions {
+    /// the input read by the `,` operation, empty by default
+    pub input: Option<String>,
+    /// the max value of a cell
+    pub max_cell_value: Option<u32>,
+    /// the max length of the memory array
+    pub memory_size: Option<u32>,
+    /// the limit on the amount of instructions executed
+    pub instructions_limit: Option<i64>,
+    /// the limit on the wall-clock time of the execution, in milliseconds
+    pub timeout: Option<u32>,
+    /// the character `,` reads on EOF, along with the `fallback` EOF behavior
+    pub fallback_input: Option<String>,
+    /// whether or not `+` and `-` wrap the current cell around
+    pub wrapping: Option<bool>,
+    /// one of `none`, `basic` or `aggressive`
+    pub optimization: Option<String>,
+    /// one of `fallback`, `max` or `unchanged`
+    pub eof: Option<String>,
+    /// one of `brainfuck`, `pbrain`, `brainfork`, `extended-type-i` or `boolfuck`
+    pub dialect: Option<String>,
+}
+
+/// the output and execution information resolved by [`execute`]
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ExecuteResult {
+    /// everything the program wrote with the `.` operation, decoded as UTF-8
+    pub output: String,
+    /// the final memory array
+    pub cells: Vec<u32>,
+    /// the final pointer
+    pub pointer: u32,
+    /// the amount of instructions executed
+    pub instructions: i64,
+    /// how long the execution took, in milliseconds
+    pub time: f64,
+}
+
+/// returns an error rejecting the promise with the provided message
+fn invalid(message: &str) -> NapiError {
+    NapiError::from_reason(message)
+}
+
+/// builds the interpreter of the provided code configured by the provided options
+fn configure<'a>(code: &str, options: &ExecuteOptions) -> NapiResult<Brainfuck<'a>> {
+    let mut interp = Brainfuck::new(code);
+
+    if let Some(value) = options.max_cell_value {
+        interp = interp.with_max_value(value);
+    }
+    if let Some(size) = options.memory_size {
+        interp = interp.with_mem_size(size as usize);
+    }
+    if let Some(limit) = options.instructions_limit {
+        let limit = usize::try_from(limit)
+            .map_err(|_| invalid("`instructionsLimit` must be a non-negative integer"))?;
+        interp = interp.with_instructions_limit(limit);
+    }
+    if let Some(timeout) = options.timeout {
+        interp = interp.with_timeout(Duration::from_millis(u64::from(timeout)));
+    }
+    if let Some(ref chr) = options.fallback_input {
+        let chr = chr
+            .chars()
+            .next()
+            .ok_or_else(|| invalid("`fallbackInput` must not be empty"))?;
+        interp = interp.with_fallback_input(chr);
+    }
+    if let Some(wrapping) = options.wrapping {
+        interp = interp.with_wrapping(wrapping);
+    }
+    if let Some(ref level) = options.optimization {
+        let level = OptimizationLevel::from_name(level)
+            .ok_or_else(|| invalid("`optimization` must be one of `none`, `basic` or `aggressive`"))?;
+        interp = interp.with_optimization(level);
+    }
+    if let Some(ref eof) = options.eof {
+        let eof = EofBehavior::from_name(eof)
+            .ok_or_else(|| invalid("`eof` must be one of `fallback`, `max` or `unchanged`"))?;
+        interp = interp.with_eof(eof);
+    }
+    if let Some(ref dialect) = options.dialect {
+        let dialect = Dialect::from_name(dialect).ok_or_else(|| invalid(
+            "`dialect` must be one of `brainfuck`, `pbrain`, `brainfork`, `extended-type-i` or `boolfuck`"
+        ))?;
+        interp = interp.with_dialect(dialect);
+    }
+    Ok(interp)
+}
+
+/// the execution of a program on the thread pool of Node.js, see [`execute`]
+pub struct Execution {
+    /// the code to execute
+    code: String,
+    /// the options to execute the code with
+    options: ExecuteOptions,
+}
+
+impl Task for Execution {
+    type Output = (Vec<u8>, ExecutionInfo);
+    type JsValue = ExecuteResult;
+
+    fn compute(&mut self) -> NapiResult<Self::Output> {
+        let input = self.options.input
+            .take()
+            .unwrap_or_default();
+        let mut output = Vec::new();
+
+        let info = configure(&self.code, &self.options)?
+            .with_input(Cursor::new(input.into_bytes()))
+            .with_output_ref(&mut output)
+            .execute()
+            .map_err(|err| NapiError::from_reason(err.to_string()))?;
+        Ok((output, info))
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn resolve(&mut self, _: Env, (output, info): Self::Output) -> NapiResult<Self::JsValue> {
+        Ok(ExecuteResult {
+            output: String::from_utf8_lossy(&output).into_owned(),
+            cells: info.cells,
+            pointer: info.pointer as u32,
+            instructions: info.instructions as i64,
+            time: info.time.map_or(0.0, |time| time.as_secs_f64() * 1000.0),
+        })
+    }
+}
+
+/// executes the provided code on the thread pool of Node.js, without blocking the event loop,
+/// resolving to `{ output, cells, pointer, instructions, time }`, the time being in milliseconds
+///
+/// the promise is rejected with the message of the error if the options are invalid or the execution fails
+///
+/// ```js
+/// const { execute } = require("brainfuck-exe");
+///
+/// const { output } = await execute(",[.,]", { input: "hello", instructionsLimit: 1_000_000 });
+/// ```
+#[napi]
+#[must_use]
+pub fn execute(code: String, options: Option<ExecuteOptions>) -> AsyncTask<Execution> {
+    AsyncTask::new(Execution { code, options: options.unwrap_or_default() })
+}
+
+#[cfg(test)]
+mod tests {
+    use napi::Task;
+    use super::{execute, ExecuteOptions, Execution};
+
+    /// computes the execution of the provided code off the event loop, as Node.js does for the task of [`execute`]
+    fn compute(code: &str, options: ExecuteOptions) -> napi::Result<(Vec<u8>, brainfuck_exe::ExecutionInfo)> {
+        Execution { code: code.to_string(), options }.compute()
+    }
+
+    #[test]
+    fn test_execute() -> napi::Result<()> {
+        let (output, info) = compute(",[.,]", ExecuteOptions {
+            input: Some(String::from("hello")),
+            optimization: Some(String::from("aggressive")),
+            ..ExecuteOptions::default()
+        })?;
+
+        assert_eq!(output, b"hello");
+        assert_eq!(info.cells, [0]);
+
+        // the task is only created here, as it is computed once Node.js polls it
+        let _ = execute(String::from("+."), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_errors() {
+        let message = |result: napi::Result<_>| result.map(|_| ()).unwrap_err().reason;
+
+        assert_eq!(
+            message(compute("+", ExecuteOptions { eof: Some(String::from("never")), ..ExecuteOptions::default() })),
+            "`eof` must be one of `fallback`, `max` or `unchanged`",
+        );
+        assert_eq!(
+            message(compute("+", ExecuteOptions { instructions_limit: Some(-1), ..ExecuteOptions::default() })),
+            "`instructionsLimit` must be a non-negative integer",
+        );
+        assert!(message(compute("+[]", ExecuteOptions { instructions_limit: Some(10), ..ExecuteOptions::default() }))
+            .contains("instructions"));
+    }
+}
@@ -0,0 +1,123 @@
+//! module containing the static analysis of a [`Program`], see [`Program::analyze`]
+
+use crate::{
+    ast::{Node, NodeKind, Program},
+    Span,
+};
+
+/// the static analysis of a single loop of a [`Program`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LoopAnalysis {
+    /// the range of the code of the loop, from its `[` up to and including its `]`
+    pub span: Span,
+    /// the net movement of the pointer over a single iteration of the loop, to the right if positive
+    ///
+    /// it is [`None`] if it cannot be determined statically,
+    /// as the loop contains a nested loop that is not balanced
+    pub shift: Option<isize>,
+    /// whether or not the pointer always ends up on the same cell after every iteration of the loop
+    pub balanced: bool,
+}
+
+/// the static analysis of a [`Program`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Analysis {
+    /// the analysis of every loop, in the order they appear in
+    pub loops: Vec<LoopAnalysis>,
+    /// the rightmost cell the pointer can ever reach
+    ///
+    /// it is [`None`] if it cannot be determined statically, as the program contains a loop that is not balanced,
+    /// or the pointer moves to the left of the first cell
+    pub max_pointer: Option<usize>,
+}
+
+impl Analysis {
+    /// returns the smallest fixed memory size the program can safely be executed with, if it can be determined
+    #[must_use]
+    pub fn memory_size(&self) -> Option<usize> {
+        self.max_pointer
+            .map(|ptr| ptr + 1)
+    }
+}
+
+/// the extent of the pointer movement of a sequence of nodes, relative to the cell it starts at
+#[derive(Clone, Copy)]
+struct Extent {
+    /// the net movement of the pointer
+    shift: isize,
+    /// the leftmost cell reached
+    min: isize,
+    /// the rightmost cell reached
+    max: isize,
+}
+
+/// analyzes the provided nodes, recording the analysis of every loop within them,
+/// returning their extent if it can be determined statically
+fn walk(nodes: &[Node], loops: &mut Vec<LoopAnalysis>) -> Option<Extent> {
+    let mut extent = Some(Extent { shift: 0, min: 0, max: 0 });
+
+    for node in nodes {
+        match node.kind {
+            NodeKind::Move(amount) => if let Some(ref mut extent) = extent {
+                extent.shift += amount;
+                extent.min = extent.min.min(extent.shift);
+                extent.max = extent.max.max(extent.shift);
+            },
+            NodeKind::Loop(ref body) => {
+                // reserves the slot of the loop, so that it comes before its nested loops
+                let idx = loops.len();
+                loops.push(LoopAnalysis { span: node.span, shift: None, balanced: false });
+
+                let inner = walk(body, loops);
+                let shift = inner.map(|inner| inner.shift);
+                loops[idx].shift = shift;
+                loops[idx].balanced = shift == Some(0);
+
+                // an unbalanced loop moves the pointer by an unknown amount of iterations
+                extent = extent
+                    .zip(inner.filter(|inner| inner.shift == 0))
+                    .map(|(outer, inner)| Extent {
+                        shift: outer.shift,
+                        min: outer.min.min(outer.shift + inner.min),
+                        max: outer.max.max(outer.shift + inner.max),
+                    });
+            },
+            _ => (),
+        }
+    }
+    extent
+}
+
+impl Program {
+    /// statically analyzes the pointer movement of the program, without executing it
+    ///
+    /// - reports whether or not every loop is balanced, always ending up on the same cell after an iteration
+    /// - estimates the rightmost cell the pointer can reach, which can be used to pick a safe fixed memory size
+    ///
+    /// ```rust
+    /// use brainfuck_exe::ast::Program;
+    ///
+    /// let analysis = Program::parse("++[>+>++<<-]>>.")?.analyze();
+    ///
+    /// assert!(analysis.loops[0].balanced);
+    /// assert_eq!(analysis.memory_size(), Some(3));
+    ///
+    /// let analysis = Program::parse("+[>+]")?.analyze();
+    ///
+    /// assert_eq!(analysis.loops[0].shift, Some(1));
+    /// assert_eq!(analysis.memory_size(), None);
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn analyze(&self) -> Analysis {
+        let mut loops = Vec::new();
+        let extent = walk(&self.nodes, &mut loops);
+
+        Analysis {
+            loops,
+            max_pointer: extent
+                .filter(|extent| extent.min >= 0)
+                .and_then(|extent| usize::try_from(extent.max).ok()),
+        }
+    }
+}
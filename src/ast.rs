@@ -0,0 +1,325 @@
+//! module containing the abstract syntax tree of brainfuck code, built by [`parse`]
+//!
+//! the tree can be walked with a [`Visitor`] and rewritten with a [`Folder`],
+//! which only have to handle the kinds of nodes they are interested in
+
+use std::{fmt, mem};
+use crate::{machine::Machine, Position, Result, Span};
+
+/// the operation of a [`Node`]
+///
+/// more kinds of nodes may be added in the future, prefer a [`Visitor`] or [`Folder`] over matching on it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NodeKind {
+    /// a run of `+` and `-`, adding their net amount to the current cell
+    Inc(i64),
+    /// a run of `>` and `<`, moving the pointer by their net amount, to the right if positive
+    Move(isize),
+    /// a `[` `]` pair along with the nodes enclosed within it
+    Loop(Vec<Node>),
+    /// a `,`
+    Input,
+    /// a `.`
+    Output,
+    /// writes the string into the output at once, produced by the [`crate::optimizer::Optimizer`]
+    /// folding the output of code that does not depend on any input
+    ///
+    /// it is written back as code printing the string through the current cell,
+    /// which must be `0` and is cleared back to `0` afterwards
+    Emit(String),
+}
+
+/// a node of the syntax tree, along with the range of the code it was parsed from
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Node {
+    /// the operation of the node
+    pub kind: NodeKind,
+    /// the range of the code the node was parsed from,
+    /// including any comments in between the instructions of a run
+    pub span: Span,
+}
+
+impl fmt::Display for Node {
+    /// writes the node back as code, without any comments and with every run reduced to its net amount
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            NodeKind::Inc(amount) => f.write_str(
+                &(if amount > 0 { "+" } else { "-" }).repeat(amount.unsigned_abs() as usize)
+            ),
+            NodeKind::Move(amount) => f.write_str(
+                &(if amount > 0 { ">" } else { "<" }).repeat(amount.unsigned_abs())
+            ),
+            NodeKind::Loop(ref body) => {
+                f.write_str("[")?;
+                for node in body {
+                    write!(f, "{node}")?;
+                }
+                f.write_str("]")
+            },
+            NodeKind::Input => f.write_str(","),
+            NodeKind::Output => f.write_str("."),
+            NodeKind::Emit(ref string) => {
+                let mut value = 0_i64;
+
+                for chr in string.chars() {
+                    let delta = i64::from(u32::from(chr)) - value;
+                    value += delta;
+
+                    write!(f, "{}.", Self { kind: NodeKind::Inc(delta), span: self.span })?;
+                }
+                write!(f, "{}", Self { kind: NodeKind::Inc(-value), span: self.span })
+            },
+        }
+    }
+}
+
+/// parses the provided code into its syntax tree
+///
+/// consecutive `+` and `-` are merged into a single [`NodeKind::Inc`], and `>` and `<` into a single [`NodeKind::Move`],
+/// even if separated by comments; every other character is ignored as a comment
+///
+/// # Errors
+/// - [`crate::Error::MismatchedBrackets`]: the code contains an unmatched `[` or a stray `]`
+///
+/// ```rust
+/// use brainfuck_exe::ast::{parse, NodeKind};
+///
+/// let nodes = parse("++>-[<.]")?;
+///
+/// assert_eq!(nodes.len(), 4);
+/// assert_eq!(nodes[0].kind, NodeKind::Inc(2));
+/// assert_eq!(nodes[3].span.range(), 4..8);
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+pub fn parse(code: &str) -> Result<Vec<Node>> {
+    let code = code
+        .chars()
+        .collect::<Vec<char>>();
+    Machine::match_brackets(&code)?;
+
+    let positions = Position::locate_all(&code);
+    let mut nodes = Vec::new();
+    // the index of every enclosing `[`, along with the nodes before it
+    let mut stack = Vec::new();
+
+    for (idx, chr) in code.iter().enumerate() {
+        let span = Span {
+            start: positions[idx],
+            end: positions[idx + 1],
+        };
+
+        match chr {
+            '+' | '-' => {
+                let delta = if *chr == '+' { 1 } else { -1 };
+
+                if let Some(Node { kind: NodeKind::Inc(amount), span: last }) = nodes.last_mut() {
+                    *amount += delta;
+                    last.end = span.end;
+                } else {
+                    nodes.push(Node { kind: NodeKind::Inc(delta), span });
+                }
+            },
+            '>' | '<' => {
+                let delta = if *chr == '>' { 1 } else { -1 };
+
+                if let Some(Node { kind: NodeKind::Move(amount), span: last }) = nodes.last_mut() {
+                    *amount += delta;
+                    last.end = span.end;
+                } else {
+                    nodes.push(Node { kind: NodeKind::Move(delta), span });
+                }
+            },
+            ',' => nodes.push(Node { kind: NodeKind::Input, span }),
+            '.' => nodes.push(Node { kind: NodeKind::Output, span }),
+            '[' => stack.push((idx, mem::take(&mut nodes))),
+            ']' => if let Some((open, outer)) = stack.pop() {
+                let body = mem::replace(&mut nodes, outer);
+
+                nodes.push(Node {
+                    kind: NodeKind::Loop(body),
+                    span: Span { start: positions[open], end: span.end },
+                });
+            },
+            _ => (),
+        }
+    }
+    Ok(nodes)
+}
+
+/// a parsed program, the root of the syntax tree
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Program {
+    /// the top-level nodes of the program
+    pub nodes: Vec<Node>,
+}
+
+impl Program {
+    /// parses the provided code into a program, see [`parse`]
+    ///
+    /// # Errors
+    /// - [`crate::Error::MismatchedBrackets`]: the code contains an unmatched `[` or a stray `]`
+    pub fn parse(code: &str) -> Result<Self> {
+        parse(code)
+            .map(|nodes| Self { nodes })
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.nodes {
+            write!(f, "{node}")?;
+        }
+        Ok(())
+    }
+}
+
+/// walks the syntax tree, visiting every node in the order they appear in
+///
+/// every method does nothing by default, other than [`Visitor::visit_loop`] walking into the body of the loop
+///
+/// ```rust
+/// use brainfuck_exe::{ast::{parse, Visitor}, Span};
+///
+/// /// counts the `.` operations of the code
+/// struct Outputs(usize);
+///
+/// impl Visitor for Outputs {
+///     fn visit_output(&mut self, _span: Span) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// let mut outputs = Outputs(0);
+/// outputs.visit_nodes(&parse("+[.>.]<.")?);
+///
+/// assert_eq!(outputs.0, 3);
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+pub trait Visitor {
+    /// visits every one of the provided nodes in order
+    fn visit_nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.visit_node(node);
+        }
+    }
+
+    /// visits a single node, dispatching to the method of its kind
+    fn visit_node(&mut self, node: &Node) {
+        walk_node(self, node);
+    }
+
+    /// visits a run of `+` and `-` with the provided net amount
+    fn visit_inc(&mut self, _amount: i64, _span: Span) {}
+
+    /// visits a run of `>` and `<` with the provided net amount
+    fn visit_move(&mut self, _amount: isize, _span: Span) {}
+
+    /// visits a loop with the provided body, walking into it by default
+    fn visit_loop(&mut self, body: &[Node], _span: Span) {
+        self.visit_nodes(body);
+    }
+
+    /// visits a `,`
+    fn visit_input(&mut self, _span: Span) {}
+
+    /// visits a `.`
+    fn visit_output(&mut self, _span: Span) {}
+
+    /// visits the folded output of the provided string
+    fn visit_emit(&mut self, _string: &str, _span: Span) {}
+}
+
+/// dispatches the provided node to the method of its kind on the visitor,
+/// which is the default behavior of [`Visitor::visit_node`]
+pub fn walk_node<V: Visitor + ?Sized>(visitor: &mut V, node: &Node) {
+    match node.kind {
+        NodeKind::Inc(amount) => visitor.visit_inc(amount, node.span),
+        NodeKind::Move(amount) => visitor.visit_move(amount, node.span),
+        NodeKind::Loop(ref body) => visitor.visit_loop(body, node.span),
+        NodeKind::Input => visitor.visit_input(node.span),
+        NodeKind::Output => visitor.visit_output(node.span),
+        NodeKind::Emit(ref string) => visitor.visit_emit(string, node.span),
+    }
+}
+
+/// rewrites the syntax tree, folding every node into either a replacement or nothing, removing it
+///
+/// every method keeps the node as is by default, other than [`Folder::fold_loop`] folding the body of the loop
+///
+/// ```rust
+/// use brainfuck_exe::{ast::{parse, Folder, Node, NodeKind}, Span};
+///
+/// /// removes every `.` operation of the code
+/// struct Silence;
+///
+/// impl Folder for Silence {
+///     fn fold_output(&mut self, _span: Span) -> Option<Node> {
+///         None
+///     }
+/// }
+///
+/// let nodes = Silence.fold_nodes(parse("+[.-]")?);
+/// let NodeKind::Loop(ref body) = nodes[1].kind else {
+///     unreachable!();
+/// };
+///
+/// assert_eq!(body.len(), 1);
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+pub trait Folder {
+    /// folds every one of the provided nodes in order, discarding the removed ones
+    fn fold_nodes(&mut self, nodes: Vec<Node>) -> Vec<Node> {
+        nodes.into_iter()
+            .filter_map(|node| self.fold_node(node))
+            .collect()
+    }
+
+    /// folds a single node, dispatching to the method of its kind
+    fn fold_node(&mut self, node: Node) -> Option<Node> {
+        fold_node(self, node)
+    }
+
+    /// folds a run of `+` and `-` with the provided net amount
+    fn fold_inc(&mut self, amount: i64, span: Span) -> Option<Node> {
+        Some(Node { kind: NodeKind::Inc(amount), span })
+    }
+
+    /// folds a run of `>` and `<` with the provided net amount
+    fn fold_move(&mut self, amount: isize, span: Span) -> Option<Node> {
+        Some(Node { kind: NodeKind::Move(amount), span })
+    }
+
+    /// folds a loop with the provided body, folding the body by default
+    fn fold_loop(&mut self, body: Vec<Node>, span: Span) -> Option<Node> {
+        Some(Node { kind: NodeKind::Loop(self.fold_nodes(body)), span })
+    }
+
+    /// folds a `,`
+    fn fold_input(&mut self, span: Span) -> Option<Node> {
+        Some(Node { kind: NodeKind::Input, span })
+    }
+
+    /// folds a `.`
+    fn fold_output(&mut self, span: Span) -> Option<Node> {
+        Some(Node { kind: NodeKind::Output, span })
+    }
+
+    /// folds the folded output of the provided string
+    fn fold_emit(&mut self, string: String, span: Span) -> Option<Node> {
+        Some(Node { kind: NodeKind::Emit(string), span })
+    }
+}
+
+/// dispatches the provided node to the method of its kind on the folder,
+/// which is the default behavior of [`Folder::fold_node`]
+pub fn fold_node<F: Folder + ?Sized>(folder: &mut F, node: Node) -> Option<Node> {
+    match node.kind {
+        NodeKind::Inc(amount) => folder.fold_inc(amount, node.span),
+        NodeKind::Move(amount) => folder.fold_move(amount, node.span),
+        NodeKind::Loop(body) => folder.fold_loop(body, node.span),
+        NodeKind::Input => folder.fold_input(node.span),
+        NodeKind::Output => folder.fold_output(node.span),
+        NodeKind::Emit(string) => folder.fold_emit(string, node.span),
+    }
+}
@@ -0,0 +1,117 @@
+//! module containing the asynchronous execution mode of the interpreter
+//!
+//! requires the `async` feature
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use crate::{Action, Brainfuck, ExecutionInfo, Result};
+
+/// the amount of steps taken in between yielding back to the async runtime
+///
+/// this keeps long running programs from blocking the runtime's other tasks
+pub const ASYNC_YIELD_INTERVAL: usize = 4096;
+
+impl<'a> Brainfuck<'a> {
+    /// executes the provided brainfuck code asynchronously
+    ///
+    /// this is the same as [`Brainfuck::execute`],
+    /// except that the `,` and `.` operations await on the provided async `input` and `output` streams
+    /// instead of the ones stored in the struct, and control is yielded back to the runtime
+    /// every [`ASYNC_YIELD_INTERVAL`] steps so it can run inside of a [`tokio`] task
+    ///
+    /// # Errors
+    /// - [`crate::Error::ZeroMemorySize`] and [`crate::Error::ZeroMaxCellValue`]: the configuration is invalid, see [`crate::Brainfuck::validate`]
+    /// - [`crate::Error::MismatchedBrackets`]: the code contains an unmatched `[` or a stray `]`
+    /// - [`crate::Error::UnexpectedCharacter`]: the code contains a character that is neither an instruction
+    ///   nor whitespace, while `strict_syntax` is enabled
+    /// - [`crate::Error::OutputError`]: Propogated from [`std::io::Error`] in the `.` operation,
+    ///   or when flushing the output at the end
+    /// - [`crate::Error::MaxInstructionsExceeded`]: the set instructions limit was exceeded
+    /// - [`crate::Error::Cancelled`]: the set `cancel_token` was set to `true`
+    /// - [`crate::Error::TimedOut`]: the set timeout has elapsed
+    /// - [`crate::Error::InfiniteLoop`]: the program is stuck in an infinite loop, if they are being detected
+    /// - [`crate::Error::UndefinedProcedure`]: the program called a procedure that was never defined,
+    ///   in [`crate::Dialect::Pbrain`]
+    /// - [`crate::Error::CellOverflow`]: `+` or `-` would have wrapped the current cell around,
+    ///   while `wrapping` is disabled
+    pub async fn execute_async<R, W>(&mut self, input: &mut R, output: &mut W) -> Result<ExecutionInfo>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+
+            self.run_async(input, output)
+                .instrument(tracing::info_span!("execute", mode = "async"))
+                .await
+        }
+        #[cfg(not(feature = "tracing"))]
+        self.run_async(input, output).await
+    }
+
+    /// helper method driving the execution of [`Brainfuck::execute_async`]
+    async fn run_async<R, W>(&mut self, input: &mut R, output: &mut W) -> Result<ExecutionInfo>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let mut machine = self.begin()?;
+        // the amount of bytes written since the last flush
+        let mut pending = 0;
+
+        let result = loop {
+            let action = match self.advance(&mut machine) {
+                Ok(action) => action,
+                Err(err) => break Err(err),
+            };
+            match action {
+                Action::Continue => (),
+                Action::Output(_) if self.discard_output => (),
+                Action::Output(chr) => {
+                    let mut buf = [0; 4];
+                    let bytes = chr.encode_utf8(&mut buf).as_bytes();
+                    self.notify_output(bytes);
+
+                    let flush = self.flush_policy.should_flush(bytes, &mut pending);
+                    let written = async {
+                        output.write_all(bytes).await?;
+                        if flush {
+                            output.flush().await?;
+                        }
+                        Ok(())
+                    }
+                    .await;
+
+                    if let Err(err) = written {
+                        break Err(Self::output_failed(&machine, err));
+                    }
+                },
+                Action::Input => {
+                    let value = input.read_u8()
+                        .await
+                        .ok()
+                        .map(u32::from);
+                    let value = self.resolve_input(value, &machine);
+                    machine.write_input(value);
+                },
+                Action::Halt => break Ok(()),
+            }
+
+            if machine.steps.is_multiple_of(ASYNC_YIELD_INTERVAL) {
+                tokio::task::yield_now().await;
+            }
+        };
+        // the same as the synchronous `run`, the output written before a failure is still flushed,
+        // the error of the execution taking precedence over failing to flush it
+        let flushed = if pending == 0 {
+            Ok(())
+        } else {
+            output.flush().await
+        };
+        result?;
+        flushed.map_err(|err| Self::output_failed(&machine, err))?;
+
+        Ok(machine.into_info())
+    }
+}
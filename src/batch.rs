@@ -0,0 +1,95 @@
+//! module containing the batch execution API, running many programs across a [`rayon`] thread pool
+//!
+//! requires the `parallel` feature
+
+use std::{
+    io::{self, Cursor, Write},
+    sync::{Arc, Mutex, PoisonError},
+};
+use rayon::prelude::*;
+use crate::{Brainfuck, ExecutionInfo, Result};
+
+/// the outcome of a single program execution within a batch
+#[derive(Debug)]
+pub struct BatchResult {
+    /// everything the program wrote with the `.` operation
+    pub output: Vec<u8>,
+    /// the result of the execution
+    pub result: Result<ExecutionInfo>,
+}
+
+/// an output stream writing into a buffer that is shared with the caller
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// executes a single interpreter, capturing its output into a buffer
+fn execute_captured(interp: Brainfuck<'static>) -> BatchResult {
+    let buffer = SharedBuffer::default();
+    let result = interp
+        .with_output(buffer.clone())
+        .execute();
+
+    let output = std::mem::take(
+        &mut *buffer.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    );
+    BatchResult { output, result }
+}
+
+impl Brainfuck<'static> {
+    /// executes many programs in parallel across a [`rayon`] thread pool
+    ///
+    /// the output of each program is captured into [`BatchResult::output`]
+    /// instead of the output stream it was configured with;
+    /// the results are returned in the same order as `programs`
+    pub fn execute_batch<I>(programs: I) -> Vec<BatchResult>
+    where
+        I: IntoIterator<Item = Self>
+    {
+        programs
+            .into_iter()
+            .collect::<Vec<Self>>()
+            .into_par_iter()
+            .map(execute_captured)
+            .collect()
+    }
+}
+
+impl Brainfuck<'_> {
+    /// executes this program against many inputs in parallel across a [`rayon`] thread pool
+    ///
+    /// each run uses a copy of this interpreter's configuration (see [`Brainfuck::clone_config`])
+    /// reading from its input, and its output is captured into [`BatchResult::output`];
+    /// the results are returned in the same order as `inputs`
+    pub fn execute_inputs<I, B>(&self, inputs: I) -> Vec<BatchResult>
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u8]>,
+    {
+        inputs
+            .into_iter()
+            .map(|input| self
+                .clone_config()
+                .with_input(Cursor::new(input.as_ref().to_vec()))
+            )
+            .collect::<Vec<Brainfuck<'static>>>()
+            .into_par_iter()
+            .map(execute_captured)
+            .collect()
+    }
+}
@@ -0,0 +1,154 @@
+//! the `bench` subcommand, timing repeated executions of a program
+//!
+//! the baselines are JSON objects of the timings, written and read by this subcommand only
+
+use std::{io::Cursor, time::{Duration, Instant}};
+use serde::{Deserialize, Serialize};
+use crate::{read_file, report, Settings};
+
+#[derive(clap::Args, Debug)]
+pub struct BenchArgs {
+    /// the brainfuck source file to benchmark
+    file: String,
+    /// the amount of timed executions
+    #[arg(short = 'n', long, action, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+    /// the amount of untimed executions before the timed ones
+    #[arg(long, action, default_value_t = 3)]
+    warmup: u32,
+    /// the input of every execution, which is otherwise empty
+    #[arg(short = 'i', long, action)]
+    input: Option<String>,
+    /// writes the timings to the provided file as a baseline to compare later runs against
+    #[arg(long, action)]
+    save_baseline: Option<String>,
+    /// compares the timings against the baseline saved in the provided file
+    #[arg(long, action)]
+    baseline: Option<String>,
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// the timings of the executions of a program
+struct Timings {
+    min: Duration,
+    mean: Duration,
+    max: Duration,
+    /// the amount of instructions executed by every execution
+    instructions: usize,
+}
+
+impl Timings {
+    /// returns the amount of instructions executed per second on average
+    fn speed(&self) -> f64 {
+        self.instructions as f64 / self.mean.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// formats the timings as a baseline
+    fn to_json(&self, file: &str, runs: u32) -> String {
+        let baseline = Baseline {
+            file: file.to_string(),
+            runs,
+            min_ms: millis(self.min),
+            mean_ms: millis(self.mean),
+            max_ms: millis(self.max),
+            instructions: self.instructions,
+            instructions_per_second: self.speed(),
+        };
+        serde_json::to_string(&baseline).unwrap_or_default() + "\n"
+    }
+}
+
+/// the timings saved with `--save-baseline`
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    file: String,
+    runs: u32,
+    min_ms: f64,
+    mean_ms: f64,
+    max_ms: f64,
+    instructions: usize,
+    instructions_per_second: f64,
+}
+
+/// returns the provided duration in milliseconds
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// formats the change from the baseline to the current value in percent
+fn change(current: f64, baseline: f64) -> String {
+    if baseline == 0.0 {
+        return String::from("n/a");
+    }
+    format!("{:+.1}%", (current - baseline) / baseline * 100.0)
+}
+
+/// executes the program repeatedly, then prints its timings along with the comparison against the baseline, if any
+pub fn bench(args: &BenchArgs) {
+    let format = args.settings.error_format;
+    let interp = args.settings.apply(read_file(&args.file, format));
+    let input = args.input.clone().unwrap_or_default().into_bytes();
+
+    let execute = || {
+        let mut interp = interp
+            .clone_config()
+            .with_input(Cursor::new(input.clone()))
+            .with_no_output();
+        let start = Instant::now();
+        let info = interp
+            .execute()
+            .unwrap_or_else(|err| report::fail(&err, format, &args.file, Some(&interp.code)));
+
+        (start.elapsed(), info.instructions)
+    };
+
+    for _ in 0..args.warmup {
+        execute();
+    }
+    let (times, instructions) = (0..args.runs)
+        .map(|_| execute())
+        .unzip::<_, _, Vec<Duration>, Vec<usize>>();
+
+    let timings = Timings {
+        min: times.iter().min().copied().unwrap_or_default(),
+        mean: times.iter().sum::<Duration>() / args.runs,
+        max: times.iter().max().copied().unwrap_or_default(),
+        instructions: instructions.last().copied().unwrap_or_default(),
+    };
+
+    println!(
+        "runs:           {} ({} warmup)\nmin:            {:.3} ms\nmean:           {:.3} ms\nmax:            {:.3} ms\n\
+        instructions:   {}\ninstructions/s: {:.0}",
+        args.runs, args.warmup,
+        millis(timings.min),
+        millis(timings.mean),
+        millis(timings.max),
+        timings.instructions,
+        timings.speed(),
+    );
+
+    if let Some(ref path) = args.baseline {
+        let baseline = std::fs::read_to_string(path)
+            .unwrap_or_else(|err| report::fail_io("Failed to read the provided file", path, &err, format));
+
+        let Baseline { mean_ms: mean, instructions_per_second: speed, .. } = serde_json::from_str(&baseline)
+            .unwrap_or_else(|err| {
+                eprintln!("error: Invalid baseline: {path}: expected the timings saved with `--save-baseline`: {err}");
+                std::process::exit(report::USAGE);
+            });
+        println!(
+            "\ncompared to the baseline {path}:\nmean:           {mean:.3} ms -> {:.3} ms ({})\n\
+            instructions/s: {speed:.0} -> {:.0} ({})",
+            millis(timings.mean),
+            change(millis(timings.mean), mean),
+            timings.speed(),
+            change(timings.speed(), speed),
+        );
+    }
+    if let Some(ref path) = args.save_baseline {
+        if let Err(err) = std::fs::write(path, timings.to_json(&args.file, args.runs)) {
+            report::fail_io("Failed to write to the provided file", path, &err, format);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! the `--print-cells` and `--dump-memory` flags, printing the memory array on STDERR
+//! or writing it to a file once the program finishes
+
+use std::ops::Range;
+use clap::ValueEnum;
+use crate::report::{self, ErrorFormat};
+
+/// the amount of cells printed on every row
+const ROW: usize = 16;
+
+/// how the values of the cells are printed
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellsFormat {
+    /// as decimal numbers
+    Dec,
+    /// as hexadecimal numbers
+    Hex,
+    /// as characters, printing `.` for the unprintable ones
+    Ascii,
+}
+
+impl CellsFormat {
+    /// formats the provided cell value
+    fn format(self, cell: u32) -> String {
+        match self {
+            Self::Dec => cell.to_string(),
+            Self::Hex => format!("{cell:02x}"),
+            Self::Ascii => char::from_u32(cell)
+                .filter(|chr| !chr.is_control())
+                .unwrap_or('.')
+                .to_string(),
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct CellsArgs {
+    /// prints the memory array on STDERR once the program finishes,
+    /// 16 cells per row prefixed with the index of the first of them
+    #[arg(long, action, verbatim_doc_comment)]
+    print_cells: bool,
+    /// how the values of the cells are printed
+    #[arg(long, value_enum, default_value_t = CellsFormat::Dec, requires = "print_cells")]
+    cells_format: CellsFormat,
+    /// only prints the cells in the provided range, such as `0..64`, `16..` or `..8`
+    #[arg(long, value_parser = parse_range, requires = "print_cells")]
+    cells_range: Option<(usize, Option<usize>)>,
+    /// collapses the runs of rows only made of zeros into a single line
+    #[arg(long, action, requires = "print_cells")]
+    skip_zeros: bool,
+    /// writes the memory array to the provided file once the program finishes,
+    /// every cell as a little-endian integer of the smallest width of 1, 2 or 4 bytes that fits the max cell value
+    /// a pipeline writes the memory array of its last program
+    #[arg(long, action, verbatim_doc_comment)]
+    dump_memory: Option<String>,
+}
+
+/// parses a range of cell indices, either bound of which can be omitted
+fn parse_range(value: &str) -> Result<(usize, Option<usize>), String> {
+    let (start, end) = value
+        .split_once("..")
+        .ok_or_else(|| format!("`{value}` is not a range such as `0..64`"))?;
+    let bound = |bound: &str| bound
+        .trim()
+        .parse::<usize>()
+        .map_err(|err| format!("`{bound}` is not a cell index: {err}"));
+
+    let start = if start.trim().is_empty() { 0 } else { bound(start)? };
+    let end = if end.trim().is_empty() { None } else { Some(bound(end)?) };
+
+    if end.is_some_and(|end| end < start) {
+        return Err(format!("the range `{value}` ends before it starts"));
+    }
+    Ok((start, end))
+}
+
+impl CellsArgs {
+    /// prints the provided cells if enabled, with a header naming the program if provided
+    pub fn print(&self, cells: &[u32], name: Option<&str>) {
+        if !self.print_cells {
+            return;
+        }
+        let (start, end) = self.cells_range.unwrap_or((0, None));
+        let end = end.map_or(cells.len(), |end| end.min(cells.len()));
+        let range = start.min(end)..end;
+
+        if let Some(name) = name {
+            eprintln!("\n[{name}]");
+        } else {
+            eprintln!("\n");
+        }
+        eprint!("{}", self.format(cells, range));
+    }
+
+    /// writes the provided cells to the file passed with `--dump-memory`, if any,
+    /// each in the width fitting the provided max cell value
+    pub fn dump(&self, cells: &[u32], max_value: u32, format: ErrorFormat) {
+        let Some(ref path) = self.dump_memory else {
+            return;
+        };
+        let width = match max_value {
+            0..=0xFF => 1,
+            0x100..=0xFFFF => 2,
+            _ => 4,
+        };
+        let bytes = cells
+            .iter()
+            .flat_map(|cell| cell.to_le_bytes().into_iter().take(width))
+            .collect::<Vec<u8>>();
+
+        if let Err(err) = std::fs::write(path, bytes) {
+            report::fail_io("Failed to write to the provided file", path, &err, format);
+        }
+    }
+
+    /// formats the cells in the provided range into rows
+    fn format(&self, cells: &[u32], range: Range<usize>) -> String {
+        let values = cells[range.clone()]
+            .iter()
+            .map(|&cell| self.cells_format.format(cell))
+            .collect::<Vec<String>>();
+        let width = values
+            .iter()
+            .map(String::len)
+            .max()
+            .unwrap_or(0);
+        let index_width = range.end.saturating_sub(1).to_string().len();
+
+        let mut rows = String::new();
+        let mut skipped = 0;
+
+        for (row, chunk) in values.chunks(ROW).enumerate() {
+            let offset = range.start + row * ROW;
+
+            if self.skip_zeros && cells[offset..offset + chunk.len()].iter().all(|&cell| cell == 0) {
+                skipped += chunk.len();
+                continue;
+            }
+            if skipped > 0 {
+                rows += &format!("{:>index_width$}  ... {skipped} zero cells\n", "*");
+                skipped = 0;
+            }
+            rows += &format!(
+                "{offset:>index_width$}: {}\n",
+                chunk
+                    .iter()
+                    .map(|value| format!("{value:>width$}"))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+            );
+        }
+        if skipped > 0 {
+            rows += &format!("{:>index_width$}  ... {skipped} zero cells\n", "*");
+        }
+        rows
+    }
+}
@@ -0,0 +1,61 @@
+//! the `check` subcommand, validating programs without executing them
+
+use brainfuck_exe::{Dialect, Severity};
+use crate::{diagnostic, read_file, report, Settings};
+
+#[derive(clap::Args, Debug)]
+pub struct CheckArgs {
+    /// the brainfuck source files to check
+    #[arg(required = true)]
+    files: Vec<String>,
+    /// skips the lints, only reporting the problems that prevent the programs from being executed
+    /// and the loops that never terminate
+    #[arg(long, action, verbatim_doc_comment)]
+    no_lints: bool,
+    /// exits with a failure if any warning or lint is found, rather than only on errors
+    #[arg(long, action)]
+    deny_warnings: bool,
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// prints every problem found in the programs, exiting with [`report::SYNTAX`] if any of them cannot be executed,
+/// or with [`report::FAILURE`] if warnings are denied and there were any
+pub fn check(args: &CheckArgs) {
+    let format = args.settings.error_format;
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    for file in &args.files {
+        let interp = args.settings.apply(read_file(file, format));
+        if let Err(err) = interp.validate() {
+            report::fail(&err, format, file, None);
+        }
+
+        let mut diagnostics = interp.check();
+        // the lints only know of plain brainfuck, and would misread the instructions of the other dialects
+        if !args.no_lints && interp.dialect == Dialect::Brainfuck {
+            diagnostics.extend(interp.lint());
+            diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+        }
+
+        for diagnostic in &diagnostics {
+            println!("{}", diagnostic::render_diagnostic(diagnostic, file, &interp.code));
+
+            match diagnostic.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+            }
+        }
+    }
+
+    if errors + warnings > 0 {
+        println!("Found {errors} error(s) and {warnings} warning(s)");
+    }
+    if errors > 0 {
+        std::process::exit(report::SYNTAX);
+    }
+    if warnings > 0 && args.deny_warnings {
+        std::process::exit(report::FAILURE);
+    }
+}
@@ -0,0 +1,158 @@
+//! the configuration file, providing defaults for the interpreter options
+//!
+//! the options are read from the file passed with `--config`, or otherwise from the first of
+//! `brainfuck.toml` in the current directory and `brainfuck/config.toml` in the XDG config directory that exists
+
+use std::path::PathBuf;
+use clap::ValueEnum;
+use crate::{report, CellSize, Settings};
+
+/// the keys of the configuration file, named after their command-line flags
+const KEYS: [&str; 17] = [
+    "max-cell-value",
+    "cell-size",
+    "eof",
+    "no-wrap",
+    "memory-size",
+    "flush-output",
+    "instructions-limit",
+    "timeout",
+    "fallback-char",
+    "detect-infinite-loops",
+    "max-loop-iterations",
+    "strict",
+    "opt-level",
+    "dialect",
+    "random",
+    "seed",
+    "tokens",
+];
+
+/// returns the path of the configuration file to read, if any
+fn locate(settings: &Settings) -> Option<PathBuf> {
+    if settings.no_config {
+        return None;
+    }
+    if let Some(ref path) = settings.config {
+        return Some(PathBuf::from(path));
+    }
+    let local = PathBuf::from("brainfuck.toml");
+    if local.is_file() {
+        return Some(local);
+    }
+
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("brainfuck").join("config.toml"))
+        .filter(|path| path.is_file())
+}
+
+/// exits the process after reporting an invalid configuration file
+fn invalid(path: &str, message: &str) -> ! {
+    eprintln!("error: Invalid configuration file: {path}: {message}");
+    std::process::exit(report::USAGE)
+}
+
+/// the values of a configuration file, checked against the types of their options
+struct Config<'t> {
+    path: &'t str,
+    table: &'t toml::Table,
+}
+
+impl Config<'_> {
+    /// returns the integer of the provided key, if it is set
+    fn integer<T: TryFrom<i64>>(&self, key: &str) -> Option<T> {
+        self.table.get(key).map(|value| value
+            .as_integer()
+            .and_then(|value| T::try_from(value).ok())
+            .unwrap_or_else(|| invalid(self.path, &format!("`{key}` must be a non-negative integer in range")))
+        )
+    }
+
+    /// returns the boolean of the provided key, defaulting to `false`
+    fn boolean(&self, key: &str) -> bool {
+        self.table.get(key).is_some_and(|value| value
+            .as_bool()
+            .unwrap_or_else(|| invalid(self.path, &format!("`{key}` must be a boolean")))
+        )
+    }
+
+    /// returns the string of the provided key, if it is set
+    fn string(&self, key: &str) -> Option<&str> {
+        self.table.get(key).map(|value| value
+            .as_str()
+            .unwrap_or_else(|| invalid(self.path, &format!("`{key}` must be a string")))
+        )
+    }
+
+    /// returns the value of the provided key, parsed like its command-line flag, if it is set
+    fn value<T: ValueEnum>(&self, key: &str) -> Option<T> {
+        self.string(key).map(|value| T::from_str(value, true)
+            .unwrap_or_else(|_| invalid(self.path, &format!("unknown `{key}` value `{value}`")))
+        )
+    }
+}
+
+impl Settings {
+    /// fills in the options that were not passed on the command line from the configuration file, if any
+    ///
+    /// flags can only enable the boolean options, so those set in the file cannot be disabled on the command line
+    pub fn load_config(&mut self) {
+        if let Some(path) = locate(self) {
+            self.load_file(&path.to_string_lossy());
+        }
+    }
+
+    /// fills in the options that were not passed on the command line from the provided configuration file
+    pub fn load_file(&mut self, path: &str) {
+        let contents = std::fs::read_to_string(path)
+            .unwrap_or_else(|err| report::fail_io("Failed to read the configuration file", path, &err, self.error_format));
+        let table = contents
+            .parse::<toml::Table>()
+            .unwrap_or_else(|err| invalid(path, err.message()));
+
+        if let Some(key) = table
+            .keys()
+            .find(|key| !KEYS.contains(&key.as_str()))
+        {
+            invalid(path, &format!("unknown key `{key}`"));
+        }
+        let config = Config { path, table: &table };
+
+        // the max value and the cell size passed on the command line take precedence over both of them in the file
+        if self.cell_size.is_none() {
+            self.max_cell_value = self.max_cell_value.or_else(|| config.integer("max-cell-value"));
+        }
+        self.memory_size = self.memory_size.or_else(|| config.integer("memory-size"));
+        self.instructions_limit = self.instructions_limit.or_else(|| config.integer("instructions-limit"));
+        self.timeout = self.timeout.or_else(|| config.integer("timeout"));
+        self.max_loop_iterations = self.max_loop_iterations.or_else(|| config.integer("max-loop-iterations"));
+        self.opt_level = self.opt_level.or_else(|| config.integer::<u8>("opt-level").inspect(|&level| if level > 2 {
+            invalid(path, "`opt-level` must be 0, 1 or 2");
+        }));
+        self.seed = self.seed.or_else(|| config.integer::<u64>("seed"));
+        self.fallback_char = self.fallback_char.or_else(|| config.string("fallback-char").map(|chr| {
+            let mut chars = chr.chars();
+            match (chars.next(), chars.next()) {
+                (Some(chr), None) => chr,
+                _ => invalid(path, "`fallback-char` must be a single character"),
+            }
+        }));
+        self.tokens = self.tokens.take().or_else(|| config.string("tokens").map(String::from));
+        self.dialect = self.dialect.or_else(|| config.value("dialect"));
+        self.eof = self.eof.or_else(|| config.value("eof"));
+        if self.max_cell_value.is_none() {
+            self.cell_size = self.cell_size.or_else(|| config.integer::<i64>("cell-size").map(|size| {
+                CellSize::from_str(&size.to_string(), false)
+                    .unwrap_or_else(|_| invalid(path, "`cell-size` must be 8, 16 or 32"))
+            }));
+        }
+
+        self.flush_output |= config.boolean("flush-output");
+        self.detect_infinite_loops |= config.boolean("detect-infinite-loops");
+        self.strict |= config.boolean("strict");
+        self.random |= config.boolean("random");
+        self.no_wrap |= config.boolean("no-wrap");
+    }
+}
@@ -0,0 +1,56 @@
+//! the `corpus` subcommand, executing every program of a directory in parallel under the same limits
+
+use brainfuck_exe::Brainfuck;
+use crate::{diagnostic, report, Settings};
+
+#[derive(clap::Args, Debug)]
+pub struct CorpusArgs {
+    /// the directory to look for `*.bf` and `*.b` programs in, recursively
+    #[arg(default_value = ".")]
+    dir: String,
+    /// the amount of programs executed at once, defaults to the available parallelism
+    #[arg(short = 'j', long, action, default_value_t = 0)]
+    jobs: usize,
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// executes every program found with an empty input, printing the ones that failed or reached a limit
+/// and a summary, exiting with [`report::FAILURE`] if any of them did
+pub fn corpus(args: &CorpusArgs) {
+    let format = args.settings.error_format;
+    let report = args.settings
+        .apply(Brainfuck::new(""))
+        .execute_corpus(&args.dir, args.jobs)
+        .unwrap_or_else(|err| report::fail(&err, format, &args.dir, None));
+
+    for entry in report.limited().chain(report.failed()) {
+        let Err(ref err) = entry.result else {
+            continue;
+        };
+        let name = entry.path.to_string_lossy();
+        let code = std::fs::read_to_string(&entry.path).unwrap_or_default();
+
+        println!(
+            "{} {} [{} ms]\n{}",
+            if entry.is_limited() { "LIMIT" } else { "FAIL" },
+            entry.path.display(),
+            entry.elapsed.as_millis(),
+            diagnostic::render(err, &name, &code).trim_end(),
+        );
+    }
+
+    let (finished, limited, failed) = (
+        report.finished().count(),
+        report.limited().count(),
+        report.failed().count(),
+    );
+    println!(
+        "{} program(s): {finished} finished, {limited} reached a limit, {failed} failed in [{} ms]",
+        report.entries.len(),
+        report.elapsed.as_millis(),
+    );
+    if limited + failed > 0 {
+        std::process::exit(report::FAILURE);
+    }
+}
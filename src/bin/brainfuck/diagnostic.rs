@@ -0,0 +1,125 @@
+//! rendering of errors as diagnostics pointing into the source code
+
+use std::io::IsTerminal;
+use brainfuck_exe::{Diagnostic, Error, Position, Span};
+use crate::describe;
+
+/// the ANSI escape codes used to color the diagnostics
+struct Palette {
+    /// the color of the `error` label and the caret
+    error: &'static str,
+    /// the color of the gutter and line numbers
+    gutter: &'static str,
+    /// resets the color
+    reset: &'static str,
+}
+
+impl Palette {
+    /// returns the palette to use, colorless unless the provided stream is a terminal and `NO_COLOR` is not set
+    fn detect(stream: &impl IsTerminal) -> Self {
+        if stream.is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+            Self { error: "\x1b[1;31m", gutter: "\x1b[1;34m", reset: "\x1b[0m" }
+        } else {
+            Self { error: "", gutter: "", reset: "" }
+        }
+    }
+}
+
+/// renders the provided error, pointing at the offending line of `code` if it has a position
+///
+/// `name` is the name of the source, such as the file the code was read from;
+/// the rendered error is meant for STDERR
+pub fn render(err: &Error, name: &str, code: &str) -> String {
+    let palette = Palette::detect(&std::io::stderr());
+    let mut rendered = format!("{}error{}: {}\n", palette.error, palette.reset, describe(err));
+
+    if let Error::LoopIterationsExceeded { span, .. } = err {
+        rendered += &snippet(&palette, name, code, span.start, width(code, *span));
+    } else if let Some(position) = err.position() {
+        rendered += &snippet(&palette, name, code, position, 1);
+    }
+    rendered
+}
+
+/// renders the provided diagnostic, pointing at the spanned code along with its suggested fix, if any
+pub fn render_diagnostic(diagnostic: &Diagnostic, name: &str, code: &str) -> String {
+    let palette = Palette::detect(&std::io::stdout());
+    let lint = diagnostic.lint
+        .map(|lint| format!(" [{lint}]"))
+        .unwrap_or_default();
+    let mut rendered = format!(
+        "{}{}{}: {}{lint}\n", palette.error, diagnostic.severity, palette.reset, diagnostic.message,
+    );
+
+    rendered += &snippet(&palette, name, code, diagnostic.span.start, width(code, diagnostic.span));
+
+    match diagnostic.suggestion.as_deref() {
+        Some("") => rendered += "  = help: remove this code\n",
+        Some(suggestion) => rendered += &format!("  = help: replace this code with `{suggestion}`\n"),
+        None => (),
+    }
+    rendered
+}
+
+/// returns the amount of characters of the provided span to underline,
+/// the rest of its first line if it spans several lines of `code`
+fn width(code: &str, Span { start, end }: Span) -> usize {
+    let last = if start.line == end.line {
+        end.column
+    } else {
+        code.split('\n')
+            .nth(start.line - 1)
+            .map_or(0, |line| line.trim_end_matches('\r').chars().count() + 1)
+    };
+    last.saturating_sub(start.column).max(1)
+}
+
+/// renders the line of `code` at the provided position, underlining `width` characters from it
+fn snippet(palette: &Palette, name: &str, code: &str, position: Position, width: usize) -> String {
+    let Palette { error, gutter, reset } = palette;
+    let line = code
+        .split('\n')
+        .nth(position.line - 1)
+        .unwrap_or_default()
+        .trim_end_matches('\r');
+    let number = position.line.to_string();
+    let padding = " ".repeat(number.len());
+
+    // keeps the carets aligned with lines indented with tabs
+    let indent = line
+        .chars()
+        .take(position.column - 1)
+        .map(|chr| if chr == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    let carets = "^".repeat(width);
+
+    format!(
+        "{padding}{gutter}-->{reset} {name}:{position}\n\
+        {padding} {gutter}|{reset}\n\
+        {gutter}{number} |{reset} {line}\n\
+        {padding} {gutter}|{reset} {indent}{error}{carets}{reset}\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use brainfuck_exe::Brainfuck;
+    use super::render;
+
+    #[test]
+    fn test_render_loop_span() {
+        let code = "+++[>+<-]\n+[\n-]";
+
+        let err = Brainfuck::new(code)
+            .with_max_loop_iterations(2)
+            .execute()
+            .unwrap_err();
+        assert!(render(&err, "loop.bf", code).ends_with("1 | +++[>+<-]\n  |    ^^^^^^\n"));
+
+        let err = Brainfuck::new(&code[10..])
+            .with_max_loop_iterations(0)
+            .execute()
+            .unwrap_err();
+        assert!(render(&err, "loop.bf", &code[10..]).ends_with("1 | +[\n  |  ^\n"));
+    }
+}
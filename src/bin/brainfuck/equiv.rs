@@ -0,0 +1,77 @@
+//! the `equiv` subcommand, checking that two programs behave the same over a set of inputs
+
+use brainfuck_exe::equivalence::{fuzz_inputs, DivergenceKind};
+use crate::{read_file, report, Settings};
+
+#[derive(clap::Args, Debug)]
+pub struct EquivArgs {
+    /// the brainfuck source file of the first program
+    left: String,
+    /// the brainfuck source file of the second program
+    right: String,
+    /// an input to execute both programs with, can be passed multiple times
+    #[arg(short = 'i', long = "input", action)]
+    inputs: Vec<String>,
+    /// the amount of random inputs to execute both programs with as well, the first of which is empty
+    #[arg(long, action, default_value_t = 64)]
+    fuzz: usize,
+    /// the max length of the random inputs
+    #[arg(long, action, default_value_t = 16)]
+    fuzz_len: usize,
+    /// the seed of the random inputs
+    #[arg(long, action, default_value_t = 0)]
+    fuzz_seed: u64,
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// formats the provided cells separated by spaces
+fn cells(cells: &[u32]) -> String {
+    cells
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// executes both programs with every input under the same options, printing how they diverged first,
+/// exiting with [`report::FAILURE`] if they did
+pub fn equiv(args: &EquivArgs) {
+    let format = args.settings.error_format;
+    let left = args.settings.apply(read_file(&args.left, format));
+    let right = read_file(&args.right, format).code;
+
+    let inputs = args.inputs
+        .iter()
+        .map(|input| input.clone().into_bytes())
+        .chain(fuzz_inputs(args.fuzz, args.fuzz_len, args.fuzz_seed));
+    let checked = left.check_equivalence(&right, inputs);
+
+    let Some(divergence) = checked.divergence else {
+        println!(
+            "The programs behaved the same with {} input(s), {} of which were inconclusive as they reached a limit",
+            checked.compared + checked.inconclusive, checked.inconclusive,
+        );
+        return;
+    };
+    println!("The programs diverged with the input {:?}", String::from_utf8_lossy(&divergence.input));
+
+    match divergence.kind {
+        DivergenceKind::Output { offset, left: left_output, right: right_output } => println!(
+            "the outputs differ at byte offset {offset}\n{}: {:?}\n{}: {:?}",
+            args.left, String::from_utf8_lossy(&left_output),
+            args.right, String::from_utf8_lossy(&right_output),
+        ),
+        DivergenceKind::Result { left: left_error, right: right_error } => println!(
+            "only one of the programs failed, or they failed differently\n{}: {}\n{}: {}",
+            args.left, left_error.as_deref().unwrap_or("finished"),
+            args.right, right_error.as_deref().unwrap_or("finished"),
+        ),
+        DivergenceKind::Tape { index, left: left_cells, right: right_cells } => println!(
+            "the memory arrays differ at cell {index}\n{}: {}\n{}: {}",
+            args.left, cells(&left_cells),
+            args.right, cells(&right_cells),
+        ),
+    }
+    std::process::exit(report::FAILURE);
+}
@@ -0,0 +1,51 @@
+//! the `--expect` flags, comparing the output of the program against the expected output
+
+use crate::{report, Args};
+
+/// returns the expected output, passed directly or read from the file passed with `--expect-file`
+pub fn expected(args: &Args) -> Option<Vec<u8>> {
+    if let Some(ref expected) = args.expect {
+        return Some(expected.clone().into_bytes());
+    }
+    args.expect_file
+        .as_ref()
+        .map(|path| std::fs::read(path)
+            .unwrap_or_else(|err| report::fail_io("Failed to read the provided file", path, &err, args.settings.error_format))
+        )
+}
+
+/// returns a diff of the lines of the expected and actual outputs that differ, or [`None`] if they are equal
+pub fn diff(expected: &[u8], actual: &[u8]) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    let offset = expected
+        .iter()
+        .zip(actual)
+        .position(|(expected, actual)| expected != actual)
+        .unwrap_or_else(|| expected.len().min(actual.len()));
+
+    let expected = String::from_utf8_lossy(expected);
+    let actual = String::from_utf8_lossy(actual);
+    let expected_lines = expected.split('\n').collect::<Vec<&str>>();
+    let actual_lines = actual.split('\n').collect::<Vec<&str>>();
+
+    let mut diff = format!(
+        "error: the output differs from the expected output, starting at byte offset {offset}\n\
+        --- expected\n\
+        +++ actual\n",
+    );
+    for idx in 0..expected_lines.len().max(actual_lines.len()) {
+        let (expected, actual) = (expected_lines.get(idx), actual_lines.get(idx));
+
+        if expected != actual {
+            if let Some(line) = expected {
+                diff += &format!("-{:>4} | {line:?}\n", idx + 1);
+            }
+            if let Some(line) = actual {
+                diff += &format!("+{:>4} | {line:?}\n", idx + 1);
+            }
+        }
+    }
+    Some(diff)
+}
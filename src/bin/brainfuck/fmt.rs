@@ -0,0 +1,49 @@
+//! the `fmt` subcommand, formatting a program in place
+
+use brainfuck_exe::{format_source, FormatOptions};
+use crate::{read_file, report};
+
+#[derive(clap::Args, Debug)]
+pub struct FmtArgs {
+    /// the brainfuck source file to format
+    file: String,
+    /// the amount of spaces to indent the body of a loop with
+    #[arg(long, default_value_t = 4)]
+    indent: usize,
+    /// the maximum width of a line
+    #[arg(long, default_value_t = 80)]
+    width: usize,
+    /// removes the comments instead of keeping them
+    #[arg(long, action)]
+    strip_comments: bool,
+    /// only checks whether or not the file is formatted, without writing to it
+    /// exits with 1 if it is not
+    #[arg(long, action, verbatim_doc_comment)]
+    check: bool,
+    /// the format errors are reported in on STDERR
+    #[arg(long, value_enum, default_value_t)]
+    error_format: report::ErrorFormat,
+}
+
+/// formats the file, or checks whether or not it is formatted
+pub fn fmt(args: &FmtArgs) {
+    let code = read_file(&args.file, args.error_format).code;
+    let options = FormatOptions::new()
+        .with_indent(args.indent)
+        .with_width(args.width)
+        .with_comments(!args.strip_comments);
+
+    let formatted = format_source(&code, options)
+        .unwrap_or_else(|err| report::fail(&err, args.error_format, &args.file, Some(&code)));
+
+    if formatted == code {
+        return;
+    }
+    if args.check {
+        println!("{} is not formatted", args.file);
+        std::process::exit(report::FAILURE);
+    }
+    if let Err(err) = std::fs::write(&args.file, formatted) {
+        report::fail_io("Failed to write the formatted code to the provided file", &args.file, &err, args.error_format);
+    }
+}
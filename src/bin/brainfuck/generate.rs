@@ -0,0 +1,29 @@
+//! the `generate` subcommand, emitting a program printing the provided text
+
+use brainfuck_exe::codegen;
+use crate::report;
+
+#[derive(clap::Args, Debug)]
+pub struct GenerateArgs {
+    /// the text for the program to print
+    text: String,
+    /// specifies a file to write the program to instead of STDOUT
+    #[arg(short = 'o', long, action)]
+    output: Option<String>,
+    /// the format errors are reported in on STDERR
+    #[arg(long, value_enum, default_value_t)]
+    error_format: report::ErrorFormat,
+}
+
+/// generates the program, writing it to the output
+pub fn generate(args: &GenerateArgs) {
+    let code = codegen::generate(&args.text);
+
+    if let Some(ref path) = args.output {
+        if let Err(err) = std::fs::write(path, code) {
+            report::fail_io("Failed to write the generated code to the provided file", path, &err, args.error_format);
+        }
+    } else {
+        println!("{code}");
+    }
+}
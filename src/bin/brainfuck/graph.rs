@@ -0,0 +1,32 @@
+//! the `graph` subcommand, exporting the control-flow graph of a program
+
+use brainfuck_exe::ast::Program;
+use crate::{read_file, report};
+
+#[derive(clap::Args, Debug)]
+pub struct GraphArgs {
+    /// the brainfuck source file to graph
+    file: String,
+    /// specifies a file to write the graph to instead of STDOUT
+    #[arg(short = 'o', long, action)]
+    output: Option<String>,
+    /// the format errors are reported in on STDERR
+    #[arg(long, value_enum, default_value_t)]
+    error_format: report::ErrorFormat,
+}
+
+/// renders the control-flow graph of the program as Graphviz DOT, writing it to the output
+pub fn graph(args: &GraphArgs) {
+    let code = read_file(&args.file, args.error_format).code;
+    let dot = Program::parse(&code)
+        .unwrap_or_else(|err| report::fail(&err, args.error_format, &args.file, Some(&code)))
+        .to_dot();
+
+    if let Some(ref path) = args.output {
+        if let Err(err) = std::fs::write(path, dot) {
+            report::fail_io("Failed to write the graph to the provided file", path, &err, args.error_format);
+        }
+    } else {
+        print!("{dot}");
+    }
+}
@@ -0,0 +1,64 @@
+//! the `html` subcommand, exporting a program as syntax-highlighted HTML
+
+use std::io::Cursor;
+use brainfuck_exe::{render_html, Brainfuck, HtmlOptions, Profiler};
+use crate::{read_file, report, Settings};
+
+#[derive(clap::Args, Debug)]
+pub struct HtmlArgs {
+    /// the brainfuck source file to export
+    file: String,
+    /// specifies a file to write the HTML to instead of STDOUT
+    #[arg(short = 'o', long, action)]
+    output: Option<String>,
+    /// executes the program first, annotating every loop with how often it iterated
+    #[arg(long, action)]
+    profile: bool,
+    /// manually enters the inputs (used in `,`) for the profiled execution instead of STDIN
+    #[arg(short = 'i', long, action, requires = "profile")]
+    input: Option<String>,
+    /// renders only the `<pre>` element for embedding, without the document and its stylesheet
+    #[arg(long, action)]
+    fragment: bool,
+    /// the title of the document, defaults to the name of the file
+    #[arg(long, action, conflicts_with = "fragment")]
+    title: Option<String>,
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// renders the program as HTML, optionally profiling it first, writing it to the output
+pub fn html(args: &HtmlArgs) {
+    let format = args.settings.error_format;
+    let code = read_file(&args.file, format).code;
+    let mut options = HtmlOptions::new()
+        .with_standalone(!args.fragment)
+        .with_title(args.title.as_ref().unwrap_or(&args.file));
+
+    if args.profile {
+        let mut profiler = Profiler::new(&code);
+        let mut interp = args.settings.apply(Brainfuck::new(&code));
+
+        if let Some(ref input) = args.input {
+            interp = interp.with_input(Cursor::new(input.clone().into_bytes()));
+        }
+        // the output of the program is discarded, so that only the HTML is written to STDOUT
+        if let Err(e) = interp
+            .with_output(std::io::sink())
+            .with_observer(&mut profiler)
+            .execute()
+        {
+            report::fail(&e, format, &args.file, Some(&code));
+        }
+        options = options.with_profile(&profiler);
+    }
+    let html = render_html(&code, &options);
+
+    if let Some(ref path) = args.output {
+        if let Err(err) = std::fs::write(path, html) {
+            report::fail_io("Failed to write the HTML to the provided file", path, &err, format);
+        }
+    } else {
+        print!("{html}");
+    }
+}
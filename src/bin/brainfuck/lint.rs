@@ -0,0 +1,25 @@
+//! the `lint` subcommand, reporting common smells in a program
+
+use crate::{diagnostic, read_file, report, Settings};
+
+#[derive(clap::Args, Debug)]
+pub struct LintArgs {
+    /// the brainfuck source file to lint
+    file: String,
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// prints every lint found in the program, exiting with [`report::FAILURE`] if there were any
+pub fn lint(args: &LintArgs) {
+    let interp = args.settings.apply(read_file(&args.file, args.settings.error_format));
+    let lints = interp.lint();
+
+    for lint in &lints {
+        println!("{}", diagnostic::render_diagnostic(lint, &args.file, &interp.code));
+    }
+    if !lints.is_empty() {
+        println!("Found {} lint(s)", lints.len());
+        std::process::exit(report::FAILURE);
+    }
+}
@@ -0,0 +1,418 @@
+//! the `lsp` subcommand, a language server speaking the Language Server Protocol over STDIN and STDOUT
+//!
+//! it publishes the diagnostics of `check` and `lint`, highlights the bracket matching the one at the cursor,
+//! describes the effect of a single iteration of the loop hovered over, and formats documents with `fmt`
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, BufRead, Write},
+};
+use brainfuck_exe::{format_source, Brainfuck, Diagnostic, FormatOptions, Position, Severity};
+use serde_json::{json, Value};
+use crate::report;
+
+#[derive(clap::Args, Debug)]
+pub struct LspArgs {
+    /// communicates over STDIN and STDOUT, which is the only transport supported,
+    /// accepted as editors commonly pass it
+    #[arg(long, action, verbatim_doc_comment)]
+    stdio: bool,
+}
+
+/// the error code of a request for a method the server does not implement
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// reads the next message, returning [`None`] once the client closed the stream
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut body = vec![0; length.unwrap_or_default()];
+    input.read_exact(&mut body)?;
+    // a message that cannot be parsed is skipped, as there is no id to respond to
+    Ok(Some(serde_json::from_slice(&body).unwrap_or(Value::Null)))
+}
+
+/// writes the provided message with its header
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+/// returns the line of the provided index of the code and its character within the line counted in UTF-16,
+/// both starting at `0`
+fn locate(code: &[char], idx: usize) -> (usize, usize) {
+    let line = code[..idx]
+        .iter()
+        .filter(|&&chr| chr == '\n')
+        .count();
+    let line_start = code[..idx]
+        .iter()
+        .rposition(|&chr| chr == '\n')
+        .map_or(0, |newline| newline + 1);
+    let character = code[line_start..idx]
+        .iter()
+        .map(|chr| chr.len_utf16())
+        .sum();
+
+    (line, character)
+}
+
+/// returns the position of the provided index of the code in the protocol
+fn to_protocol(code: &[char], idx: usize) -> Value {
+    let (line, character) = locate(code, idx);
+
+    json!({ "line": line, "character": character })
+}
+
+/// returns the provided value as an unsigned integer, if it is one
+fn as_usize(value: &Value) -> Option<usize> {
+    value
+        .as_u64()
+        .and_then(|number| usize::try_from(number).ok())
+}
+
+/// returns the index of the code at the provided position in the protocol, clamped to the end of its line
+fn from_protocol(code: &[char], position: &Value) -> Option<usize> {
+    let line = as_usize(position.get("line")?)?;
+    let character = as_usize(position.get("character")?)?;
+
+    let mut idx = if line == 0 {
+        0
+    } else {
+        code.iter()
+            .enumerate()
+            .filter(|&(_, &chr)| chr == '\n')
+            .nth(line - 1)?
+            .0 + 1
+    };
+    let mut units = 0;
+    while units < character && code.get(idx).is_some_and(|&chr| chr != '\n') {
+        units += code[idx].len_utf16();
+        idx += 1;
+    }
+    Some(idx)
+}
+
+/// returns the range of the provided indices of the code in the protocol, `end` being exclusive
+fn range(code: &[char], start: usize, end: usize) -> Value {
+    json!({ "start": to_protocol(code, start), "end": to_protocol(code, end) })
+}
+
+/// converts a diagnostic of `check` or `lint` into the protocol
+fn diagnostic(code: &[char], diagnostic: &Diagnostic) -> Value {
+    let index = |position: Position| code
+        .iter()
+        .scan(0, |offset, chr| {
+            let idx = *offset;
+            *offset += chr.len_utf8();
+            Some(idx)
+        })
+        .position(|offset| offset >= position.offset)
+        .unwrap_or(code.len());
+
+    let mut converted = json!({
+        "range": range(code, index(diagnostic.span.start), index(diagnostic.span.end)),
+        "severity": match diagnostic.severity {
+            Severity::Error => 1,
+            Severity::Warning => 2,
+        },
+        "source": "brainfuck",
+        "message": diagnostic.message,
+    });
+    // the code is omitted rather than `null` for the errors of `check`, which are not lints
+    if let Some(lint) = diagnostic.lint {
+        converted["code"] = json!(lint);
+    }
+    converted
+}
+
+/// returns the index of the bracket matching every bracket of the code, unmatched brackets having none
+fn match_brackets(code: &[char]) -> HashMap<usize, usize> {
+    let mut matching = HashMap::new();
+    let mut open = Vec::new();
+
+    for (idx, &chr) in code.iter().enumerate() {
+        match chr {
+            '[' => open.push(idx),
+            ']' => if let Some(start) = open.pop() {
+                matching.insert(start, idx);
+                matching.insert(idx, start);
+            },
+            _ => (),
+        }
+    }
+    matching
+}
+
+/// the effect of a single iteration of a loop, relative to the cell the iteration starts at
+#[derive(Default)]
+struct Effect {
+    /// the net movement of the pointer, or [`None`] if a nested loop moves it by an amount that depends on the data
+    shift: Option<isize>,
+    /// the net change of every cell changed outside of the nested loops, keyed by its offset from the starting cell
+    cells: BTreeMap<isize, i64>,
+    /// whether or not the loop contains nested loops, whose changes depend on how often they iterate
+    nested: bool,
+}
+
+/// computes the effect of the body of the loop spanning the provided brackets
+fn loop_effect(code: &[char], matching: &HashMap<usize, usize>, start: usize, end: usize) -> Effect {
+    let mut effect = Effect { shift: Some(0), ..Effect::default() };
+    let mut pointer = 0;
+    let mut idx = start + 1;
+
+    while idx < end {
+        match code[idx] {
+            '+' => *effect.cells.entry(pointer).or_default() += 1,
+            '-' => *effect.cells.entry(pointer).or_default() -= 1,
+            '>' => pointer += 1,
+            '<' => pointer -= 1,
+            '[' => {
+                let close = matching[&idx];
+                effect.nested = true;
+
+                if loop_effect(code, matching, idx, close).shift != Some(0) {
+                    effect.shift = None;
+                    break;
+                }
+                idx = close;
+            },
+            _ => (),
+        }
+        idx += 1;
+    }
+    if effect.shift.is_some() {
+        effect.shift = Some(pointer);
+    }
+    effect.cells.retain(|_, delta| *delta != 0);
+    effect
+}
+
+/// describes the effect of a single iteration of the innermost loop containing the provided index, as Markdown
+fn hover(code: &[char], idx: usize) -> Option<Value> {
+    let matching = match_brackets(code);
+    let (start, end) = matching
+        .iter()
+        .filter(|&(&open, &close)| code[open] == '[' && open <= idx && idx <= close)
+        .min_by_key(|&(&open, &close)| close - open)
+        .map(|(&open, &close)| (open, close))?;
+
+    let effect = loop_effect(code, &matching, start, end);
+    let (line, character) = locate(code, start);
+    let pointer = match effect.shift {
+        Some(0) => String::from("balanced, back on the same cell"),
+        Some(shift) => format!("moves by `{shift:+}`"),
+        None => String::from("moves by an amount depending on the data, through a nested loop"),
+    };
+    let cells = if effect.cells.is_empty() {
+        String::from("none")
+    } else {
+        effect.cells
+            .iter()
+            .map(|(offset, delta)| format!("`p{offset:+}: {delta:+}`"))
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    let mut contents = format!(
+        "**loop** at {}:{}, a single iteration:\n\n- pointer: {pointer}\n- cells changed: {cells}",
+        line + 1,
+        character + 1,
+    );
+    if effect.nested {
+        contents += "\n- the nested loops change cells depending on how often they iterate";
+    }
+    Some(json!({
+        "contents": { "kind": "markdown", "value": contents },
+        "range": range(code, start, end + 1),
+    }))
+}
+
+/// returns the bracket at or right before the provided index, and the bracket matching it
+fn highlight(code: &[char], idx: usize) -> Value {
+    let matching = match_brackets(code);
+    let bracket = [Some(idx), idx.checked_sub(1)]
+        .into_iter()
+        .flatten()
+        .find(|idx| matching.contains_key(idx));
+
+    Value::Array(bracket.map_or_else(Vec::new, |bracket| [bracket, matching[&bracket]]
+        .into_iter()
+        .map(|idx| json!({ "range": range(code, idx, idx + 1), "kind": 1 }))
+        .collect()
+    ))
+}
+
+/// formats the whole document, returning the edit replacing it, or [`None`] if its brackets are mismatched
+fn format(code: &[char], options: Option<&Value>) -> Option<Value> {
+    let indent = options
+        .and_then(|options| options.get("tabSize"))
+        .and_then(as_usize)
+        .unwrap_or(4);
+    let source = code.iter().collect::<String>();
+    let formatted = format_source(&source, FormatOptions::new().with_indent(indent)).ok()?;
+
+    Some(json!([{ "range": range(code, 0, code.len()), "newText": formatted }]))
+}
+
+/// returns the diagnostics of the provided document, as a notification publishing them
+fn publish(uri: &str, code: &[char]) -> Value {
+    let interp = Brainfuck::new(code.iter().collect::<String>());
+    let mut diagnostics = interp.check();
+    diagnostics.extend(interp.lint());
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": {
+            "uri": uri,
+            "diagnostics": diagnostics
+                .iter()
+                .map(|found| diagnostic(code, found))
+                .collect::<Vec<Value>>(),
+        },
+    })
+}
+
+/// the state of the server, the open documents being kept split into their characters
+#[derive(Default)]
+struct Server {
+    documents: HashMap<String, Vec<char>>,
+    shutdown: bool,
+}
+
+impl Server {
+    /// returns the document of the request, along with the index of its position if it has one
+    fn document(&self, params: &Value) -> Option<(&[char], Option<usize>)> {
+        let uri = params.get("textDocument")?.get("uri")?.as_str()?;
+        let code = self.documents.get(uri)?;
+        let idx = params
+            .get("position")
+            .and_then(|position| from_protocol(code, position));
+
+        Some((code, idx))
+    }
+
+    /// handles a request, returning its result, or [`None`] if the method is not implemented
+    fn request(&mut self, method: &str, params: &Value) -> Option<Value> {
+        Some(match method {
+            "initialize" => json!({
+                "capabilities": {
+                    // the whole document is sent on every change
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "documentHighlightProvider": true,
+                    "documentFormattingProvider": true,
+                },
+                "serverInfo": {
+                    "name": "brainfuck",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }),
+            "shutdown" => {
+                self.shutdown = true;
+                Value::Null
+            },
+            "textDocument/hover" => self
+                .document(params)
+                .and_then(|(code, idx)| hover(code, idx?))
+                .unwrap_or(Value::Null),
+            "textDocument/documentHighlight" => self
+                .document(params)
+                .and_then(|(code, idx)| Some(highlight(code, idx?)))
+                .unwrap_or(Value::Null),
+            "textDocument/formatting" => self
+                .document(params)
+                .and_then(|(code, _)| format(code, params.get("options")))
+                .unwrap_or(Value::Null),
+            _ => return None,
+        })
+    }
+
+    /// handles a notification, returning the diagnostics to publish, if any
+    fn notification(&mut self, method: &str, params: &Value) -> Option<Value> {
+        let document = params.get("textDocument")?;
+        let uri = document.get("uri")?.as_str()?;
+
+        let text = match method {
+            "textDocument/didOpen" => document.get("text")?.as_str()?,
+            "textDocument/didChange" => params
+                .get("contentChanges")?
+                .as_array()?
+                .last()?
+                .get("text")?
+                .as_str()?,
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                return Some(publish(uri, &[]));
+            },
+            _ => return None,
+        };
+        let code = self.documents
+            .entry(uri.to_string())
+            .or_default();
+        *code = text.chars().collect();
+
+        Some(publish(uri, code))
+    }
+}
+
+/// serves a single client over STDIN and STDOUT until it exits
+pub fn lsp(_: &LspArgs) {
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout().lock();
+    let mut server = Server::default();
+
+    while let Some(message) = read_message(&mut input)
+        .unwrap_or_else(|err| report::fail_io("Failed to read a message", "STDIN", &err, report::ErrorFormat::default()))
+    {
+        let method = message
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let params = message
+            .get("params")
+            .unwrap_or(&Value::Null);
+
+        let reply = match message.get("id") {
+            Some(id) => Some(match server.request(method, params) {
+                Some(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                None => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": METHOD_NOT_FOUND,
+                        "message": format!("`{method}` is not supported"),
+                    },
+                }),
+            }),
+            None if method == "exit" => std::process::exit(if server.shutdown { 0 } else { report::FAILURE }),
+            None => server.notification(method, params),
+        };
+
+        if let Some(reply) = reply {
+            if let Err(err) = write_message(&mut output, &reply) {
+                report::fail_io("Failed to write a message", "STDOUT", &err, report::ErrorFormat::default());
+            }
+        }
+    }
+}
@@ -0,0 +1,870 @@
+//! CLI implementation for the brainfuck interpreter
+//! powered by [`clap`]
+//!
+//! requires the `cli` feature which is enabled by default
+
+use std::{
+    fs::File,
+    io::{Cursor, Read},
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use brainfuck_exe::{
+    translate::TokenTable,
+    Brainfuck,
+    Dialect,
+    DEFAULT_MAX_CELL_VALUE,
+    EofBehavior,
+    ExecutionInfo,
+    OpenMode,
+    OptimizationLevel,
+    Pipeline,
+};
+
+mod cells;
+mod bench;
+mod check;
+mod config;
+mod corpus;
+mod diagnostic;
+mod equiv;
+mod expect;
+mod fmt;
+mod generate;
+mod graph;
+mod html;
+mod lint;
+mod lsp;
+mod minify;
+mod obfuscate;
+mod optimize;
+mod profile;
+mod repl;
+mod report;
+mod serve;
+#[cfg(feature = "server")]
+mod serve_http;
+mod stats;
+mod test;
+mod trace;
+mod translate;
+mod visualize;
+mod watch;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "Brainfuck-exe",
+    author,
+    version,
+    about,
+    arg_required_else_help = true,
+    args_conflicts_with_subcommands = true,
+    after_help = report::EXIT_CODES,
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// The code of the brainfuck program
+    /// this argument is required unless [-f] [--file] is specified (file)
+    #[arg(value_parser, verbatim_doc_comment)]
+    code: Option<String>,
+    /// specifies a file to use for the brainfuck program instead
+    /// specifying it multiple times chains the programs into a pipeline,
+    /// where the output of each program is the input of the next
+    #[arg(short = 'f', long = "file", verbatim_doc_comment)]
+    file: Vec<String>,
+    /// manually enters the inputs (used in `,`) for the brainfuck program instead of STDIN
+    #[arg(short = 'i', long, action)]
+    input: Option<String>,
+    /// manually enters the inputs as comma separated byte values instead,
+    /// each in decimal, or in hexadecimal, octal or binary with a `0x`, `0o` or `0b` prefix
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = parse_byte,
+        conflicts_with = "input",
+        verbatim_doc_comment,
+    )]
+    input_bytes: Option<Vec<u8>>,
+    /// specifies a file to read the inputs from instead
+    #[arg(short = 'I', long, action, conflicts_with_all = ["input", "input_bytes"])]
+    input_file: Option<String>,
+    /// specifies a file to write the program output to instead of STDOUT
+    /// its parent directories are created if they do not exist
+    #[arg(short = 'o', long, action, verbatim_doc_comment)]
+    output: Option<String>,
+    /// appends the program output to the end of the output file instead of overwriting it
+    #[arg(long, action, requires = "output")]
+    append: bool,
+    /// fails instead of overwriting the output file if it already exists
+    #[arg(long, action, requires = "output", conflicts_with = "append")]
+    no_clobber: bool,
+    /// compares the program output against the provided output once the program finishes,
+    /// exiting with a failure and printing the lines that differ on mismatch
+    #[arg(long, action, verbatim_doc_comment)]
+    expect: Option<String>,
+    /// compares the program output against the contents of the provided file instead
+    #[arg(long, action, conflicts_with = "expect")]
+    expect_file: Option<String>,
+    /// exits with the value of the current cell once the program finishes, clamped to 255,
+    /// the last program of a pipeline being the one exiting
+    #[arg(long, action, verbatim_doc_comment)]
+    exit_cell: bool,
+    /// specifies whether or not to prompt the STDIN once at the beginning for all the input data
+    /// or instead get 1 character every time it is needed
+    #[arg(long, action, verbatim_doc_comment)]
+    prompt_stdin_once: bool,
+    /// specifies whether or not to print the program execution information on STDERR
+    /// such as the instructions-count, pointer and non-zero cells
+    #[arg(long, action, verbatim_doc_comment)]
+    print_info: bool,
+    /// prints the program execution information as a JSON object on the last line of STDERR,
+    /// along with the cells if [--print-info] is specified
+    /// a pipeline prints an array of the objects of its programs instead
+    #[arg(long, action, verbatim_doc_comment)]
+    json: bool,
+    /// prints a summary of the execution on STDERR once the program finishes,
+    /// such as the instructions executed per second and the amount of bytes written
+    #[arg(long, action, verbatim_doc_comment)]
+    stats: bool,
+    /// does not print the execution time on STDERR once the program finishes,
+    /// leaving only the errors and the explicitly requested information
+    #[arg(short = 'q', long, action, verbatim_doc_comment)]
+    quiet: bool,
+    /// emits the provided output instead of executing the program
+    #[arg(long, value_enum)]
+    emit: Option<optimize::Emit>,
+    /// executes the program again every time one of its files or the input file is modified,
+    /// clearing the screen in between the executions
+    #[arg(long, action, requires = "file", verbatim_doc_comment)]
+    watch: bool,
+    #[command(flatten)]
+    trace: trace::TraceArgs,
+    #[command(flatten)]
+    visualize: visualize::VisualizeArgs,
+    #[command(flatten)]
+    cells: cells::CellsArgs,
+    #[command(flatten)]
+    settings: Settings,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// accepts TCP connections, wiring each socket to the `,` and `.` streams
+    /// of a fresh interpreter executing the provided program
+    #[command(verbatim_doc_comment)]
+    Serve(serve::ServeArgs),
+    /// executes the provided program, then reports its hottest loops
+    /// by the amount of instructions executed directly within them
+    #[command(verbatim_doc_comment)]
+    Profile(profile::ProfileArgs),
+    /// reports common smells in the provided program, such as empty loops
+    /// and instructions cancelling each other out, along with suggested fixes
+    #[command(verbatim_doc_comment)]
+    Lint(lint::LintArgs),
+    /// validates the provided programs without executing them, reporting mismatched brackets,
+    /// unexpected characters with [--strict], endless loops and lints
+    #[command(verbatim_doc_comment)]
+    Check(check::CheckArgs),
+    /// formats the provided program in place, indenting loops by their depth
+    /// and wrapping long lines
+    #[command(verbatim_doc_comment)]
+    Fmt(fmt::FmtArgs),
+    /// strips the comments of the provided program and shrinks it
+    /// into the smallest equivalent program that can be found
+    #[command(verbatim_doc_comment)]
+    Minify(minify::MinifyArgs),
+    /// exports the control-flow graph of the provided program
+    /// in the Graphviz DOT format
+    #[command(verbatim_doc_comment)]
+    Graph(graph::GraphArgs),
+    /// exports the provided program as syntax-highlighted HTML, its loops colored by their depth,
+    /// optionally annotated with how often every loop iterated when executing it
+    #[command(verbatim_doc_comment)]
+    Html(html::HtmlArgs),
+    /// translates the provided program between brainfuck and the languages derived from it,
+    /// such as Ook!
+    #[command(verbatim_doc_comment)]
+    Translate(translate::TranslateArgs),
+    /// executes every line entered against a memory array and pointer kept across the lines,
+    /// along with meta-commands such as `:cells`, see `:help`
+    #[command(verbatim_doc_comment)]
+    Repl(repl::ReplArgs),
+    /// executes every `*.bf` program of the provided directory against its sibling `.in` file,
+    /// comparing the output against its sibling `.out` file and the options of its sibling `.toml` file
+    #[command(verbatim_doc_comment)]
+    Test(test::TestArgs),
+    /// executes the provided program repeatedly after warming up, reporting its min, mean and max time,
+    /// optionally comparing them against a baseline saved by a previous run
+    #[command(verbatim_doc_comment)]
+    Bench(bench::BenchArgs),
+    /// executes every `*.bf` and `*.b` program of the provided directory in parallel with an empty input,
+    /// reporting the ones that failed or reached the instruction limit or the timeout
+    #[command(verbatim_doc_comment)]
+    Corpus(corpus::CorpusArgs),
+    /// emits a short program printing the provided text,
+    /// built with multiplication loops from the previous character printed
+    #[command(verbatim_doc_comment)]
+    Generate(generate::GenerateArgs),
+    /// emits an obfuscated version of the provided program, only once executing both programs
+    /// with every sample input produced the same output
+    #[command(verbatim_doc_comment)]
+    Obfuscate(obfuscate::ObfuscateArgs),
+    /// optimizes the provided program, aggressively unless another [--opt-level] is passed,
+    /// emitting it as plain brainfuck that any other interpreter can execute
+    #[command(verbatim_doc_comment)]
+    Optimize(optimize::OptimizeArgs),
+    /// summarizes the provided program without executing it, such as the amount of every instruction,
+    /// the depth of its loops, the share of comments and how many cells it can use
+    #[command(verbatim_doc_comment)]
+    Stats(stats::StatsArgs),
+    /// executes both provided programs with the same options over the provided and random inputs,
+    /// reporting the first input their output, failure or final memory array differed on
+    #[command(verbatim_doc_comment)]
+    Equiv(equiv::EquivArgs),
+    /// serves a language server over STDIN and STDOUT, publishing the diagnostics of `check` and `lint`,
+    /// highlighting matching brackets, describing the hovered loop and formatting documents
+    #[command(verbatim_doc_comment)]
+    Lsp(lsp::LspArgs),
+    /// serves a JSON API executing the programs posted to `POST /run` within the limits of the server,
+    /// responding with their output and execution information, for web playgrounds
+    #[cfg(feature = "server")]
+    #[command(verbatim_doc_comment)]
+    ServeHttp(serve_http::ServeHttpArgs),
+}
+
+impl Args {
+    /// returns the input stream passed with `--input`, `--input-bytes` or `--input-file`, if any,
+    /// exiting the process if the file cannot be opened
+    fn input_stream(&self) -> Option<Box<dyn Read + Send>> {
+        if let Some(ref path) = self.input_file {
+            let file = File::open(path)
+                .unwrap_or_else(|err| report::fail_io("Failed to open the provided file", path, &err, self.settings.error_format));
+            return Some(Box::new(file));
+        }
+        self.input
+            .as_ref()
+            .map(|input| input.clone().into_bytes())
+            .or_else(|| self.input_bytes.clone())
+            .map(|bytes| Box::new(Cursor::new(bytes)) as Box<dyn Read + Send>)
+    }
+
+    /// returns the interpreter options of the command to run, if it takes any
+    fn settings_mut(&mut self) -> Option<&mut Settings> {
+        match self.command {
+            Some(Command::Serve(ref mut args)) => Some(&mut args.settings),
+            Some(Command::Profile(ref mut args)) => Some(&mut args.settings),
+            Some(Command::Html(ref mut args)) => Some(&mut args.settings),
+            Some(Command::Lint(ref mut args)) => Some(&mut args.settings),
+            Some(Command::Check(ref mut args)) => Some(&mut args.settings),
+            Some(Command::Repl(ref mut args)) => Some(&mut args.settings),
+            Some(Command::Test(ref mut args)) => Some(&mut args.settings),
+            Some(Command::Bench(ref mut args)) => Some(&mut args.settings),
+            Some(Command::Corpus(ref mut args)) => Some(&mut args.settings),
+            Some(Command::Optimize(ref mut args)) => Some(&mut args.settings),
+            Some(Command::Equiv(ref mut args)) => Some(&mut args.settings),
+            Some(
+                Command::Fmt(_)
+                | Command::Minify(_)
+                | Command::Graph(_)
+                | Command::Translate(_)
+                | Command::Generate(_)
+                | Command::Obfuscate(_)
+                | Command::Stats(_)
+                | Command::Lsp(_)
+            ) => None,
+            #[cfg(feature = "server")]
+            Some(Command::ServeHttp(_)) => None,
+            None => Some(&mut self.settings),
+        }
+    }
+}
+
+/// an extension of brainfuck the program can be written in
+#[derive(ValueEnum, Debug, Clone, Copy, Default)]
+enum DialectArg {
+    /// plain brainfuck
+    #[default]
+    Brainfuck,
+    /// pbrain, adding procedures with `(`, `)` and `:`
+    Pbrain,
+    /// Brainfork, adding threads forked with `Y`
+    Brainfork,
+    /// Extended Brainfuck Type I, adding `@` to end the program, a storage cell and bitwise operations
+    ExtendedTypeI,
+    /// Boolfuck, where every cell is a single bit, written with `;`
+    Boolfuck,
+}
+
+impl From<DialectArg> for Dialect {
+    fn from(dialect: DialectArg) -> Self {
+        match dialect {
+            DialectArg::Brainfuck => Self::Brainfuck,
+            DialectArg::Pbrain => Self::Pbrain,
+            DialectArg::Brainfork => Self::Brainfork,
+            DialectArg::ExtendedTypeI => Self::ExtendedTypeI,
+            DialectArg::Boolfuck => Self::Boolfuck,
+        }
+    }
+}
+
+/// the size of a cell in bits
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum CellSize {
+    /// cells from 0 to 255
+    #[value(name = "8")]
+    Bits8,
+    /// cells from 0 to 65535
+    #[value(name = "16")]
+    Bits16,
+    /// cells from 0 to 4294967295
+    #[value(name = "32")]
+    Bits32,
+}
+
+impl CellSize {
+    /// returns the max value of a cell of this size
+    const fn max_value(self) -> u32 {
+        match self {
+            Self::Bits8 => u8::MAX as u32,
+            Self::Bits16 => u16::MAX as u32,
+            Self::Bits32 => u32::MAX,
+        }
+    }
+}
+
+/// what `,` sets the current cell to once the input reached EOF
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum EofArg {
+    /// `0`, or the fallback character if one is specified
+    #[value(name = "0")]
+    Zero,
+    /// the max value of a cell, which is `-1` in interpreters with signed cells
+    Max,
+    /// leaves the cell unchanged
+    Unchanged,
+}
+
+impl From<EofArg> for EofBehavior {
+    fn from(eof: EofArg) -> Self {
+        match eof {
+            EofArg::Zero => Self::Fallback,
+            EofArg::Max => Self::Max,
+            EofArg::Unchanged => Self::Unchanged,
+        }
+    }
+}
+
+/// the interpreter options shared by the different commands
+#[derive(clap::Args, Debug, Clone)]
+struct Settings {
+    /// specifies the maximum value a cell can have
+    /// defaults to 255 (8 bits / 1 byte)
+    #[arg(long, action, verbatim_doc_comment)]
+    max_cell_value: Option<u32>,
+    /// specifies the size of a cell in bits, an alternative to [--max-cell-value]
+    #[arg(long, value_enum, conflicts_with = "max_cell_value")]
+    cell_size: Option<CellSize>,
+    /// specifies what `,` sets the current cell to once the input reached EOF
+    /// defaults to 0
+    #[arg(long, value_enum, verbatim_doc_comment)]
+    eof: Option<EofArg>,
+    /// aborts the program once `+` or `-` would wrap the current cell around
+    /// instead of wrapping it around
+    #[arg(long, action, verbatim_doc_comment)]
+    no_wrap: bool,
+    /// specifies a set size for the memory array of the brainfuck program
+    /// if not set, the array is growable and has no set size
+    #[arg(long, action, verbatim_doc_comment)]
+    memory_size: Option<usize>,
+    /// specifies whether or not to manually flush the output buffer every write
+    /// if not set it is flushed at every newline and at the end of the program
+    #[arg(long, action, verbatim_doc_comment)]
+    flush_output: bool,
+    /// specifies the limit on the amount of instructions we can process in one program
+    /// if not provided, there will be no limit
+    #[arg(long, action, verbatim_doc_comment)]
+    instructions_limit: Option<usize>,
+    /// specifies the limit on the wall-clock time (in milliseconds) the program can execute for
+    /// if not provided, there will be no limit
+    #[arg(long, action, verbatim_doc_comment)]
+    timeout: Option<u64>,
+    /// the fallback character for EOF in the input stream
+    #[arg(long, action)]
+    fallback_char: Option<char>,
+    /// aborts the program once it reaches the exact same state twice without any I/O in between,
+    /// which means it is stuck in an infinite loop
+    #[arg(long, action, verbatim_doc_comment)]
+    detect_infinite_loops: bool,
+    /// aborts the program once a loop iterates more than this amount of times every time it is entered,
+    /// pointing out the loop that is stuck rather than only the instruction the program stopped at
+    #[arg(long, action, verbatim_doc_comment)]
+    max_loop_iterations: Option<usize>,
+    /// rejects any character in the code that is neither an instruction nor whitespace
+    /// instead of treating it as a comment
+    #[arg(long, action, verbatim_doc_comment)]
+    strict: bool,
+    /// specifies how aggressively to optimize the program before executing it
+    /// 0: not at all, 1: eliminates dead code,
+    /// 2: also evaluates the start of the program that does not depend on any input at compile time
+    /// defaults to 0, and only applies along with [--memory-size]
+    #[arg(
+        short = 'O',
+        long,
+        value_parser = clap::value_parser!(u8).range(0..=2),
+        verbatim_doc_comment,
+    )]
+    opt_level: Option<u8>,
+    /// the extension of brainfuck the program is written in
+    /// defaults to brainfuck
+    #[arg(long, value_enum, verbatim_doc_comment)]
+    dialect: Option<DialectArg>,
+    /// makes `?` an instruction setting the current cell to a random value
+    #[arg(long, action)]
+    random: bool,
+    /// seeds the random values of `?`, making them the same on every execution
+    /// implies [--random]
+    #[arg(long, action, verbatim_doc_comment)]
+    seed: Option<u64>,
+    /// specifies a TOML file mapping every instruction to its token in the substitution dialect
+    /// the program is written in, such as `increment = "a"`, translating it into brainfuck
+    #[arg(long, action, verbatim_doc_comment)]
+    tokens: Option<String>,
+    /// the format errors are reported in on STDERR
+    #[arg(long, value_enum, default_value_t)]
+    error_format: report::ErrorFormat,
+    /// specifies a TOML file providing the defaults of the options above, named after their flags
+    /// such as `max-cell-value = 65535`, instead of `brainfuck.toml` in the current directory
+    /// or `brainfuck/config.toml` in the XDG config directory
+    #[arg(long, action, verbatim_doc_comment)]
+    config: Option<String>,
+    /// ignores the configuration file
+    #[arg(long, action, conflicts_with = "config")]
+    no_config: bool,
+}
+
+impl Settings {
+    /// returns the max value of the cells passed with `--max-cell-value` or `--cell-size`, if any
+    fn max_value(&self) -> Option<u32> {
+        self.max_cell_value.or_else(|| self.cell_size.map(CellSize::max_value))
+    }
+
+    /// applies the settings onto the provided interpreter
+    fn apply<'a>(&self, mut interp: Brainfuck<'a>) -> Brainfuck<'a> {
+        interp = interp
+            .with_flush(self.flush_output)
+            .with_strict_syntax(self.strict)
+            .with_infinite_loop_detection(self.detect_infinite_loops)
+            .with_wrapping(!self.no_wrap)
+            .with_eof(self.eof.map_or(EofBehavior::Fallback, EofBehavior::from))
+            .with_dialect(self.dialect.unwrap_or_default().into())
+            .with_optimization(match self.opt_level.unwrap_or_default() {
+                0 => OptimizationLevel::None,
+                1 => OptimizationLevel::Basic,
+                _ => OptimizationLevel::Aggressive,
+            });
+
+        if let Some(value) = self.max_value() {
+            interp = interp.with_max_value(value);
+        }
+        if let Some(size) = self.memory_size {
+            interp = interp.with_mem_size(size);
+        }
+        if let Some(limit) = self.instructions_limit {
+            interp = interp.with_instructions_limit(limit);
+        }
+        if let Some(timeout) = self.timeout {
+            interp = interp.with_timeout(Duration::from_millis(timeout));
+        }
+        if let Some(limit) = self.max_loop_iterations {
+            interp = interp.with_max_loop_iterations(limit);
+        }
+        if let Some(chr) = self.fallback_char {
+            interp = interp.with_fallback_input(chr);
+        }
+        if let Some(seed) = self.seed {
+            interp = interp.with_random_seed(seed);
+        } else if self.random {
+            interp = interp.with_random();
+        }
+        if let Some(ref path) = self.tokens {
+            let toml = std::fs::read_to_string(path)
+                .unwrap_or_else(|err| report::fail_io("Failed to read the provided file", path, &err, self.error_format));
+            let table = TokenTable::from_toml(&toml)
+                .unwrap_or_else(|err| report::fail(&err, self.error_format, path, None));
+
+            interp = interp.with_token_table(&table);
+        }
+        interp
+    }
+}
+
+/// parses a byte value passed to `--input-bytes`
+fn parse_byte(value: &str) -> Result<u8, String> {
+    let value = value.trim();
+    let (digits, radix) = match value.get(..2).map(str::to_ascii_lowercase).as_deref() {
+        Some("0x") => (&value[2..], 16),
+        Some("0o") => (&value[2..], 8),
+        Some("0b") => (&value[2..], 2),
+        _ => (value, 10),
+    };
+
+    u8::from_str_radix(digits, radix)
+        .map_err(|err| format!("`{value}` is not a byte value from 0 to 255: {err}"))
+}
+
+/// formats the provided error along with every one of its sources
+fn describe(err: &dyn std::error::Error) -> String {
+    let mut description = err.to_string();
+    let mut source = err.source();
+
+    while let Some(err) = source {
+        description = format!("{description}: {err}");
+        source = err.source();
+    }
+    description
+}
+
+/// returns the provided execution information as a JSON object, along with the cells if `cells` is `true`
+///
+/// the duration is in milliseconds, and is `null` if the execution was not benched
+fn info_json(info: &ExecutionInfo, cells: bool) -> serde_json::Value {
+    let mut json = serde_json::json!({
+        "instructions": info.instructions,
+        "duration_ms": info.time.map(|time| (time.as_secs_f64() * 1_000_000.0).round() / 1000.0),
+        "pointer": info.pointer,
+        "memory_size": info.mem_size,
+        "code_length": info.code_len,
+    });
+    if cells {
+        json["cells"] = serde_json::json!(info.cells);
+    }
+    json
+}
+
+/// formats a human readable summary of the provided execution, along with the amount of bytes it wrote
+#[allow(clippy::cast_precision_loss)]
+fn stats(info: &ExecutionInfo, written: usize) -> String {
+    let (elapsed, speed) = info.time
+        .filter(|time| !time.is_zero())
+        .map_or_else(
+            || (String::from("n/a"), String::from("n/a")),
+            |time| (
+                format!("{:.3} ms", time.as_secs_f64() * 1000.0),
+                format!("{:.0}", info.instructions as f64 / time.as_secs_f64()),
+            ),
+        );
+
+    format!(
+        "instructions:   {}\nelapsed:        {elapsed}\ninstructions/s: {speed}\ncells used:     {}\nbytes written:  {written}",
+        info.instructions,
+        info.mem_size,
+    )
+}
+
+/// the output written by a program, recorded through its output hook
+#[derive(Debug, Default)]
+struct Recorded {
+    /// the amount of bytes written
+    bytes: usize,
+    /// the bytes written, if they are captured
+    captured: Option<Vec<u8>>,
+}
+
+/// records the output of the provided interpreter if either the statistics are printed or `capture` is `true`,
+/// in which case the bytes written are captured as well
+fn record_output(interp: Brainfuck<'_>, stats: bool, capture: bool) -> (Brainfuck<'_>, Arc<Mutex<Recorded>>) {
+    let recorded = Arc::new(Mutex::new(Recorded {
+        bytes: 0,
+        captured: capture.then(Vec::new),
+    }));
+
+    if stats || capture {
+        let recorder = Arc::clone(&recorded);
+        let interp = interp.on_output(move |byte| {
+            let mut recorded = recorder
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            recorded.bytes += 1;
+            if let Some(ref mut captured) = recorded.captured {
+                captured.push(byte);
+            }
+        });
+        (interp, recorded)
+    } else {
+        (interp, recorded)
+    }
+}
+
+/// exits with [`report::FAILURE`] after printing the diff if the recorded output is not the expected output
+fn check_expected(expected: Option<&[u8]>, recorded: &Mutex<Recorded>) {
+    let recorded = recorded
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    if let (Some(expected), Some(actual)) = (expected, recorded.captured.as_deref()) {
+        if let Some(diff) = expect::diff(expected, actual) {
+            eprint!("\n\n{diff}");
+            std::process::exit(report::FAILURE);
+        }
+    }
+}
+
+/// opens the provided output file, creating its parent directories, exiting the process on failure
+///
+/// the file is truncated unless `--append` or `--no-clobber` is specified
+fn open_output(args: &Args, path: &str) -> File {
+    let format = args.settings.error_format;
+
+    if let Some(parent) = std::path::Path::new(path)
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        if let Err(err) = std::fs::create_dir_all(parent) {
+            report::fail_io("Failed to create the directories of the provided file", path, &err, format);
+        }
+    }
+
+    let mode = if args.append {
+        OpenMode::Append
+    } else if args.no_clobber {
+        OpenMode::CreateNew
+    } else {
+        OpenMode::Truncate
+    };
+
+    mode
+        .options()
+        .open(path)
+        .unwrap_or_else(|err| if err.kind() == std::io::ErrorKind::AlreadyExists {
+            report::fail_io("Refusing to overwrite the provided file", path, &err, format)
+        } else {
+            report::fail_io("Failed to open the provided file", path, &err, format)
+        })
+}
+
+/// exits with the value of the current cell of the provided execution, clamped to 255, if `--exit-cell` is specified
+fn exit_with_cell(args: &Args, info: &ExecutionInfo) {
+    if args.exit_cell {
+        let cell = info.cells
+            .get(info.pointer)
+            .copied()
+            .unwrap_or_default();
+        std::process::exit(i32::from(u8::try_from(cell).unwrap_or(u8::MAX)));
+    }
+}
+
+/// reads the brainfuck code from the provided file, exiting the process on failure
+fn read_file(file: &str, format: report::ErrorFormat) -> Brainfuck<'static> {
+    Brainfuck::from_file(file)
+        .unwrap_or_else(|err| report::fail(&err, format, file, None))
+}
+
+fn main() {
+    let mut args = Args::parse();
+    if let Some(settings) = args.settings_mut() {
+        settings.load_config();
+    }
+
+    match args.command {
+        Some(Command::Serve(serve_args)) => serve::serve(&serve_args),
+        Some(Command::Profile(profile_args)) => profile::profile(&profile_args),
+        Some(Command::Lint(lint_args)) => lint::lint(&lint_args),
+        Some(Command::Check(check_args)) => check::check(&check_args),
+        Some(Command::Fmt(fmt_args)) => fmt::fmt(&fmt_args),
+        Some(Command::Minify(minify_args)) => minify::minify(&minify_args),
+        Some(Command::Graph(graph_args)) => graph::graph(&graph_args),
+        Some(Command::Html(html_args)) => html::html(&html_args),
+        Some(Command::Translate(translate_args)) => translate::translate(&translate_args),
+        Some(Command::Repl(repl_args)) => repl::repl(&repl_args),
+        Some(Command::Test(test_args)) => test::test(&test_args),
+        Some(Command::Bench(bench_args)) => bench::bench(&bench_args),
+        Some(Command::Corpus(corpus_args)) => corpus::corpus(&corpus_args),
+        Some(Command::Generate(generate_args)) => generate::generate(&generate_args),
+        Some(Command::Obfuscate(obfuscate_args)) => obfuscate::obfuscate(&obfuscate_args),
+        Some(Command::Optimize(optimize_args)) => optimize::optimize(&optimize_args),
+        Some(Command::Stats(stats_args)) => stats::stats(&stats_args),
+        Some(Command::Equiv(equiv_args)) => equiv::equiv(&equiv_args),
+        Some(Command::Lsp(lsp_args)) => lsp::lsp(&lsp_args),
+        #[cfg(feature = "server")]
+        Some(Command::ServeHttp(serve_http_args)) => serve_http::serve_http(&serve_http_args),
+        None => run(args),
+    }
+}
+
+/// executes the brainfuck program provided through the top-level arguments
+#[allow(clippy::option_if_let_else, clippy::needless_pass_by_value)]
+fn run(args: Args) {
+    if args.watch {
+        watch::watch(&args.file
+            .iter()
+            .chain(&args.input_file)
+            .cloned()
+            .collect::<Vec<String>>());
+    }
+    if args.code.is_none() && args.file.len() > 1 {
+        if let Err(err) = check_pipeline(&args) {
+            err.exit();
+        }
+        return run_pipeline(&args);
+    }
+
+    let format = args.settings.error_format;
+    let name = args.file
+        .first()
+        .map_or_else(|| String::from("<code>"), String::clone);
+
+    let mut interp =
+        if let Some(ref code) = args.code {
+            Brainfuck::new(code)
+        } else if let Some(file) = args.file.first() {
+            read_file(file, format)
+        } else {
+            let mut cmd = Args::command();
+            if cmd.print_long_help().is_err() {
+                eprintln!("Something went wrong when printing the output.");
+                std::process::exit(report::IO);
+            }
+            std::process::exit(0);
+        }
+        .prompt_stdin_once(args.prompt_stdin_once);
+    interp = args.visualize.apply(args.trace.apply(args.settings.apply(interp), None));
+    let expected = expect::expected(&args);
+    let (mut interp, recorded) = record_output(interp, args.stats, expected.is_some());
+
+    if let Some(emit) = args.emit {
+        return optimize::emit(&interp, emit, &name, format);
+    }
+
+    if let Some(ref path) = args.output {
+        interp = interp.with_output(open_output(&args, path));
+    }
+
+    if let Some(input) = args.input_stream() {
+        interp = interp.with_input(input);
+    }
+
+    match interp.execute() {
+        Ok(info) => {
+            if args.stats {
+                eprintln!("\n\n{}", stats(&info, recorded.lock().unwrap_or_else(PoisonError::into_inner).bytes));
+            }
+            check_expected(expected.as_deref(), &recorded);
+            if args.json {
+                eprintln!("\n{}", info_json(&info, args.print_info));
+            } else if args.print_info {
+                eprintln!("\n\n{info}");
+            } else if !args.stats && !args.quiet {
+                eprintln!("\n\nFinished in [{} ms]", info.time
+                    .map_or(0, |t| t.as_millis())
+                );
+            }
+            args.cells.print(&info.cells, None);
+            args.cells.dump(&info.cells, interp.max_cell_value, format);
+            exit_with_cell(&args, &info);
+        },
+        Err(e) => report::fail(&e, format, &name, Some(&interp.code)),
+    }
+}
+
+/// rejects the options that cannot apply to a pipeline, as its programs are executed concurrently
+fn check_pipeline(args: &Args) -> Result<(), clap::Error> {
+    if args.visualize.enabled() {
+        return Err(Args::command().error(
+            clap::error::ErrorKind::ArgumentConflict,
+            "[--visualize] cannot be used with a pipeline of multiple files, \
+            as the drawings of its programs would overwrite one another",
+        ));
+    }
+    Ok(())
+}
+
+/// executes the programs of the provided files as a [`Pipeline`]
+fn run_pipeline(args: &Args) {
+    let format = args.settings.error_format;
+    let mut output = args.output
+        .as_ref()
+        .map(|path| open_output(args, path));
+
+    let last = args.file.len() - 1;
+    let mut pipeline = Pipeline::new();
+    let expected = expect::expected(args);
+    let mut recorded = Vec::new();
+
+    for (idx, file) in args.file.iter().enumerate() {
+        let stage = args.trace.apply(args.settings.apply(read_file(file, format)), Some(file));
+        // only the output of the last program is compared against the expected output
+        let (mut stage, recorder) = record_output(stage, args.stats, idx == last && expected.is_some());
+        recorded.push(recorder);
+
+        if idx == last {
+            if let Some(output) = output.take() {
+                stage = stage.with_output(output);
+            }
+        }
+        pipeline = pipeline.then(stage);
+    }
+
+    let input = args
+        .input_stream()
+        .unwrap_or_else(|| Box::new(std::io::stdin()));
+
+    match pipeline.execute(input) {
+        Ok(infos) => {
+            if args.stats {
+                for ((file, info), recorded) in args.file.iter().zip(&infos).zip(&recorded) {
+                    eprintln!("\n[{file}]\n{}", stats(info, recorded.lock().unwrap_or_else(PoisonError::into_inner).bytes));
+                }
+            }
+            if let Some(last) = recorded.last() {
+                check_expected(expected.as_deref(), last);
+            }
+            if args.json {
+                eprintln!("\n{}", infos
+                    .iter()
+                    .map(|info| info_json(info, args.print_info))
+                    .collect::<serde_json::Value>()
+                );
+            } else if args.print_info {
+                for (file, info) in args.file.iter().zip(&infos) {
+                    eprintln!("\n[{file}]\n{info}");
+                }
+            } else if !args.stats && !args.quiet {
+                eprintln!("\n\nFinished in [{} ms]", infos
+                    .iter()
+                    .filter_map(|info| info.time)
+                    .max()
+                    .map_or(0, |t| t.as_millis())
+                );
+            }
+            for (file, info) in args.file.iter().zip(&infos) {
+                args.cells.print(&info.cells, Some(file));
+            }
+            if let Some(info) = infos.last() {
+                let max_value = args.settings.max_value().unwrap_or(DEFAULT_MAX_CELL_VALUE);
+                args.cells.dump(&info.cells, max_value, format);
+            }
+            if let Some(info) = infos.last() {
+                exit_with_cell(args, info);
+            }
+        },
+        Err(e) => report::fail(&e, format, "<pipeline>", None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use super::{check_pipeline, Args};
+
+    #[test]
+    fn test_check_pipeline() {
+        let args = Args::parse_from(["brainfuck", "-f", "a.bf", "-f", "b.bf", "--trace"]);
+        assert!(check_pipeline(&args).is_ok());
+
+        let args = Args::parse_from(["brainfuck", "-f", "a.bf", "-f", "b.bf", "--visualize"]);
+        let err = check_pipeline(&args).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+}
@@ -0,0 +1,31 @@
+//! the `minify` subcommand, emitting the smallest equivalent program
+
+use brainfuck_exe::minify_source;
+use crate::{read_file, report};
+
+#[derive(clap::Args, Debug)]
+pub struct MinifyArgs {
+    /// the brainfuck source file to minify
+    file: String,
+    /// specifies a file to write the minified program to instead of STDOUT
+    #[arg(short = 'o', long, action)]
+    output: Option<String>,
+    /// the format errors are reported in on STDERR
+    #[arg(long, value_enum, default_value_t)]
+    error_format: report::ErrorFormat,
+}
+
+/// minifies the program, writing it to the output
+pub fn minify(args: &MinifyArgs) {
+    let code = read_file(&args.file, args.error_format).code;
+    let minified = minify_source(&code)
+        .unwrap_or_else(|err| report::fail(&err, args.error_format, &args.file, Some(&code)));
+
+    if let Some(ref path) = args.output {
+        if let Err(err) = std::fs::write(path, minified) {
+            report::fail_io("Failed to write the minified code to the provided file", path, &err, args.error_format);
+        }
+    } else {
+        println!("{minified}");
+    }
+}
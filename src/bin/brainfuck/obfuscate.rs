@@ -0,0 +1,73 @@
+//! the `obfuscate` subcommand, emitting an obfuscated program verified to behave like the original
+
+use std::io::Cursor;
+use brainfuck_exe::{codegen, Brainfuck, Error};
+use crate::{read_file, report};
+
+/// the amount of instructions the programs are limited to when they are compared
+const SAMPLE_LIMIT: usize = 10_000_000;
+
+#[derive(clap::Args, Debug)]
+pub struct ObfuscateArgs {
+    /// the brainfuck source file to obfuscate
+    file: String,
+    /// specifies a file to write the obfuscated program to instead of STDOUT
+    #[arg(short = 'o', long, action)]
+    output: Option<String>,
+    /// the seed of the obfuscation, making it deterministic, which is otherwise random
+    #[arg(long, action)]
+    seed: Option<u64>,
+    /// an input both programs are executed with to compare their output, on top of an empty input;
+    /// can be passed multiple times
+    #[arg(long = "sample", action)]
+    samples: Vec<String>,
+    /// the format errors are reported in on STDERR
+    #[arg(long, value_enum, default_value_t)]
+    error_format: report::ErrorFormat,
+}
+
+/// executes the provided program with the provided input, returning its output and how it ended,
+/// or [`None`] if it reached the instruction limit, in which case it cannot be compared
+fn sample(code: &str, input: &str) -> Option<(Vec<u8>, Option<std::mem::Discriminant<Error>>)> {
+    let mut output = Vec::new();
+    let result = Brainfuck::new(code)
+        .with_input(Cursor::new(input.to_string()))
+        .with_output_ref(&mut output)
+        .with_instructions_limit(SAMPLE_LIMIT)
+        .execute();
+
+    match result {
+        Err(Error::MaxInstructionsExceeded { .. }) => None,
+        result => Some((output, result.err().as_ref().map(std::mem::discriminant))),
+    }
+}
+
+/// obfuscates the program, then executes both programs with every sample input,
+/// writing the obfuscated program to the output only if they behaved the same
+pub fn obfuscate(args: &ObfuscateArgs) {
+    let code = read_file(&args.file, args.error_format).code;
+    let seed = args.seed.unwrap_or_else(|| std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+    );
+    let obfuscated = codegen::obfuscate(&code, seed);
+
+    for input in std::iter::once("").chain(args.samples.iter().map(String::as_str)) {
+        let (Some(expected), Some(actual)) = (sample(&code, input), sample(&obfuscated, input)) else {
+            eprintln!("warning: the programs did not finish within {SAMPLE_LIMIT} instructions with the input {input:?}, skipping it");
+            continue;
+        };
+        if expected != actual {
+            eprintln!("error: the obfuscated program behaves differently with the input {input:?}, please report this as a bug");
+            std::process::exit(report::FAILURE);
+        }
+    }
+
+    if let Some(ref path) = args.output {
+        if let Err(err) = std::fs::write(path, obfuscated) {
+            report::fail_io("Failed to write the obfuscated code to the provided file", path, &err, args.error_format);
+        }
+    } else {
+        print!("{obfuscated}");
+    }
+}
@@ -0,0 +1,80 @@
+//! emitting the optimized program instead of executing it, and the `optimize` subcommand
+
+use clap::ValueEnum;
+use brainfuck_exe::{optimizer::Optimized, Brainfuck, OptimizationLevel};
+use crate::{read_file, report, Settings};
+
+/// what to emit instead of executing the program
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum Emit {
+    /// the optimized program as brainfuck code, reporting the removed code on STDERR
+    OptimizedBf,
+    /// a report of which optimization passes fired and how many instructions they eliminated
+    OptimizationReport,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct OptimizeArgs {
+    /// the brainfuck source file to optimize
+    file: String,
+    /// specifies a file to write the optimized program to instead of STDOUT
+    #[arg(short = 'o', long, action)]
+    output: Option<String>,
+    /// also prints a report of which optimization passes fired on STDERR
+    #[arg(long, action)]
+    report: bool,
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// optimizes the provided program, exiting the process if it cannot be parsed
+fn optimized(interp: &Brainfuck, name: &str, format: report::ErrorFormat) -> Optimized {
+    interp
+        .optimize()
+        .unwrap_or_else(|err| report::fail(&err, format, name, Some(&interp.code)))
+}
+
+/// prints the code removed by the optimizer on STDERR
+fn print_removed(interp: &Brainfuck, optimized: &Optimized, name: &str) {
+    for removal in &optimized.removed {
+        eprintln!(
+            "removed `{}` at {name}:{} ({})",
+            interp.code[removal.span.range()].trim(), removal.span.start, removal.pass,
+        );
+    }
+}
+
+/// writes the provided output of the optimized program to STDOUT
+pub fn emit(interp: &Brainfuck, emit: Emit, name: &str, format: report::ErrorFormat) {
+    let optimized = optimized(interp, name, format);
+
+    if let Emit::OptimizationReport = emit {
+        return println!("{}", optimized.report(&interp.code));
+    }
+    print_removed(interp, &optimized, name);
+    println!("{}", optimized.program);
+}
+
+/// optimizes the program, aggressively unless another level is passed, writing it to the output
+/// as plain brainfuck that can be executed by any other interpreter
+pub fn optimize(args: &OptimizeArgs) {
+    let format = args.settings.error_format;
+    let mut interp = args.settings.apply(read_file(&args.file, format));
+    if args.settings.opt_level.is_none() {
+        interp = interp.with_optimization(OptimizationLevel::Aggressive);
+    }
+    let optimized = optimized(&interp, &args.file, format);
+
+    if args.report {
+        eprintln!("{}", optimized.report(&interp.code));
+    }
+    print_removed(&interp, &optimized, &args.file);
+
+    if let Some(ref path) = args.output {
+        if let Err(err) = std::fs::write(path, optimized.program.to_string()) {
+            report::fail_io("Failed to write the optimized code to the provided file", path, &err, format);
+        }
+    } else {
+        println!("{}", optimized.program);
+    }
+}
@@ -0,0 +1,80 @@
+//! the `profile` subcommand, reporting the hottest loops of a program
+
+use std::io::Cursor;
+use clap::ValueEnum;
+use brainfuck_exe::{Brainfuck, Profiler};
+use crate::{read_file, report, Settings};
+
+/// the format the profile is reported in
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum ProfileFormat {
+    /// a human readable list of the hottest loops
+    Text,
+    /// the callgrind format, for tools such as KCachegrind
+    Callgrind,
+    /// folded stacks, for inferno and flamegraph.pl
+    Folded,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ProfileArgs {
+    /// the brainfuck source file to profile
+    file: String,
+    /// manually enters the inputs (used in `,`) for the brainfuck program instead of STDIN
+    #[arg(short = 'i', long, action)]
+    input: Option<String>,
+    /// the amount of loops to report
+    #[arg(short = 'n', long, default_value_t = 10)]
+    top: usize,
+    /// also measures the time spent within each loop, slowing down the execution
+    #[arg(long, action)]
+    timing: bool,
+    /// the format to report the profile in
+    #[arg(long, value_enum, default_value_t = ProfileFormat::Text)]
+    format: ProfileFormat,
+    /// specifies a file to write the profile to instead of STDOUT
+    #[arg(short = 'o', long, action)]
+    output: Option<String>,
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// executes the program, then reports its profile
+pub fn profile(args: &ProfileArgs) {
+    let format = args.settings.error_format;
+    let code = read_file(&args.file, format).code;
+    let mut profiler = Profiler::new(&code)
+        .with_timing(args.timing);
+    let mut interp = args.settings.apply(Brainfuck::new(&code));
+
+    if let Some(ref input) = args.input {
+        interp = interp.with_input(Cursor::new(input.clone().into_bytes()));
+    }
+
+    if let Err(e) = interp
+        .with_observer(&mut profiler)
+        .execute()
+    {
+        report::fail(&e, format, &args.file, Some(&code));
+    }
+
+    let report = match args.format {
+        ProfileFormat::Text => profiler
+            .hottest_loops(args.top)
+            .iter()
+            .enumerate()
+            .fold(String::from("Hottest loops:\n"), |report, (rank, profile)|
+                format!("{report}{:>3}. {profile}\n", rank + 1)
+            ),
+        ProfileFormat::Callgrind => profiler.to_callgrind(&args.file),
+        ProfileFormat::Folded => profiler.to_folded(),
+    };
+
+    if let Some(ref path) = args.output {
+        if let Err(err) = std::fs::write(path, report) {
+            report::fail_io("Failed to write the profile to the provided file", path, &err, format);
+        }
+    } else {
+        print!("\n\n{report}");
+    }
+}
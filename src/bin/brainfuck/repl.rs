@@ -0,0 +1,59 @@
+//! the `repl` subcommand, executing every line entered against a persistent memory array
+
+use std::io::Write;
+use brainfuck_exe::{Brainfuck, EvalOutcome, Session};
+use crate::{diagnostic, Settings};
+
+#[derive(clap::Args, Debug)]
+pub struct ReplArgs {
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// reads lines from STDIN until EOF or `:quit`, evaluating each of them in a single session
+pub fn repl(args: &ReplArgs) {
+    let mut session = Session::new(args.settings.apply(Brainfuck::new("")))
+        .with_stdin(true);
+    println!("Enter brainfuck code to execute it, or `:help` for the meta-commands");
+
+    loop {
+        print!("> ");
+        if std::io::stdout().flush().is_err() {
+            break;
+        }
+        // STDIN is not locked for the whole session, as `,` reads from it too
+        let mut line = String::new();
+        match std::io::stdin().read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => (),
+            Err(err) => {
+                eprintln!("error: Failed to read from STDIN: {err}");
+                break;
+            },
+        }
+        let line = line.trim();
+
+        // the program of `:load` is read here, so that its errors can point into it
+        let (result, name, code) = match line.split_once(char::is_whitespace) {
+            Some((":load", path)) => match Brainfuck::from_file(path.trim()) {
+                Ok(interp) => (session.execute(&interp.code), path.trim(), interp.code),
+                Err(err) => (Err(err), path.trim(), String::new()),
+            },
+            _ => (session.eval(line), "<repl>", line.to_string()),
+        };
+
+        match result {
+            Ok(EvalOutcome::Executed { output, .. }) => {
+                let output = String::from_utf8_lossy(&output);
+                print!("{output}");
+                if !output.is_empty() && !output.ends_with('\n') {
+                    println!();
+                }
+            },
+            Ok(EvalOutcome::Printed(text)) => println!("{text}"),
+            Ok(EvalOutcome::Quit) => break,
+            Ok(EvalOutcome::Empty | EvalOutcome::Reset) => (),
+            Err(err) => eprint!("{}", diagnostic::render(&err, name, &code)),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! reporting of failures on STDERR, along with the exit codes of the CLI
+
+use clap::ValueEnum;
+use serde_json::json;
+use brainfuck_exe::Error;
+use crate::{describe, diagnostic};
+
+/// the exit code of a failure that does not fall in any of the other categories,
+/// such as the `lint` subcommand finding lints
+pub const FAILURE: i32 = 1;
+/// the exit code of invalid arguments, the same as the one [`clap`] uses
+pub const USAGE: i32 = 2;
+/// the exit code of code that cannot be executed, such as mismatched brackets
+pub const SYNTAX: i32 = 3;
+/// the exit code of an execution aborted by one of its limits, such as the instructions limit
+pub const LIMIT: i32 = 4;
+/// the exit code of failing to read or write a file or stream
+pub const IO: i32 = 5;
+
+/// the exit codes, documented in `--help`
+pub const EXIT_CODES: &str = "\
+Exit codes:
+  0  success
+  1  failure, such as lints being found
+  2  invalid arguments or configuration
+  3  syntax error in the code
+  4  runtime limit reached (instructions limit, timeout, infinite loop)
+  5  I/O error";
+
+/// the format failures are reported in
+#[derive(ValueEnum, Debug, Clone, Copy, Default)]
+pub enum ErrorFormat {
+    /// a human readable diagnostic pointing into the code
+    #[default]
+    Text,
+    /// a single line JSON object, for tooling
+    Json,
+}
+
+/// returns the label of the kind of the provided error, along with its exit code
+pub const fn classify(err: &Error) -> (&'static str, i32) {
+    match err {
+        Error::ZeroMemorySize => ("zero_memory_size", USAGE),
+        Error::InitialPointerOutOfBounds { .. } => ("initial_pointer_out_of_bounds", USAGE),
+        Error::ZeroMaxCellValue => ("zero_max_cell_value", USAGE),
+        Error::MismatchedBrackets { .. } => ("mismatched_brackets", SYNTAX),
+        Error::UnexpectedCharacter { .. } => ("unexpected_character", SYNTAX),
+        Error::InvalidToken { .. } => ("invalid_token", SYNTAX),
+        Error::InvalidTokenTable(_) => ("invalid_token_table", USAGE),
+        Error::FileReadError(_) => ("file_read", IO),
+        Error::FileWriteError(_) => ("file_write", IO),
+        Error::IoError(_) => ("io", IO),
+        Error::OutputError { .. } => ("output", IO),
+        Error::MaxInstructionsExceeded { .. } => ("max_instructions_exceeded", LIMIT),
+        Error::TimedOut { .. } => ("timed_out", LIMIT),
+        Error::MemoryLimitExceeded { .. } => ("memory_limit_exceeded", LIMIT),
+        Error::OutputLimitExceeded { .. } => ("output_limit_exceeded", LIMIT),
+        Error::LoopDepthExceeded { .. } => ("loop_depth_exceeded", LIMIT),
+        Error::LoopIterationsExceeded { .. } => ("loop_iterations_exceeded", LIMIT),
+        Error::InfiniteLoop(_) => ("infinite_loop", LIMIT),
+        Error::MissingCallback { .. } => ("missing_callback", USAGE),
+        Error::UndefinedProcedure { .. } => ("undefined_procedure", FAILURE),
+        Error::CellOverflow(_) => ("cell_overflow", FAILURE),
+        Error::Cancelled(_) => ("cancelled", FAILURE),
+        Error::UnknownCommand(_) => ("unknown_command", USAGE),
+    }
+}
+
+/// reports the provided error on STDERR, then exits with its exit code
+///
+/// `name` is the name of the source, such as the file the code was read from;
+/// the text format points into `code` if it is provided and the error has a position
+pub fn fail(err: &Error, format: ErrorFormat, name: &str, code: Option<&str>) -> ! {
+    let (kind, exit_code) = classify(err);
+
+    match format {
+        ErrorFormat::Text => match code {
+            Some(code) => eprint!("\n\n{}", diagnostic::render(err, name, code)),
+            None => eprintln!("error: {}", describe(err)),
+        },
+        ErrorFormat::Json => {
+            let position = err.position();
+            let context = err.context();
+
+            eprintln!("{}", json!({
+                "kind": kind,
+                "message": describe(err),
+                "exit_code": exit_code,
+                "file": name,
+                "line": position.map(|position| position.line),
+                "column": position.map(|position| position.column),
+                "offset": position.map(|position| position.offset),
+                "pointer": context.map(|context| context.pointer),
+                "instructions": context.map(|context| context.info.instructions),
+            }));
+        },
+    }
+    std::process::exit(exit_code)
+}
+
+/// reports a failure to access the provided file on STDERR, then exits with [`IO`]
+pub fn fail_io(message: &str, path: &str, err: &std::io::Error, format: ErrorFormat) -> ! {
+    match format {
+        ErrorFormat::Text => eprintln!("error: {message}: {path}: {err}"),
+        ErrorFormat::Json => eprintln!("{}", json!({
+            "kind": "io",
+            "message": format!("{message}: {err}"),
+            "exit_code": IO,
+            "file": path,
+        })),
+    }
+    std::process::exit(IO)
+}
@@ -0,0 +1,167 @@
+//! the `serve` subcommand, bridging TCP sockets to the program's I/O
+
+use std::{
+    io::Write,
+    net::{TcpListener, TcpStream},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+use brainfuck_exe::{Brainfuck, ExecutionLimits, FlushPolicy};
+use crate::{describe, read_file, report, Settings};
+
+#[derive(clap::Args, Debug)]
+pub struct ServeArgs {
+    /// the brainfuck source file to execute for every connection
+    file: String,
+    /// the address to listen on for connections
+    #[arg(short, long, default_value = "127.0.0.1:8000")]
+    listen: String,
+    /// the max amount of connections served at the same time, further connections being turned away
+    #[arg(long, default_value_t = 64, value_parser = clap::value_parser!(u32).range(1..))]
+    max_connections: u32,
+    /// executes every connection without the default limits of the server,
+    /// only with the limits passed as options
+    #[arg(long, action, verbatim_doc_comment)]
+    no_limits: bool,
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// the limits of every connection unless overridden by the options, those of [`ExecutionLimits::untrusted`]
+/// with a longer timeout, as it includes the time spent waiting for the client to send input
+fn default_limits() -> ExecutionLimits {
+    ExecutionLimits::untrusted()
+        .with_timeout(Duration::from_secs(60))
+}
+
+/// a slot of the connections served at the same time, freed once dropped
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    /// takes a slot, unless all `max` of them are taken
+    fn take(active: &Arc<AtomicUsize>, max: usize) -> Option<Self> {
+        active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| (count < max).then_some(count + 1))
+            .ok()
+            .map(|_| Self(Arc::clone(active)))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// executes a fresh interpreter for a single connection,
+/// flushing every write so a prompt reaches the client before the program waits for its input
+fn handle_connection(interp: Brainfuck<'static>, stream: TcpStream) {
+    let peer = stream
+        .peer_addr()
+        .map_or_else(|_| String::from("unknown"), |addr| addr.to_string());
+
+    let reader = match stream.try_clone() {
+        Ok(reader) => reader,
+        Err(e) => {
+            eprintln!("[{peer}] Failed to set up the connection: {e}");
+            return;
+        }
+    };
+
+    match interp
+        .with_flush_policy(FlushPolicy::EveryWrite)
+        .with_input(reader)
+        .with_output(stream)
+        .execute()
+    {
+        Ok(info) => println!(
+            "[{peer}] Finished after {} instructions", info.instructions
+        ),
+        Err(e) => eprintln!("[{peer}] Something went wrong: {}", describe(&e)),
+    }
+}
+
+/// listens for connections, executing the program once per connection on its own thread
+pub fn serve(args: &ServeArgs) {
+    let format = args.settings.error_format;
+    let mut interp = read_file(&args.file, format);
+
+    if !args.no_limits {
+        let mut limits = default_limits();
+        // a memory size picked by the user bounds the memory array instead
+        if args.settings.memory_size.is_some() {
+            limits.memory_bytes = None;
+        }
+        interp = interp.with_limits(limits);
+    }
+    // the limits passed as options take precedence over the default ones
+    let interp = args.settings.apply(interp);
+
+    let listener = TcpListener::bind(&args.listen)
+        .unwrap_or_else(|err| report::fail_io("Failed to listen on the provided address", &args.listen, &err, format));
+    println!("Listening on {}", args.listen);
+
+    let active = Arc::new(AtomicUsize::new(0));
+    let max = args.max_connections as usize;
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(mut stream) => {
+                let Some(slot) = Slot::take(&active, max) else {
+                    eprintln!("Turned a connection away, the max of {max} connections are already being served");
+                    let _ = stream.write_all(b"the server is busy, try again later\n");
+                    continue;
+                };
+                let interp = interp.clone_config();
+                thread::spawn(move || {
+                    handle_connection(interp, stream);
+                    drop(slot);
+                });
+            },
+            Err(e) => eprintln!("Failed to accept a connection: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        thread,
+        time::Duration,
+    };
+    use brainfuck_exe::Brainfuck;
+    use super::handle_connection;
+
+    #[test]
+    fn test_prompt_before_input() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // prints a `?` prompt without a newline, then echoes the byte it reads
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle_connection(
+                Brainfuck::new("+++++++++[>+++++++<-]>.,.").with_flush(false),
+                stream,
+            );
+        });
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        client.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut byte = [0];
+
+        client.read_exact(&mut byte).expect("the prompt reaches the client before the program reads");
+        assert_eq!(&byte, b"?");
+        client.write_all(b"x").unwrap();
+        client.read_exact(&mut byte).unwrap();
+        assert_eq!(&byte, b"x");
+
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,350 @@
+//! the `serve-http` subcommand, executing programs posted to a small JSON API for web playgrounds
+//!
+//! requires the `server` feature
+
+use std::{
+    io::{self, Cursor, Read},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+use brainfuck_exe::{Brainfuck, Dialect, EofBehavior, ExecutionLimits, OptimizationLevel};
+use crate::{describe, info_json, report};
+
+#[derive(clap::Args, Debug)]
+pub struct ServeHttpArgs {
+    /// the port to listen on
+    #[arg(short, long, default_value_t = 8080)]
+    port: u16,
+    /// the address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+    /// the amount of programs executed at the same time, further requests waiting for a worker
+    #[arg(short = 'j', long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    workers: u16,
+    /// the max amount of instructions a program can execute, also the default of requests without a limit
+    #[arg(long, default_value_t = 10_000_000)]
+    max_instructions: usize,
+    /// the max time a program can execute for in milliseconds, also the default of requests without a timeout
+    #[arg(long, default_value_t = 5_000)]
+    max_timeout: u64,
+    /// the max amount of cells a program can use, also the default of requests without a memory size
+    #[arg(long, default_value_t = 30_000)]
+    max_memory_size: usize,
+    /// the max amount of bytes a program can write, failing the execution once exceeded
+    #[arg(long, default_value_t = 65_536)]
+    max_output: usize,
+    /// the max size of the body of a request in bytes, the code and input included
+    #[arg(long, default_value_t = 1_048_576)]
+    max_request: usize,
+}
+
+/// the limits enforced on every execution, regardless of the options of the request
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    instructions: usize,
+    timeout: u64,
+    memory_size: usize,
+    output: usize,
+    request: usize,
+}
+
+/// a response to send, its body being JSON
+struct Reply {
+    status: u16,
+    body: String,
+}
+
+impl Reply {
+    /// a response with the provided status, its body being an object with the provided error message
+    fn error(status: u16, message: &str) -> Self {
+        Self { status, body: json!({ "error": message }).to_string() }
+    }
+}
+
+/// returns the numeric option of the request, failing if it is not a non-negative integer
+fn number(options: &Value, key: &str) -> Result<Option<usize>, Reply> {
+    options
+        .get(key)
+        .filter(|value| !value.is_null())
+        .map(|value| value
+            .as_u64()
+            .and_then(|number| usize::try_from(number).ok())
+            .ok_or_else(|| Reply::error(400, &format!("`{key}` must be a non-negative integer")))
+        )
+        .transpose()
+}
+
+/// returns the string option of the request, failing if it is not a string
+fn string<'j>(options: &'j Value, key: &str) -> Result<Option<&'j str>, Reply> {
+    options
+        .get(key)
+        .filter(|value| !value.is_null())
+        .map(|value| value
+            .as_str()
+            .ok_or_else(|| Reply::error(400, &format!("`{key}` must be a string")))
+        )
+        .transpose()
+}
+
+/// builds the interpreter of the request, clamping its limits to the ones of the server
+///
+/// the limits the server has no option for are those of [`ExecutionLimits::untrusted`],
+/// the memory of the threads of a [`Dialect::Brainfork`] program being bounded along with the nested loops
+fn configure<'a>(request: &Value, limits: Limits) -> Result<Brainfuck<'a>, Reply> {
+    let code = string(request, "code")?
+        .ok_or_else(|| Reply::error(400, "`code` is required"))?;
+    let memory_size = number(request, "memorySize")?
+        .map_or(limits.memory_size, |size| size.min(limits.memory_size));
+    let untrusted = ExecutionLimits::untrusted();
+    let execution_limits = untrusted
+        .with_instructions(number(request, "instructionsLimit")?
+            .map_or(limits.instructions, |limit| limit.min(limits.instructions)))
+        .with_timeout(Duration::from_millis(number(request, "timeout")?
+            .map_or(limits.timeout, |timeout| (timeout as u64).min(limits.timeout))))
+        .with_output_bytes(limits.output)
+        // a single memory array of the max size always fits
+        .with_memory_bytes(untrusted.memory_bytes
+            .unwrap_or_default()
+            .max(memory_size.saturating_mul(size_of::<u32>())));
+    let mut interp = Brainfuck::new(code)
+        .with_bench_execution(true)
+        .with_limits(execution_limits)
+        .with_mem_size(memory_size);
+
+    if let Some(value) = number(request, "maxCellValue")? {
+        let value = u32::try_from(value)
+            .map_err(|_| Reply::error(400, "`maxCellValue` must fit in 32 bits"))?;
+        interp = interp.with_max_value(value);
+    }
+    if let Some(chr) = string(request, "fallbackInput")? {
+        let chr = chr
+            .chars()
+            .next()
+            .ok_or_else(|| Reply::error(400, "`fallbackInput` must not be empty"))?;
+        interp = interp.with_fallback_input(chr);
+    }
+    match request.get("wrapping") {
+        None | Some(Value::Null) => (),
+        Some(Value::Bool(wrapping)) => interp = interp.with_wrapping(*wrapping),
+        Some(_) => return Err(Reply::error(400, "`wrapping` must be a boolean")),
+    }
+    if let Some(level) = string(request, "optimization")? {
+        let level = OptimizationLevel::from_name(level)
+            .ok_or_else(|| Reply::error(400, "`optimization` must be one of `none`, `basic` or `aggressive`"))?;
+        interp = interp.with_optimization(level);
+    }
+    if let Some(eof) = string(request, "eof")? {
+        let eof = EofBehavior::from_name(eof)
+            .ok_or_else(|| Reply::error(400, "`eof` must be one of `fallback`, `max` or `unchanged`"))?;
+        interp = interp.with_eof(eof);
+    }
+    if let Some(dialect) = string(request, "dialect")? {
+        let dialect = Dialect::from_name(dialect).ok_or_else(|| Reply::error(
+            400,
+            "`dialect` must be one of `brainfuck`, `pbrain`, `brainfork`, `extended-type-i` or `boolfuck`",
+        ))?;
+        interp = interp.with_dialect(dialect);
+    }
+    Ok(interp)
+}
+
+/// executes the program of the provided body of a `POST /run` request
+fn run(body: &str, limits: Limits) -> Reply {
+    let Some(request) = serde_json::from_str::<Value>(body).ok().filter(Value::is_object) else {
+        return Reply::error(400, "the body must be a JSON object");
+    };
+    let input = match string(&request, "input") {
+        Ok(input) => input.unwrap_or_default().as_bytes().to_vec(),
+        Err(reply) => return reply,
+    };
+    // the memory array spans the whole memory size, so it is only returned when asked for
+    let cells = request.get("cells") == Some(&Value::Bool(true));
+    let mut output = Vec::new();
+
+    let result = match configure(&request, limits) {
+        Ok(interp) => interp
+            .with_input(Cursor::new(input))
+            .with_output_ref(&mut output)
+            .execute(),
+        Err(reply) => return reply,
+    };
+    let text = String::from_utf8_lossy(&output);
+
+    match result {
+        Ok(info) => Reply {
+            status: 200,
+            body: json!({ "output": text, "stats": info_json(&info, cells) }).to_string(),
+        },
+        // the execution failing is not a failure of the request, the partial output being returned along with it
+        Err(err) => Reply {
+            status: 422,
+            body: json!({
+                "error": describe(&err),
+                "kind": report::classify(&err).0,
+                "output": text,
+                "instructions": err.context().map(|context| context.info.instructions),
+            })
+            .to_string(),
+        },
+    }
+}
+
+/// returns the headers of every response, allowing playgrounds hosted anywhere to call the API
+fn headers() -> Vec<Header> {
+    [
+        ("Content-Type", "application/json"),
+        ("Access-Control-Allow-Origin", "*"),
+        ("Access-Control-Allow-Methods", "POST, OPTIONS"),
+        ("Access-Control-Allow-Headers", "Content-Type"),
+    ]
+    .into_iter()
+    // the headers are constant, so they never fail to parse
+    .filter_map(|(name, value)| Header::from_bytes(name, value).ok())
+    .collect()
+}
+
+/// routes the provided request, then sends its response
+fn handle(mut request: Request, limits: Limits) {
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default();
+
+    let reply = match (request.method(), path) {
+        (Method::Options, _) => Reply { status: 204, body: String::new() },
+        (Method::Post, "/run") => {
+            let mut body = String::new();
+            let read = request
+                .as_reader()
+                .take(limits.request as u64 + 1)
+                .read_to_string(&mut body);
+
+            match read {
+                Ok(len) if len > limits.request => Reply::error(413, &format!(
+                    "the body exceeded the limit of {} bytes", limits.request,
+                )),
+                Ok(_) => run(&body, limits),
+                Err(_) => Reply::error(400, "the body must be valid UTF-8"),
+            }
+        },
+        (_, "/run") => Reply::error(405, "only `POST` is allowed"),
+        _ => Reply::error(404, "not found, programs are executed with `POST /run`"),
+    };
+
+    let mut response = Response::from_string(reply.body)
+        .with_status_code(reply.status);
+    for header in headers() {
+        response.add_header(header);
+    }
+    if let Err(err) = request.respond(response) {
+        eprintln!("Failed to respond: {err}");
+    }
+}
+
+/// listens for requests, executing them on a fixed amount of worker threads
+pub fn serve_http(args: &ServeHttpArgs) {
+    let address = format!("{}:{}", args.host, args.port);
+    let limits = Limits {
+        instructions: args.max_instructions,
+        timeout: args.max_timeout,
+        memory_size: args.max_memory_size,
+        output: args.max_output,
+        request: args.max_request,
+    };
+
+    let server = Server::http(&address).unwrap_or_else(|err| report::fail_io(
+        "Failed to listen on the provided address",
+        &address,
+        &io::Error::other(err),
+        report::ErrorFormat::default(),
+    ));
+    let server = Arc::new(server);
+    println!("Listening on http://{address}, executing programs with `POST /run`");
+
+    let workers = (0..args.workers)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            thread::spawn(move || for request in server.incoming_requests() {
+                handle(request, limits);
+            })
+        })
+        .collect::<Vec<_>>();
+    for worker in workers {
+        let _ = worker.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use super::{run, Limits};
+
+    const LIMITS: Limits = Limits {
+        instructions: 1_000,
+        timeout: 5_000,
+        memory_size: 100,
+        output: 64,
+        request: 1_024,
+    };
+
+    /// executes the provided body, returning the status and the parsed body of the response
+    fn request(body: &str) -> (u16, Value) {
+        let reply = run(body, LIMITS);
+        (reply.status, serde_json::from_str(&reply.body).expect("the body of every response is JSON"))
+    }
+
+    #[test]
+    fn test_run() {
+        let (status, body) = request(r#"{"code": ",[.,]", "input": "hi", "memorySize": 3, "cells": true}"#);
+
+        assert_eq!(status, 200);
+        assert_eq!(body["output"], "hi");
+        assert_eq!(body["stats"]["instructions"], 10);
+        assert_eq!(body["stats"]["cells"], serde_json::json!([0, 0, 0]));
+    }
+
+    #[test]
+    fn test_run_syntax_error() {
+        let (status, body) = request(r#"{"code": "+[.", "input": "hi"}"#);
+
+        assert_eq!(status, 422);
+        assert_eq!(body["kind"], "mismatched_brackets");
+        assert_eq!(body["output"], "");
+        assert_eq!(body["instructions"], Value::Null);
+
+        let (status, body) = request(r#"{"input": "hi"}"#);
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "`code` is required");
+
+        let (status, _) = request("[1, 2]");
+        assert_eq!(status, 400);
+    }
+
+    #[test]
+    fn test_run_limits() {
+        // the limit of the request cannot exceed the one of the server
+        let (status, body) = request(r#"{"code": "+[]", "instructionsLimit": 1000000}"#);
+        assert_eq!(status, 422);
+        assert_eq!(body["kind"], "max_instructions_exceeded");
+        assert_eq!(body["instructions"], LIMITS.instructions + 1);
+
+        let (status, body) = request(r#"{"code": "+[]", "instructionsLimit": 10}"#);
+        assert_eq!(status, 422);
+        assert_eq!(body["instructions"], 11);
+
+        let (status, body) = request(r#"{"code": "+[.]"}"#);
+        assert_eq!(status, 422);
+        assert_eq!(body["kind"], "output_limit_exceeded");
+        assert_eq!(body["output"].as_str().map(str::len), Some(LIMITS.output));
+
+        // the limits the server has no option for are still enforced
+        let nested = format!(r#"{{"code": "{}{}"}}"#, "[".repeat(300), "]".repeat(300));
+        let (status, body) = request(&nested);
+        assert_eq!(status, 422);
+        assert_eq!(body["kind"], "loop_depth_exceeded");
+    }
+}
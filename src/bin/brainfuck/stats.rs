@@ -0,0 +1,75 @@
+//! the `stats` subcommand, summarizing a program without executing it
+
+use brainfuck_exe::ast::Program;
+use crate::{read_file, report};
+
+/// the instructions counted, along with their names
+const INSTRUCTIONS: [(char, &str); 8] = [
+    ('+', "increment"),
+    ('-', "decrement"),
+    ('>', "move right"),
+    ('<', "move left"),
+    ('.', "output"),
+    (',', "input"),
+    ('[', "loop start"),
+    (']', "loop end"),
+];
+
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    /// the brainfuck source file to summarize
+    file: String,
+    /// the format errors are reported in on STDERR
+    #[arg(long, value_enum, default_value_t)]
+    error_format: report::ErrorFormat,
+}
+
+/// returns the deepest the loops of the provided code are nested
+fn max_depth(code: &str) -> usize {
+    code.chars()
+        .fold((0_usize, 0_usize), |(depth, max), chr| match chr {
+            '[' => (depth + 1, max.max(depth + 1)),
+            ']' => (depth.saturating_sub(1), max),
+            _ => (depth, max),
+        })
+        .1
+}
+
+/// prints the amount of every instruction in the program, its loops, comments and the cells it can use
+pub fn stats(args: &StatsArgs) {
+    let code = read_file(&args.file, args.error_format).code;
+    let analysis = Program::parse(&code)
+        .unwrap_or_else(|err| report::fail(&err, args.error_format, &args.file, Some(&code)))
+        .analyze();
+
+    let counts = INSTRUCTIONS.map(|(instruction, name)| (name, code.matches(instruction).count()));
+    let instructions = counts
+        .iter()
+        .map(|(_, count)| count)
+        .sum::<usize>();
+    let visible = code
+        .chars()
+        .filter(|chr| !chr.is_whitespace())
+        .count();
+    let comments = visible - instructions;
+
+    for (name, count) in counts {
+        println!("{:<16}{count}", format!("{name}:"));
+    }
+    println!("{:<16}{instructions}", "instructions:");
+    println!("{:<16}{}", "loops:", analysis.loops.len());
+    println!("{:<16}{}", "max depth:", max_depth(&code));
+    println!(
+        "{:<16}{comments} characters ({:.1}% of the non-whitespace characters)",
+        "comments:",
+        if visible == 0 { 0.0 } else { comments as f64 / visible as f64 * 100.0 },
+    );
+    println!(
+        "{:<16}{}",
+        "tape usage:",
+        analysis.memory_size().map_or_else(
+            || String::from("unknown, as a loop moves the pointer by a varying amount"),
+            |size| format!("at most {size} cells"),
+        ),
+    );
+}
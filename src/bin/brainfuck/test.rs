@@ -0,0 +1,125 @@
+//! the `test` subcommand, executing every program of a directory against its sibling input and expected output
+//!
+//! for a program `NAME.bf`, the input is read from `NAME.in` if it exists, the output is compared against `NAME.out`,
+//! and the options of `NAME.toml`, in the format of the configuration file, are used if it exists
+
+use std::{
+    io::Cursor,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+use brainfuck_exe::Brainfuck;
+use crate::{diagnostic, expect, report, Settings};
+
+#[derive(clap::Args, Debug)]
+pub struct TestArgs {
+    /// the directory to look for `*.bf` programs in, recursively
+    #[arg(default_value = ".")]
+    dir: String,
+    /// only executes the programs whose path contains the provided string
+    #[arg(long, action)]
+    filter: Option<String>,
+    #[command(flatten)]
+    pub settings: Settings,
+}
+
+/// the result of a single program
+enum Outcome {
+    Pass,
+    /// the reason the program failed, printed below its name
+    Fail(String),
+    /// the program has no `.out` file to be compared against
+    Skip,
+}
+
+/// returns every `*.bf` file in the provided directory and its subdirectories, sorted by path
+fn discover(dir: &Path, programs: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            discover(&path, programs)?;
+        } else if path.extension().is_some_and(|ext| ext == "bf") {
+            programs.push(path);
+        }
+    }
+    programs.sort();
+    Ok(())
+}
+
+/// executes the provided program against its sibling files
+fn run(program: &Path, settings: &Settings) -> Outcome {
+    let name = program.to_string_lossy();
+    let Ok(expected) = std::fs::read(program.with_extension("out")) else {
+        return Outcome::Skip;
+    };
+    let input = std::fs::read(program.with_extension("in")).unwrap_or_default();
+
+    let mut settings = settings.clone();
+    let config = program.with_extension("toml");
+    if config.is_file() {
+        settings.load_file(&config.to_string_lossy());
+    }
+
+    let interp = match Brainfuck::from_file(program) {
+        Ok(interp) => interp,
+        Err(err) => return Outcome::Fail(diagnostic::render(&err, &name, "")),
+    };
+    let mut output = Vec::new();
+    let mut interp = settings
+        .apply(interp)
+        .with_input(Cursor::new(input))
+        .with_output_ref(&mut output);
+
+    if let Err(err) = interp.execute() {
+        return Outcome::Fail(diagnostic::render(&err, &name, &interp.code));
+    }
+    drop(interp);
+
+    expect::diff(&expected, &output).map_or(Outcome::Pass, Outcome::Fail)
+}
+
+/// executes every program found, printing whether each of them passed and a summary,
+/// exiting with [`report::FAILURE`] if any of them failed
+pub fn test(args: &TestArgs) {
+    let mut programs = Vec::new();
+    if let Err(err) = discover(Path::new(&args.dir), &mut programs) {
+        report::fail_io("Failed to read the provided directory", &args.dir, &err, args.settings.error_format);
+    }
+    programs.retain(|program| args.filter
+        .as_ref()
+        .is_none_or(|filter| program.to_string_lossy().contains(filter.as_str()))
+    );
+
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+    let start = Instant::now();
+
+    for program in &programs {
+        let time = Instant::now();
+        let outcome = run(program, &args.settings);
+        let elapsed = time.elapsed().as_millis();
+
+        match outcome {
+            Outcome::Pass => {
+                passed += 1;
+                println!("PASS {} [{elapsed} ms]", program.display());
+            },
+            Outcome::Fail(reason) => {
+                failed += 1;
+                println!("FAIL {} [{elapsed} ms]\n{}", program.display(), reason.trim_end());
+            },
+            Outcome::Skip => {
+                skipped += 1;
+                println!("SKIP {} (no .out file)", program.display());
+            },
+        }
+    }
+
+    println!(
+        "\n{passed} passed, {failed} failed, {skipped} skipped in [{} ms]",
+        start.elapsed().as_millis(),
+    );
+    if failed > 0 {
+        std::process::exit(report::FAILURE);
+    }
+}
@@ -0,0 +1,70 @@
+//! the `--trace` mode, printing every instruction executed on STDERR
+
+use std::ops::ControlFlow;
+use clap::ValueEnum;
+use brainfuck_exe::{Brainfuck, HookCtx};
+
+/// a kind of instruction the trace can be restricted to
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// the `,` and `.` instructions
+    Io,
+    /// the `[` and `]` instructions
+    Loops,
+}
+
+impl TraceEvent {
+    /// returns whether or not the provided instruction is of this kind
+    fn matches(self, opcode: char) -> bool {
+        match self {
+            Self::Io => matches!(opcode, ',' | '.'),
+            Self::Loops => matches!(opcode, '[' | ']'),
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct TraceArgs {
+    /// prints every instruction executed on STDERR, along with its code index,
+    /// the pointer and the value of the current cell
+    #[arg(long, action, verbatim_doc_comment)]
+    trace: bool,
+    /// only prints every Nth instruction traced
+    /// implies [--trace]
+    #[arg(long, action, value_parser = clap::value_parser!(u64).range(1..), verbatim_doc_comment)]
+    trace_every: Option<u64>,
+    /// only traces the provided kinds of instructions, can be specified multiple times
+    /// implies [--trace]
+    #[arg(long, value_enum, verbatim_doc_comment)]
+    trace_only: Vec<TraceEvent>,
+}
+
+impl TraceArgs {
+    /// sets an instruction hook onto the provided interpreter printing the trace, if it is enabled
+    ///
+    /// `name` prefixes every line, to tell apart the programs of a pipeline
+    pub fn apply<'a>(&self, interp: Brainfuck<'a>, name: Option<&str>) -> Brainfuck<'a> {
+        if !self.trace && self.trace_every.is_none() && self.trace_only.is_empty() {
+            return interp;
+        }
+        let every = self.trace_every.unwrap_or(1);
+        let only = self.trace_only.clone();
+        let prefix = name
+            .map(|name| format!("[{name}] "))
+            .unwrap_or_default();
+        let mut traced = 0_u64;
+
+        interp.with_instruction_hook(move |ctx: &HookCtx| {
+            if only.is_empty() || only.iter().any(|event| event.matches(ctx.opcode)) {
+                if traced.is_multiple_of(every) {
+                    eprintln!(
+                        "{prefix}#{} {} at {}: pointer {}, cell {}",
+                        ctx.instructions + 1, ctx.opcode, ctx.code_idx, ctx.pointer, ctx.cell,
+                    );
+                }
+                traced += 1;
+            }
+            ControlFlow::Continue(())
+        })
+    }
+}
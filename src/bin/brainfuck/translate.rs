@@ -0,0 +1,76 @@
+//! the `translate` subcommand, translating a program between brainfuck and the languages derived from it
+
+use clap::ValueEnum;
+use brainfuck_exe::translate::{translate as translate_code, Language};
+use crate::report;
+
+/// a language a program can be translated into and from
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum LanguageArg {
+    /// brainfuck itself
+    Bf,
+    /// Ook!
+    Ook,
+    /// Alphuck
+    Alphuck,
+    /// ReverseFuck
+    Reversefuck,
+    /// Spoon
+    Spoon,
+}
+
+impl From<LanguageArg> for Language {
+    fn from(language: LanguageArg) -> Self {
+        match language {
+            LanguageArg::Bf => Self::Brainfuck,
+            LanguageArg::Ook => Self::Ook,
+            LanguageArg::Alphuck => Self::Alphuck,
+            LanguageArg::Reversefuck => Self::ReverseFuck,
+            LanguageArg::Spoon => Self::Spoon,
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct TranslateArgs {
+    /// the source file to translate
+    file: String,
+    /// the language of the source file
+    /// if not provided, it is inferred from the extension of the file, defaulting to brainfuck
+    #[arg(long, value_enum, verbatim_doc_comment)]
+    from: Option<LanguageArg>,
+    /// the language to translate the program into
+    #[arg(long, value_enum)]
+    to: LanguageArg,
+    /// specifies a file to write the translated program to instead of STDOUT
+    #[arg(short = 'o', long, action)]
+    output: Option<String>,
+    /// the format errors are reported in on STDERR
+    #[arg(long, value_enum, default_value_t)]
+    error_format: report::ErrorFormat,
+}
+
+/// translates the program, writing it to the output
+pub fn translate(args: &TranslateArgs) {
+    let code = std::fs::read_to_string(&args.file)
+        .unwrap_or_else(|err| report::fail_io("Failed to read the provided file", &args.file, &err, args.error_format));
+    let from = args.from.map_or_else(
+        || std::path::Path::new(&args.file)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Language::from_extension)
+            .unwrap_or(Language::Brainfuck),
+        Language::from,
+    );
+
+    let translated = translate_code(&code, from, args.to.into())
+        .unwrap_or_else(|err| report::fail(&err, args.error_format, &args.file, Some(&code)));
+
+    if let Some(ref path) = args.output {
+        if let Err(err) = std::fs::write(path, translated) {
+            report::fail_io("Failed to write the translated code to the provided file", path, &err, args.error_format);
+        }
+    } else {
+        println!("{translated}");
+    }
+}
@@ -0,0 +1,118 @@
+//! the `--visualize` mode, redrawing the memory array on STDERR as the program executes
+
+use std::{fmt::Write, thread, time::Duration};
+use brainfuck_exe::{Brainfuck, ExecutionInfo, ExecutionObserver, HookCtx, OptimizationLevel};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct VisualizeArgs {
+    /// redraws the memory array and the pointer on STDERR before every instruction executed,
+    /// along with the output thus far
+    /// the program is never optimized, so that every instruction is drawn,
+    /// and a pipeline of multiple files cannot be visualized
+    #[arg(long, action, verbatim_doc_comment)]
+    visualize: bool,
+    /// the amount of cells drawn, scrolling along with the pointer
+    #[arg(long, action, default_value_t = 16, value_parser = clap::value_parser!(u64).range(1..))]
+    visualize_cells: u64,
+    /// only redraws every Nth instruction
+    #[arg(long, action, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    visualize_every: u64,
+    /// the delay in between redraws, in milliseconds
+    #[arg(long, action, default_value_t = 50)]
+    visualize_delay: u64,
+}
+
+impl VisualizeArgs {
+    /// whether or not the memory array is drawn
+    pub const fn enabled(&self) -> bool {
+        self.visualize
+    }
+
+    /// sets an observer onto the provided interpreter drawing the memory array, if it is enabled
+    pub fn apply<'a>(&self, interp: Brainfuck<'a>) -> Brainfuck<'a> {
+        if !self.visualize {
+            return interp;
+        }
+        interp
+            .with_optimization(OptimizationLevel::None)
+            .with_observer(Visualizer {
+                // both values are parsed as `u64` for clap to range check them, and are small in practice
+                width: usize::try_from(self.visualize_cells).unwrap_or(usize::MAX),
+                every: self.visualize_every,
+                delay: Duration::from_millis(self.visualize_delay),
+                cells: vec![0],
+                output: String::new(),
+                drawn: 0,
+            })
+    }
+}
+
+/// draws the memory array as the program executes
+///
+/// the observer only sees the current cell before every instruction,
+/// so it mirrors the memory array from the cells it sees; as every instruction only modifies the current cell,
+/// the mirror is the memory array as of the previous instruction
+struct Visualizer {
+    /// the amount of cells drawn
+    width: usize,
+    /// the amount of instructions in between redraws
+    every: u64,
+    /// the delay in between redraws
+    delay: Duration,
+    /// the mirror of the memory array
+    cells: Vec<u32>,
+    /// the output written thus far
+    output: String,
+    /// the amount of instructions seen thus far
+    drawn: u64,
+}
+
+impl Visualizer {
+    /// redraws the memory array with the pointer, along with the status line below it
+    fn draw(&self, pointer: usize, status: &str) {
+        let start = (pointer + 1).saturating_sub(self.width);
+        let mut cells = String::new();
+        let mut caret = String::new();
+
+        for (idx, cell) in self.cells
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(0))
+            .enumerate()
+            .skip(start)
+            .take(self.width)
+        {
+            let _ = write!(cells, "{cell:>5}");
+            caret += if idx == pointer { "    ^" } else { "     " };
+        }
+        eprint!(
+            "\x1b[2J\x1b[H cells {start}..{}\n{cells}\n{caret}\n\n{status}\n\noutput:\n{}\n",
+            start + self.width, self.output,
+        );
+    }
+}
+
+impl ExecutionObserver for Visualizer {
+    fn on_instruction(&mut self, ctx: &HookCtx) {
+        if self.cells.len() <= ctx.pointer {
+            self.cells.resize(ctx.pointer + 1, 0);
+        }
+        self.cells[ctx.pointer] = ctx.cell;
+
+        if self.drawn.is_multiple_of(self.every) {
+            self.draw(ctx.pointer, &format!(
+                "instruction #{}: `{}` at {}", ctx.instructions + 1, ctx.opcode, ctx.code_idx,
+            ));
+            thread::sleep(self.delay);
+        }
+        if ctx.opcode == '.' {
+            self.output.extend(char::from_u32(ctx.cell));
+        }
+        self.drawn += 1;
+    }
+
+    fn on_halt(&mut self, info: &ExecutionInfo) {
+        self.cells.clone_from(&info.cells);
+        self.draw(info.pointer, &format!("halted after {} instructions", info.instructions));
+    }
+}
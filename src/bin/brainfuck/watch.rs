@@ -0,0 +1,76 @@
+//! the `--watch` mode, re-executing the program every time one of its files is modified
+
+use std::{
+    ffi::OsString,
+    io::Write,
+    process::{Child, Command},
+    thread,
+    time::{Duration, SystemTime},
+};
+use crate::report;
+
+/// the interval in between checking the files for modifications
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// returns the last modification time of every file, or [`None`] for the files that cannot be accessed
+fn modified(files: &[String]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| std::fs::metadata(file)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+        )
+        .collect()
+}
+
+/// clears the terminal and executes the program in a child process with the same arguments, without `--watch`
+fn spawn(arguments: &[OsString]) -> Child {
+    print!("\x1b[2J\x1b[H");
+    if std::io::stdout().flush().is_err() {
+        std::process::exit(report::IO);
+    }
+
+    std::env::current_exe()
+        .and_then(|exe| Command::new(exe)
+            .args(arguments)
+            .spawn()
+        )
+        .unwrap_or_else(|err| {
+            eprintln!("error: Failed to execute the program: {err}");
+            std::process::exit(report::IO);
+        })
+}
+
+/// executes the program, then executes it again every time one of the provided files is modified,
+/// killing the previous execution if it is still running
+///
+/// the program is executed in a child process, such that it can be killed and fail without ending the watch
+pub fn watch(files: &[String]) -> ! {
+    let arguments = std::env::args_os()
+        .skip(1)
+        .filter(|argument| argument != "--watch")
+        .collect::<Vec<OsString>>();
+    let mut stamps = modified(files);
+
+    loop {
+        let mut child = spawn(&arguments);
+        let mut finished = false;
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let current = modified(files);
+            if current != stamps {
+                stamps = current;
+                // the child may have exited in between, in which case there is nothing to kill
+                let _ = child.kill();
+                let _ = child.wait();
+                break;
+            }
+            if !finished && matches!(child.try_wait(), Ok(Some(_))) {
+                finished = true;
+                eprintln!("\n[watching {} for changes]", files.join(", "));
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! module containing the non-executing validation of the code
+//!
+//! see [`Brainfuck::check`] for more information
+
+use std::fmt;
+use crate::{Brainfuck, Position, Span};
+
+/// the severity of a [`Diagnostic`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// the code cannot be executed
+    Error,
+    /// the code can be executed, but likely does not behave as intended
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(
+            match self {
+                Self::Error => "error",
+                Self::Warning => "warning",
+            }
+        )
+    }
+}
+
+/// a problem found in the code by [`Brainfuck::check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// whether the problem prevents the code from being executed
+    pub severity: Severity,
+    /// a description of the problem
+    pub message: String,
+    /// the range of the code the problem is found in
+    pub span: Span,
+    /// the name of the lint that found the problem, if it was found by [`Brainfuck::lint`]
+    pub lint: Option<&'static str>,
+    /// the code to replace the spanned code with to fix the problem, if any
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}: {}", self.severity, self.span.start, self.message)?;
+        if let Some(lint) = self.lint {
+            write!(f, " [{lint}]")?;
+        }
+        Ok(())
+    }
+}
+
+impl Diagnostic {
+    /// creates a new diagnostic spanning the provided code indices, `end` being exclusive
+    pub(crate) fn new(
+        severity: Severity,
+        message: impl Into<String>,
+        positions: &[Position],
+        start: usize,
+        end: usize,
+    ) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            span: Span {
+                start: positions[start],
+                end: positions[end],
+            },
+            lint: None,
+            suggestion: None,
+        }
+    }
+}
+
+/// returns whether or not a loop with the provided body never terminates once entered,
+/// as nothing within it can change the current cell
+pub(crate) fn is_endless(body: &[char]) -> bool {
+    !body
+        .iter()
+        .any(|chr| matches!(chr, '+' | '-' | '<' | '>' | ','))
+}
+
+impl Brainfuck<'_> {
+    /// validates the code without executing it, returning every problem found, in the order they appear in
+    ///
+    /// - errors: every unmatched `[` and stray `]`,
+    ///   and every character that is neither an instruction nor whitespace if `strict_syntax` is enabled
+    /// - warnings: loops that can never terminate once entered, such as `+[]`,
+    ///   since nothing within them can change the current cell
+    ///
+    /// the code can be executed if none of the diagnostics are errors
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{Brainfuck, Severity};
+    ///
+    /// let diagnostics = Brainfuck::new("+[.]]").check();
+    ///
+    /// assert_eq!(diagnostics.len(), 2);
+    /// assert_eq!(diagnostics[0].severity, Severity::Warning);
+    /// assert_eq!(diagnostics[1].severity, Severity::Error);
+    /// assert_eq!(diagnostics[1].span.start.column, 5);
+    /// ```
+    #[must_use]
+    pub fn check(&self) -> Vec<Diagnostic> {
+        let code = self.code
+            .chars()
+            .collect::<Vec<char>>();
+        let positions = Position::locate_all(&code);
+        let mut diagnostics = Vec::new();
+        let mut loops = Vec::new();
+        let mut stack = Vec::new();
+
+        for (idx, chr) in code.iter().enumerate() {
+            match chr {
+                '[' => stack.push(idx),
+                ']' => if let Some(open) = stack.pop() {
+                    loops.push((open, idx));
+                } else {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Error, "stray `]` without a matching `[`", &positions, idx, idx + 1,
+                    ));
+                },
+                _ if self.strict_syntax
+                    && !self.is_instruction(*chr)
+                    && !chr.is_whitespace()
+                => diagnostics.push(Diagnostic::new(
+                    Severity::Error, format!("unexpected character {chr:?}"), &positions, idx, idx + 1,
+                )),
+                _ => (),
+            }
+        }
+        diagnostics.extend(
+            stack.into_iter()
+                .map(|idx| Diagnostic::new(
+                    Severity::Error, "unmatched `[` without a matching `]`", &positions, idx, idx + 1,
+                ))
+        );
+
+        loops.sort_unstable();
+        let mut endless_until = None;
+
+        for (open, close) in loops {
+            // the loops nested within an endless loop are already covered by its warning
+            if endless_until.is_some_and(|end| close < end) {
+                continue;
+            }
+            if is_endless(&code[open + 1..close]) {
+                endless_until = Some(close);
+                diagnostics.push(Diagnostic::new(
+                    Severity::Warning,
+                    "this loop never terminates once entered, as nothing within it can change the current cell",
+                    &positions, open, close + 1,
+                ));
+            }
+        }
+
+        diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+        diagnostics
+    }
+}
@@ -0,0 +1,293 @@
+//! module containing generators of brainfuck code, see [`generate`], [`shortest_const`] and [`obfuscate`]
+
+use std::collections::HashMap;
+use crate::{machine::INSTRUCTIONS, random};
+
+/// the pairs of instructions inserted by [`obfuscate`], each of which cancels itself out
+const CANCELLING: [&str; 3] = ["+-", "-+", "><"];
+
+/// the words inserted in between the instructions by [`obfuscate`], none of which contains an instruction
+const NOISE: [&str; 8] = ["bf", "nop", "tape", "cell", "loop", "jmp", "ptr", "xyzzy"];
+
+/// returns the instruction changing a cell by the sign of `delta`, repeated `count` times
+fn repeat(delta: i64, count: u64) -> String {
+    let instruction = if delta < 0 { "-" } else { "+" };
+    instruction.repeat(usize::try_from(count).unwrap_or(usize::MAX))
+}
+
+/// returns the shortest code found changing the current cell by `delta`,
+/// either directly or with a multiplication loop through the zeroed cell on its right,
+/// the pointer being back on the current cell afterwards
+fn adjust(delta: i64) -> String {
+    let magnitude = delta.unsigned_abs();
+    let mut shortest = repeat(delta, magnitude);
+
+    // `>` + `+` * factor + `[<` + (`+` or `-`) * times + `>-]<` followed by the remainder,
+    // which is subtracted when the loop overshoots
+    for factor in 2..=magnitude / 2 {
+        let times = magnitude / factor;
+
+        for times in [times, times + 1] {
+            let product = factor * times;
+            let remainder = if product > magnitude {
+                repeat(-delta, product - magnitude)
+            } else {
+                repeat(delta, magnitude - product)
+            };
+            let length = factor + times + remainder.len() as u64 + 6;
+
+            if length < shortest.len() as u64 {
+                shortest = format!(
+                    ">{}[<{}>-]<{remainder}",
+                    "+".repeat(usize::try_from(factor).unwrap_or(usize::MAX)),
+                    repeat(delta, times),
+                );
+            }
+        }
+    }
+    shortest
+}
+
+/// the greatest amount of `+` or `-` searched for either side of a multiplication loop by [`shortest_const`]
+const MAX_FACTOR: u64 = 1024;
+
+/// returns the length of the shortest run of `+` or `-` changing a cell by `delta` modulo `modulus`
+const fn run_length(delta: u64, modulus: u64) -> u64 {
+    let delta = delta % modulus;
+    if delta <= modulus - delta { delta } else { modulus - delta }
+}
+
+/// returns the shortest run of `+` or `-` changing a cell by `delta` modulo `modulus`
+fn run(delta: u64, modulus: u64) -> String {
+    let delta = delta % modulus;
+    if delta <= modulus - delta {
+        repeat(1, delta)
+    } else {
+        repeat(-1, modulus - delta)
+    }
+}
+
+/// returns code setting a zeroed cell to `value` in base `base`, multiplying the cell by `base` in place
+/// before adding every digit after the first, which does not need the cells to wrap around
+fn positional(value: u64, base: u64) -> String {
+    let mut digits = Vec::new();
+    let mut rest = value;
+    while rest > 0 {
+        digits.push(rest % base);
+        rest /= base;
+    }
+    let multiply = format!("[>{}<-]>[<+>-]<", repeat(1, base));
+
+    digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(idx, &digit)| if idx == 0 {
+            repeat(1, digit)
+        } else {
+            format!("{multiply}{}", repeat(1, digit))
+        })
+        .collect()
+}
+
+/// returns near-optimal code setting the current cell to `value`, given that it and the cell on its right are zeroed,
+/// the pointer being back on the current cell afterwards with the cell on its right zeroed again
+///
+/// the shortest code found is either a run of `+` or `-`, a multiplication loop followed by such a run,
+/// or for large values the digits of `value` in some base added in between multiplications of the cell;
+/// the code relies on the cells wrapping around past `max_cell_value` wherever that is shorter,
+/// and `value` is wrapped into `0..=max_cell_value` beforehand
+///
+/// ```rust
+/// use brainfuck_exe::codegen::shortest_const;
+///
+/// assert_eq!(shortest_const(255, 255), "-");
+/// assert_eq!(shortest_const(64, 255), ">++++++++[<++++++++>-]<");
+/// ```
+#[must_use]
+pub fn shortest_const(value: u32, max_cell_value: u32) -> String {
+    let modulus = u64::from(max_cell_value) + 1;
+    let value = u64::from(value) % modulus;
+
+    // the run is only built once nothing shorter is found, as it can be billions of instructions long
+    let mut shortest: Option<String> = None;
+    let mut length = run_length(value, modulus);
+
+    for base in 2..=16 {
+        let code = positional(value, base);
+        if (code.len() as u64) < length {
+            length = code.len() as u64;
+            shortest = Some(code);
+        }
+    }
+
+    // `>` + counter + `[<` + body + `>-]<` + remainder, where a counter of `-` makes the loop
+    // execute all the way around from `modulus - counter` down to `0`
+    for counter in 1..length.min(MAX_FACTOR) {
+        for (sign, iterations) in [(1, counter), (-1, modulus - counter)] {
+            for step in 1..length.min(MAX_FACTOR) {
+                if counter + step + 6 >= length {
+                    break;
+                }
+                let product = iterations % modulus * step % modulus;
+
+                for (body, added) in [(1, product), (-1, (modulus - product) % modulus)] {
+                    let remainder = (value + modulus - added) % modulus;
+                    let total = counter + step + 6 + run_length(remainder, modulus);
+
+                    if total < length {
+                        length = total;
+                        shortest = Some(format!(
+                            ">{}[<{}>-]<{}",
+                            repeat(sign, counter),
+                            repeat(body, step),
+                            run(remainder, modulus),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    shortest.unwrap_or_else(|| run(value, modulus))
+}
+
+/// generates a brainfuck program printing the provided text
+///
+/// every character is printed from the same cell, which is changed from the value of the previous character
+/// either directly or with a multiplication loop, whichever is shorter;
+/// characters above `255` require the program to be executed with a greater [`Brainfuck::max_cell_value`]
+///
+/// [`Brainfuck::max_cell_value`]: crate::Brainfuck::max_cell_value
+///
+/// ```rust
+/// use brainfuck_exe::{codegen::generate, Brainfuck};
+///
+/// let code = generate("Hello, World!");
+/// let mut output = Vec::new();
+/// Brainfuck::new(&code)
+///     .with_output_ref(&mut output)
+///     .execute()?;
+///
+/// assert_eq!(output, b"Hello, World!");
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+#[must_use]
+pub fn generate(text: &str) -> String {
+    // the cell on the right of the printed cell is kept zeroed for the multiplication loops
+    let mut code = String::new();
+    let mut value = 0;
+
+    for chr in text.chars() {
+        let target = i64::from(u32::from(chr));
+        code += &adjust(target - value);
+        code.push('.');
+        value = target;
+    }
+    code
+}
+
+/// rewrites the run of `count` `+` or `-` on a cell whose right neighbour is zeroed into a multiplication loop
+/// through the neighbour, with a factor picked by `random`
+fn loop_run(instruction: char, count: usize, random: &mut dyn FnMut() -> u32) -> String {
+    let factor = 2 + random() as usize % (count / 2).max(1);
+    let run = |count| instruction.to_string().repeat(count);
+
+    format!(">{}[<{}>-]<{}", "+".repeat(factor), run(count / factor), run(count % factor))
+}
+
+/// rewrites the runs of `+` and `-` of the prefix of the provided instructions without any loop or input,
+/// where the values of all the cells are known, into multiplication loops wherever the cell to the right is zeroed
+fn rewrite_constants(instructions: &[char], random: &mut dyn FnMut() -> u32) -> (String, usize) {
+    let mut cells = HashMap::<usize, i64>::new();
+    let mut pointer = 0_usize;
+    let mut rewritten = String::new();
+    let mut idx = 0;
+
+    while let Some(&instruction) = instructions.get(idx) {
+        match instruction {
+            '+' | '-' => {
+                let count = instructions[idx..]
+                    .iter()
+                    .take_while(|&&next| next == instruction)
+                    .count();
+                let delta = if instruction == '+' { count as i64 } else { -(count as i64) };
+
+                if count >= 2 && cells.get(&(pointer + 1)).is_none_or(|&cell| cell == 0) && random().is_multiple_of(2) {
+                    rewritten += &loop_run(instruction, count, random);
+                } else {
+                    rewritten += &instruction.to_string().repeat(count);
+                }
+                *cells.entry(pointer).or_default() += delta;
+                idx += count;
+                continue;
+            },
+            '>' => pointer += 1,
+            // moving left of the first cell is an error, which is left as is along with the rest of the program
+            '<' if pointer > 0 => pointer -= 1,
+            '.' => (),
+            _ => break,
+        }
+        rewritten.push(instruction);
+        idx += 1;
+    }
+    (rewritten, idx)
+}
+
+/// obfuscates the provided brainfuck program, preserving its behavior
+///
+/// the comments are stripped, the runs of `+` and `-` before the first loop or input are rewritten
+/// into multiplication loops through the zeroed cells to their right, pairs of instructions cancelling each other out
+/// are inserted in between the instructions, and the program is laid out on lines of random widths
+/// interspersed with words that are not instructions; the same `seed` always obfuscates a program the same way
+///
+/// the program is assumed to be executed with cells that wrap around and a memory array that can grow,
+/// as the inserted instructions may momentarily wrap a cell around or move past the last cell used
+///
+/// ```rust
+/// use brainfuck_exe::{codegen::obfuscate, Brainfuck};
+///
+/// let code = obfuscate("++++++++[>++++++++<-]>+.", 42);
+/// let mut output = Vec::new();
+/// Brainfuck::new(&code)
+///     .with_output_ref(&mut output)
+///     .execute()?;
+///
+/// assert_eq!(output, b"A");
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+#[must_use]
+pub fn obfuscate(code: &str, seed: u64) -> String {
+    let mut random = random::seeded(seed);
+    let instructions = code
+        .chars()
+        .filter(|&chr| INSTRUCTIONS.contains(chr))
+        .collect::<Vec<char>>();
+
+    let (prefix, rewritten) = rewrite_constants(&instructions, &mut random);
+    let mut obfuscated = String::new();
+
+    for instruction in prefix.chars().chain(instructions[rewritten..].iter().copied()) {
+        obfuscated.push(instruction);
+
+        match random() % 8 {
+            0 => obfuscated += CANCELLING[random() as usize % CANCELLING.len()],
+            1 => {
+                obfuscated.push(' ');
+                obfuscated += NOISE[random() as usize % NOISE.len()];
+                obfuscated.push(' ');
+            },
+            _ => (),
+        }
+    }
+
+    let mut laid_out = String::new();
+    let mut rest = obfuscated.as_str();
+    while !rest.is_empty() {
+        let width = (40 + random() as usize % 40).min(rest.len());
+        let (line, next) = rest.split_at(width);
+        laid_out += line.trim();
+        laid_out.push('\n');
+        rest = next;
+    }
+    laid_out
+}
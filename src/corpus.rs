@@ -0,0 +1,179 @@
+//! module containing the corpus runner, executing every program of a directory in parallel,
+//! see [`Brainfuck::execute_corpus`]
+
+use std::{
+    io::{self, Cursor},
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+use crate::{Brainfuck, Error, ExecutionInfo, Result};
+
+/// the extensions of the files executed by [`Brainfuck::execute_corpus`]
+pub const CORPUS_EXTENSIONS: [&str; 2] = ["bf", "b"];
+
+/// the outcome of a single program of a corpus
+#[derive(Debug)]
+pub struct CorpusEntry {
+    /// the path of the source file of the program
+    pub path: PathBuf,
+    /// everything the program wrote with the `.` operation
+    pub output: Vec<u8>,
+    /// the result of reading and executing the program
+    pub result: Result<ExecutionInfo>,
+    /// how long reading and executing the program took
+    pub elapsed: Duration,
+}
+
+impl CorpusEntry {
+    /// returns whether or not the program was cut short by the instruction limit, the timeout,
+    /// the memory or output limit, or the max amount of loop iterations
+    #[must_use]
+    pub const fn is_limited(&self) -> bool {
+        matches!(self.result, Err(
+            Error::MaxInstructionsExceeded { .. }
+            | Error::TimedOut { .. }
+            | Error::MemoryLimitExceeded { .. }
+            | Error::OutputLimitExceeded { .. }
+            | Error::LoopIterationsExceeded { .. }
+        ))
+    }
+}
+
+/// the outcomes of every program of a corpus, see [`Brainfuck::execute_corpus`]
+#[derive(Debug)]
+pub struct CorpusReport {
+    /// the outcome of every program, sorted by path
+    pub entries: Vec<CorpusEntry>,
+    /// how long executing the whole corpus took
+    pub elapsed: Duration,
+}
+
+impl CorpusReport {
+    /// returns the outcomes of the programs that finished
+    pub fn finished(&self) -> impl Iterator<Item = &CorpusEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.result.is_ok())
+    }
+
+    /// returns the outcomes of the programs cut short by the instruction limit or the timeout
+    pub fn limited(&self) -> impl Iterator<Item = &CorpusEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_limited())
+    }
+
+    /// returns the outcomes of the programs that failed otherwise
+    pub fn failed(&self) -> impl Iterator<Item = &CorpusEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.result.is_err() && !entry.is_limited())
+    }
+}
+
+/// collects every file with one of the [`CORPUS_EXTENSIONS`] in the provided directory and its subdirectories
+fn discover(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            discover(&path, paths)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| CORPUS_EXTENSIONS.iter().any(|&corpus| ext == corpus))
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// reads and executes the program of the provided file under the configuration of the provided interpreter,
+/// with an empty input
+fn execute_entry(path: PathBuf, config: &Brainfuck) -> CorpusEntry {
+    let start = Instant::now();
+    let mut output = Vec::new();
+    let result = Brainfuck::from_file(&path).and_then(|program| {
+        let mut interp = config.clone_config();
+        interp.code = program.code;
+        interp
+            .with_input(Cursor::new(Vec::new()))
+            .with_output_ref(&mut output)
+            .execute()
+    });
+    CorpusEntry { path, output, result, elapsed: start.elapsed() }
+}
+
+impl Brainfuck<'_> {
+    /// executes every program with one of the [`CORPUS_EXTENSIONS`] in the provided directory and its subdirectories,
+    /// spread across `jobs` threads including the current one, or as many as the available parallelism if `jobs` is `0`
+    ///
+    /// each program is executed with a copy of this interpreter's configuration (see [`Brainfuck::clone_config`]),
+    /// including its instruction limit and timeout, with an empty input and its output captured into
+    /// [`CorpusEntry::output`]
+    ///
+    /// # Errors
+    /// - [`Error::FileReadError`]: the directory or one of its subdirectories cannot be read;
+    ///   the errors of the programs themselves are collected into the report instead
+    pub fn execute_corpus<P: AsRef<Path>>(&self, dir: P, jobs: usize) -> Result<CorpusReport> {
+        let start = Instant::now();
+        let mut paths = Vec::new();
+        discover(dir.as_ref(), &mut paths).map_err(Error::FileReadError)?;
+        paths.sort();
+
+        let jobs = if jobs == 0 {
+            thread::available_parallelism().map_or(1, usize::from)
+        } else {
+            jobs
+        };
+        // the configurations are copied up front, as an interpreter cannot be shared across threads
+        let queue = Mutex::new(paths
+            .into_iter()
+            .enumerate()
+            .map(|(idx, path)| (idx, path, self.clone_config()))
+            .rev()
+            .collect::<Vec<(usize, PathBuf, Brainfuck<'static>)>>()
+        );
+        let results = Mutex::new(Vec::new());
+        let work = || loop {
+            let next = queue
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .pop();
+            let Some((idx, path, config)) = next else {
+                break;
+            };
+            let entry = execute_entry(path, &config);
+
+            results
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push((idx, entry));
+        };
+
+        thread::scope(|scope| {
+            // the current thread works through the queue as well, which is all it takes on targets without threads
+            for _ in 1..jobs {
+                if thread::Builder::new().spawn_scoped(scope, work).is_err() {
+                    break;
+                }
+            }
+            work();
+        });
+
+        let mut entries = results
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        entries.sort_by_key(|&(idx, _)| idx);
+
+        Ok(CorpusReport {
+            entries: entries
+                .into_iter()
+                .map(|(_, entry)| entry)
+                .collect(),
+            elapsed: start.elapsed(),
+        })
+    }
+}
@@ -0,0 +1,158 @@
+//! module containing [`Coverage`], an [`ExecutionObserver`] tracking which instructions of the code were executed
+
+use crate::{ExecutionObserver, HookCtx};
+
+/// the character used to underline the instructions that were never executed
+const DEAD_MARKER: char = '^';
+
+/// an [`ExecutionObserver`] tracking which instructions of the code were ever executed,
+/// useful for finding dead code
+///
+/// the coverage accumulates across executions, so a program can be executed with multiple inputs
+///
+/// ```rust
+/// use brainfuck_exe::{Brainfuck, Coverage};
+///
+/// let code = "+[-]>[+++]";
+/// let mut coverage = Coverage::new(code);
+///
+/// Brainfuck::new(code)
+///     .with_observer(&mut coverage)
+///     .execute()
+///     .unwrap();
+///
+/// assert_eq!(coverage.executed(), 6);
+/// assert_eq!(coverage.annotate(), "+[-]>[+++]\n      ^^^^\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Coverage {
+    /// the code split into its characters
+    code: Vec<char>,
+    /// whether or not each character of the code was executed
+    hits: Vec<bool>,
+}
+
+impl Coverage {
+    /// creates a new coverage tracker for the provided brainfuck code
+    ///
+    /// the code must be the same as the code of the interpreter it observes
+    #[must_use]
+    pub fn new<S: AsRef<str>>(code: S) -> Self {
+        let code = code.as_ref()
+            .chars()
+            .collect::<Vec<char>>();
+
+        Self {
+            hits: vec![false; code.len()],
+            code,
+        }
+    }
+
+    /// returns whether or not the provided code index is an instruction
+    fn is_instruction(&self, code_idx: usize) -> bool {
+        self.code
+            .get(code_idx)
+            .is_some_and(|chr| crate::machine::INSTRUCTIONS.contains(*chr))
+    }
+
+    /// returns whether or not the instruction at the provided code index was executed
+    #[must_use]
+    pub fn is_executed(&self, code_idx: usize) -> bool {
+        self.hits
+            .get(code_idx)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// returns the total amount of instructions in the code
+    #[must_use]
+    pub fn total(&self) -> usize {
+        (0..self.code.len())
+            .filter(|idx| self.is_instruction(*idx))
+            .count()
+    }
+
+    /// returns the amount of instructions in the code that were executed
+    #[must_use]
+    pub fn executed(&self) -> usize {
+        self.hits
+            .iter()
+            .filter(|hit| **hit)
+            .count()
+    }
+
+    /// returns the percentage of instructions in the code that were executed
+    ///
+    /// code without any instructions is considered fully covered
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn percentage(&self) -> f64 {
+        let total = self.total();
+
+        if total == 0 {
+            100.0
+        } else {
+            self.executed() as f64 / total as f64 * 100.0
+        }
+    }
+
+    /// returns the code indices of the instructions that were never executed
+    #[must_use]
+    pub fn dead_instructions(&self) -> Vec<usize> {
+        (0..self.code.len())
+            .filter(|idx| self.is_instruction(*idx) && !self.hits[*idx])
+            .collect()
+    }
+
+    /// returns the code annotated with the instructions that were never executed
+    ///
+    /// every line of the code containing dead instructions is followed by a line
+    /// underlining them with `^`
+    #[must_use]
+    pub fn annotate(&self) -> String {
+        let mut annotated = String::with_capacity(self.code.len() * 2);
+        let mut line = String::new();
+        let mut markers = String::new();
+
+        for (idx, chr) in self.code.iter().enumerate() {
+            if *chr == '\n' {
+                Self::push_line(&mut annotated, &line, &markers);
+                line.clear();
+                markers.clear();
+                continue;
+            }
+            line.push(*chr);
+            markers.push(
+                if self.is_instruction(idx) && !self.hits[idx] {
+                    DEAD_MARKER
+                } else if *chr == '\t' {
+                    '\t'
+                } else {
+                    ' '
+                }
+            );
+        }
+        Self::push_line(&mut annotated, &line, &markers);
+
+        annotated
+    }
+
+    /// helper function to push a line of code and its markers, if any, onto the annotated code
+    fn push_line(annotated: &mut String, line: &str, markers: &str) {
+        annotated.push_str(line);
+        annotated.push('\n');
+
+        if markers.contains(DEAD_MARKER) {
+            annotated.push_str(markers.trim_end());
+            annotated.push('\n');
+        }
+    }
+}
+
+impl ExecutionObserver for Coverage {
+    fn on_instruction(&mut self, ctx: &HookCtx) {
+        if let Some(hit) = self.hits.get_mut(ctx.code_idx) {
+            *hit = true;
+        }
+    }
+}
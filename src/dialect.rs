@@ -0,0 +1,82 @@
+//! module containing [`Dialect`], the extensions of brainfuck the interpreter can execute
+
+use crate::machine::INSTRUCTIONS;
+
+/// an extension of brainfuck adding instructions of its own, see [`crate::Brainfuck::with_dialect`]
+///
+/// the code of any dialect other than [`Dialect::Brainfuck`] is never optimized,
+/// as the optimizer would treat its instructions as comments
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Dialect {
+    /// plain brainfuck, treating every character other than `+ - < > . , [ ]` as a comment
+    #[default]
+    Brainfuck,
+    /// [pbrain](https://esolangs.org/wiki/Pbrain), adding procedures:
+    /// - `(` defines a procedure keyed by the value of the current cell, with the code up until its matching `)`
+    /// - `)` returns from the current procedure
+    /// - `:` calls the procedure keyed by the value of the current cell
+    Pbrain,
+    /// [Brainfork](https://esolangs.org/wiki/Brainfork), adding threads:
+    /// - `Y` forks the current thread, the child thread continues right after the `Y` with a copy of the memory array
+    ///   - the current cell is set to `0` in the parent thread
+    ///   - the pointer is moved one cell to the right in the child thread, and that cell is set to `1`
+    ///
+    /// the threads take turns executing a single step each, and the execution halts once every thread reached the end of the code;
+    /// infinite loops are not detected while more than one thread is running
+    Brainfork,
+    /// [Extended Brainfuck Type I](https://esolangs.org/wiki/Extended_Brainfuck), adding a single storage cell
+    /// starting at `0`, and bitwise operations:
+    /// - `@` ends the program
+    /// - `$` overwrites the storage with the value of the current cell
+    /// - `!` overwrites the current cell with the value of the storage
+    /// - `}` and `{` shift the bits of the current cell one to the right and to the left
+    /// - `~` flips every bit of the current cell, such that it becomes the max value of a cell minus its value
+    /// - `^`, `&` and `|` set the current cell to the bitwise XOR, AND and OR of it with the storage
+    ///
+    /// a value exceeding the max value of a cell wraps around, like `+` does
+    ExtendedTypeI,
+    /// [Boolfuck](https://esolangs.org/wiki/Boolfuck), where every cell is a single bit:
+    /// - `+` flips the current bit, and `-` and `.` are comments
+    /// - `,` reads a single bit from the input stream, the bytes being read least significant bit first
+    /// - `;` writes a single bit into the output stream, every 8 bits being written as a byte least significant bit first
+    ///
+    /// the max value of a cell is always `1`, and the bits still waiting to be written
+    /// at the end of the code are padded with `0` into a byte
+    Boolfuck,
+}
+
+impl Dialect {
+    /// returns the dialect of the provided kebab-case name, such as `extended-type-i`, as taken by the bindings
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "brainfuck" => Some(Self::Brainfuck),
+            "pbrain" => Some(Self::Pbrain),
+            "brainfork" => Some(Self::Brainfork),
+            "extended-type-i" => Some(Self::ExtendedTypeI),
+            "boolfuck" => Some(Self::Boolfuck),
+            _ => None,
+        }
+    }
+
+    /// returns every character that is an instruction in this dialect
+    #[must_use]
+    pub const fn instructions(self) -> &'static str {
+        match self {
+            Self::Brainfuck => INSTRUCTIONS,
+            Self::Pbrain => "+-<>.,[]():",
+            Self::Brainfork => "+-<>.,[]Y",
+            Self::ExtendedTypeI => "+-<>.,[]@$!}{~^&|",
+            Self::Boolfuck => "+<>,;[]",
+        }
+    }
+
+    /// returns the pairs of brackets that must be matched in this dialect, along with `[` and `]`
+    pub(crate) const fn brackets(self) -> &'static [(char, char)] {
+        match self {
+            Self::Brainfuck | Self::Brainfork | Self::ExtendedTypeI | Self::Boolfuck => &[('[', ']')],
+            Self::Pbrain => &[('[', ']'), ('(', ')')],
+        }
+    }
+}
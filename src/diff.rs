@@ -0,0 +1,126 @@
+//! module containing [`InfoDiff`], the differences between two executions, see [`ExecutionInfo::diff`]
+
+use std::fmt;
+use crate::ExecutionInfo;
+
+/// a cell whose final value differs between two executions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellDiff {
+    /// the index of the cell
+    pub index: usize,
+    /// the value of the cell in the first execution, `0` if it is past the end of its memory array
+    pub left: u32,
+    /// the value of the cell in the second execution, `0` if it is past the end of its memory array
+    pub right: u32,
+}
+
+/// the differences between the final states of two executions, see [`ExecutionInfo::diff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfoDiff {
+    /// every cell whose final value differs, in order
+    pub cells: Vec<CellDiff>,
+    /// the final pointer of the first and second execution
+    pub pointer: (usize, usize),
+    /// the amount of instructions executed by the first and second execution
+    pub instructions: (usize, usize),
+}
+
+/// returns the signed difference from `left` to `right`
+#[allow(clippy::cast_possible_wrap)]
+const fn delta(left: usize, right: usize) -> isize {
+    right.wrapping_sub(left) as isize
+}
+
+impl InfoDiff {
+    /// returns whether or not both executions ended with the same cells and pointer,
+    /// having executed the same amount of instructions
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+            && self.pointer.0 == self.pointer.1
+            && self.instructions.0 == self.instructions.1
+    }
+
+    /// returns how far the final pointer of the second execution is from the one of the first
+    #[must_use]
+    pub const fn pointer_delta(&self) -> isize {
+        delta(self.pointer.0, self.pointer.1)
+    }
+
+    /// returns how many more instructions the second execution executed than the first
+    #[must_use]
+    pub const fn instructions_delta(&self) -> isize {
+        delta(self.instructions.0, self.instructions.1)
+    }
+}
+
+impl fmt::Display for InfoDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no differences");
+        }
+        let mut lines = Vec::new();
+
+        if self.instructions.0 != self.instructions.1 {
+            lines.push(format!(
+                "instructions: {} → {} ({:+})",
+                self.instructions.0, self.instructions.1, self.instructions_delta(),
+            ));
+        }
+        if self.pointer.0 != self.pointer.1 {
+            lines.push(format!(
+                "pointer:      {} → {} ({:+})",
+                self.pointer.0, self.pointer.1, self.pointer_delta(),
+            ));
+        }
+        if !self.cells.is_empty() {
+            lines.push(format!(
+                "cells:        {}",
+                self.cells
+                    .iter()
+                    .map(|cell| format!("[{}] {} → {}", cell.index, cell.left, cell.right))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            ));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl ExecutionInfo {
+    /// compares the final state of this execution with the one of `other`,
+    /// such as the same program executed with different settings,
+    /// reporting which cells differ, how the pointer moved and the difference in instructions executed
+    ///
+    /// memory arrays of different lengths are compared as if the shorter one was padded with `0`
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{Brainfuck, CellDiff, EofBehavior};
+    ///
+    /// let code = ",>+";
+    /// let fallback = Brainfuck::new(code).with_input(&b""[..]).execute()?;
+    /// let max = Brainfuck::new(code).with_input(&b""[..]).with_eof(EofBehavior::Max).execute()?;
+    ///
+    /// let diff = fallback.diff(&max);
+    /// assert_eq!(diff.cells, [CellDiff { index: 0, left: 0, right: 255 }]);
+    /// assert_eq!(diff.instructions_delta(), 0);
+    /// assert!(!diff.is_empty());
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> InfoDiff {
+        let cells = (0..self.cells.len().max(other.cells.len()))
+            .filter_map(|index| {
+                let left = self.cells.get(index).copied().unwrap_or_default();
+                let right = other.cells.get(index).copied().unwrap_or_default();
+                (left != right).then_some(CellDiff { index, left, right })
+            })
+            .collect();
+
+        InfoDiff {
+            cells,
+            pointer: (self.pointer, other.pointer),
+            instructions: (self.instructions, other.instructions),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! module containing the bounded equivalence checker of two programs, see [`Brainfuck::check_equivalence`]
+
+use std::io::Cursor;
+use crate::{random, Brainfuck, Error, ExecutionInfo, Result};
+
+/// how two programs diverged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DivergenceKind {
+    /// the programs wrote different outputs, first differing at the byte `offset`
+    Output {
+        /// the index of the first byte that differs, or the length of the shorter output
+        offset: usize,
+        /// the output of the first program
+        left: Vec<u8>,
+        /// the output of the second program
+        right: Vec<u8>,
+    },
+    /// only one of the programs failed, or both failed with different kinds of errors,
+    /// [`None`] standing for a program that finished
+    Result {
+        /// the error of the first program
+        left: Option<String>,
+        /// the error of the second program
+        right: Option<String>,
+    },
+    /// the programs finished with different memory arrays, first differing at the cell `index`
+    Tape {
+        /// the index of the first cell that differs
+        index: usize,
+        /// the memory array of the first program
+        left: Vec<u32>,
+        /// the memory array of the second program
+        right: Vec<u32>,
+    },
+}
+
+/// the first input two programs diverged on, see [`Brainfuck::check_equivalence`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// the input the programs diverged on
+    pub input: Vec<u8>,
+    /// how they diverged
+    pub kind: DivergenceKind,
+}
+
+/// the outcome of checking two programs for equivalence, see [`Brainfuck::check_equivalence`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equivalence {
+    /// the amount of inputs the programs behaved the same on
+    pub compared: usize,
+    /// the amount of inputs either program reached the instruction limit or the timeout on,
+    /// which cannot be compared
+    pub inconclusive: usize,
+    /// the first divergence found, if any, after which no more inputs are compared
+    pub divergence: Option<Divergence>,
+}
+
+impl Equivalence {
+    /// returns whether or not the programs behaved the same on every input compared
+    #[must_use]
+    pub const fn is_equivalent(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+/// returns `count` random inputs of up to `max_len` bytes each, the same ones for the same seed,
+/// the first of them always being empty
+#[must_use]
+pub fn fuzz_inputs(count: usize, max_len: usize, seed: u64) -> Vec<Vec<u8>> {
+    let mut random = random::seeded(seed);
+
+    (0..count)
+        .map(|idx| {
+            let len = if idx == 0 { 0 } else { random() as usize % (max_len + 1) };
+            (0..len)
+                .map(|_| random().to_le_bytes()[0])
+                .collect()
+        })
+        .collect()
+}
+
+/// returns the memory array without its trailing zeroed cells, which depend on how much memory was allocated
+fn trimmed(cells: &[u32]) -> &[u32] {
+    let len = cells
+        .iter()
+        .rposition(|&cell| cell != 0)
+        .map_or(0, |idx| idx + 1);
+    &cells[..len]
+}
+
+/// executes a copy of the configuration of the provided interpreter with the provided code and input,
+/// returning its output and result
+fn run(interp: &Brainfuck, code: &str, input: &[u8]) -> (Vec<u8>, Result<ExecutionInfo>) {
+    let mut output = Vec::new();
+    let mut copy = interp.clone_config();
+    copy.code = code.to_string();
+
+    let result = copy
+        .with_input(Cursor::new(input.to_vec()))
+        .with_output_ref(&mut output)
+        .execute();
+
+    (output, result)
+}
+
+/// compares the outputs and results of two executions, returning how they diverged, if they did
+fn compare(
+    (left_output, left): (Vec<u8>, Result<ExecutionInfo>),
+    (right_output, right): (Vec<u8>, Result<ExecutionInfo>),
+) -> Option<DivergenceKind> {
+    if left_output != right_output {
+        let offset = left_output
+            .iter()
+            .zip(&right_output)
+            .position(|(left, right)| left != right)
+            .unwrap_or_else(|| left_output.len().min(right_output.len()));
+
+        return Some(DivergenceKind::Output { offset, left: left_output, right: right_output });
+    }
+    match (left, right) {
+        (Ok(left), Ok(right)) => {
+            let (left, right) = (trimmed(&left.cells), trimmed(&right.cells));
+            let index = (0..left.len().max(right.len()))
+                .find(|&idx| left.get(idx).unwrap_or(&0) != right.get(idx).unwrap_or(&0))?;
+
+            Some(DivergenceKind::Tape { index, left: left.to_vec(), right: right.to_vec() })
+        },
+        (Err(left), Err(right)) if std::mem::discriminant(&left) == std::mem::discriminant(&right) => None,
+        (left, right) => Some(DivergenceKind::Result {
+            left: left.err().map(|err| err.to_string()),
+            right: right.err().map(|err| err.to_string()),
+        }),
+    }
+}
+
+/// returns whether or not the provided result cannot be compared, as it was cut short by a limit
+const fn is_inconclusive(result: &Result<ExecutionInfo>) -> bool {
+    matches!(result, Err(
+        Error::MaxInstructionsExceeded { .. }
+        | Error::TimedOut { .. }
+        | Error::MemoryLimitExceeded { .. }
+        | Error::OutputLimitExceeded { .. }
+        | Error::LoopIterationsExceeded { .. }
+    ))
+}
+
+impl Brainfuck<'_> {
+    /// executes this program and the provided code with every provided input under this interpreter's configuration,
+    /// comparing their outputs, whether they fail, and their memory arrays once they finish
+    ///
+    /// each run uses a copy of this interpreter's configuration (see [`Brainfuck::clone_config`]);
+    /// the inputs either program reaches the instruction limit or the timeout on are not compared,
+    /// and the checking stops at the first divergence found
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{equivalence::{fuzz_inputs, DivergenceKind}, Brainfuck};
+    ///
+    /// let interp = Brainfuck::new(",[.,]");
+    /// // the same program, only with comments and instructions cancelling each other out
+    /// assert!(interp.check_equivalence("read , [ print . +- read , ]", fuzz_inputs(16, 8, 0)).is_equivalent());
+    ///
+    /// // the memory arrays differ once the program reads anything
+    /// let checked = interp.check_equivalence(",[.>,]", ["", "ab"]);
+    /// assert_eq!(checked.compared, 1);
+    /// assert!(matches!(checked.divergence.unwrap().kind, DivergenceKind::Tape { index: 0, .. }));
+    /// ```
+    pub fn check_equivalence<I, B>(&self, other: &str, inputs: I) -> Equivalence
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u8]>,
+    {
+        let mut equivalence = Equivalence { compared: 0, inconclusive: 0, divergence: None };
+
+        for input in inputs {
+            let input = input.as_ref();
+            let (left, right) = (run(self, &self.code, input), run(self, other, input));
+            if is_inconclusive(&left.1) || is_inconclusive(&right.1) {
+                equivalence.inconclusive += 1;
+                continue;
+            }
+            if let Some(kind) = compare(left, right) {
+                equivalence.divergence = Some(Divergence { input: input.to_vec(), kind });
+                break;
+            }
+            equivalence.compared += 1;
+        }
+        equivalence
+    }
+}
@@ -3,37 +3,220 @@
 use std::{
     fmt,
     io::Error as IoError,
+    time::Duration,
 };
+use crate::{ExecutionInfo, Position, Span};
+
+/// the state of the execution at the point a runtime error occured
+#[derive(Debug, Clone)]
+pub struct RuntimeContext {
+    /// the code index of the instruction at which the error occured
+    pub code_idx: usize,
+    /// the position of that instruction in the code
+    pub position: Position,
+    /// the pointer index at the time of the error
+    pub pointer: usize,
+    /// information on the execution up until the point of the error
+    pub info: ExecutionInfo,
+}
 
 /// Error enum for brainfuck runtime errors
 #[derive(Debug)]
 pub enum Error {
-    /// returned when the amount of `[` in the code does not equal the amount of `]`
+    /// returned when the memory size was set to `0`, leaving no cell for the pointer to point at
+    ZeroMemorySize,
+    /// returned when the max value of a cell was set to `0`, leaving every cell stuck at `0`
+    ZeroMaxCellValue,
+    /// returned when the pointer of the initial state is past the cells the growable memory array can take,
+    /// within the set limit of bytes or at all, see [`crate::Brainfuck::with_initial_state`]
+    InitialPointerOutOfBounds {
+        /// the pointer of the initial state
+        pointer: usize,
+    },
+    /// returned when the code contains an unmatched `[` or a stray `]`,
+    /// or another unmatched bracket of the set [`crate::Dialect`]
     MismatchedBrackets {
-        /// the amount of `[` in the code
+        /// the amount of opening brackets of the same kind as `bracket` in the code
         opening: usize,
-        /// the amount of `]` in the code
+        /// the amount of closing brackets of the same kind as `bracket` in the code
         closing: usize,
+        /// the first unmatched bracket, such as `[` or `]`
+        bracket: char,
+        /// the position of the first unmatched bracket in the code
+        position: Position,
+    },
+    /// returned when the code contains a character that is neither an instruction nor whitespace,
+    /// only if `strict_syntax` is enabled
+    UnexpectedCharacter {
+        /// the first unexpected character
+        character: char,
+        /// the position of the unexpected character in the code
+        position: Position,
     },
+    /// returned when translating code from another language into brainfuck,
+    /// and it contains a token with no brainfuck equivalent
+    InvalidToken {
+        /// the invalid token
+        token: String,
+        /// the position of the invalid token in the code
+        position: Position,
+    },
+    /// returned when the tokens of a [`crate::translate::TokenTable`] are invalid
+    InvalidTokenTable(
+        /// the reason the tokens are invalid
+        String
+    ),
     /// propogated from opening or reading files for the brainfuck source code
-    /// to be interpreted, in [`crate::Brainfuck::from_file`]
+    /// to be interpreted, in [`crate::Brainfuck::from_file`] and [`crate::Brainfuck::from_reader`],
+    /// or from fetching it in `Brainfuck::from_url`
     FileReadError(
         /// the propogated error
         IoError
     ),
-    /// propogated from `.` and `,` I/O operations
+    /// propogated from opening the file to write the output into, in [`crate::Brainfuck::with_output_path`]
+    FileWriteError(
+        /// the propogated error
+        IoError
+    ),
+    /// propogated from I/O operations outside of any instruction
     IoError(
         /// the propogated error
         IoError
     ),
+    /// propogated from the `.` operation writing into the output stream
+    ///
+    /// failing to read from the input stream in the `,` operation is treated as EOF instead
+    OutputError {
+        /// the propogated error
+        source: IoError,
+        /// the state of the execution at the failing `.` operation
+        context: Box<RuntimeContext>,
+    },
     /// returned when the amount of instructions executed
     /// reaches the limit of instructions to be executed that is set
-    MaxInstructionsExceeded(
+    MaxInstructionsExceeded {
         /// the instructions limit that was set
-        usize
+        limit: usize,
+        /// the state of the execution at the instruction exceeding the limit
+        context: Box<RuntimeContext>,
+    },
+    /// returned when the execution time exceeds the set timeout
+    TimedOut {
+        /// the timeout that was set
+        timeout: Duration,
+        /// the state of the execution at the point it timed out
+        context: Box<RuntimeContext>,
+    },
+    /// returned when the memory array takes more bytes than the limit that is set,
+    /// see [`crate::ExecutionLimits::memory_bytes`]
+    MemoryLimitExceeded {
+        /// the limit of bytes that was set
+        limit: usize,
+        /// the state of the execution at the instruction growing the memory array past the limit
+        context: Box<RuntimeContext>,
+    },
+    /// returned when the `.` operation would write more bytes than the limit that is set,
+    /// the bytes past the limit never being written
+    OutputLimitExceeded {
+        /// the limit of bytes that was set
+        limit: usize,
+        /// the state of the execution at the `.` operation exceeding the limit
+        context: Box<RuntimeContext>,
+    },
+    /// returned when the code nests more loops within each other than the limit that is set,
+    /// before it is executed
+    LoopDepthExceeded {
+        /// the limit of nested loops that was set
+        limit: usize,
+        /// the position of the first `[` nested past the limit
+        position: Position,
+    },
+    /// returned when infinite loop detection is enabled, and the program reached the exact same state twice
+    /// without any I/O in between, meaning it would never terminate
+    InfiniteLoop(
+        /// the state of the execution at the start of the repeated loop iteration
+        Box<RuntimeContext>
+    ),
+    /// returned when a loop iterates more times than the max amount of loop iterations that is set,
+    /// see [`crate::Brainfuck::with_max_loop_iterations`]
+    LoopIterationsExceeded {
+        /// the max amount of iterations that was set
+        limit: usize,
+        /// the span of the loop, from its `[` up to and including its `]`
+        span: Span,
+        /// the state of the execution at the `[` starting the iteration past the limit
+        context: Box<RuntimeContext>,
+    },
+    /// returned when a copy of an interpreter made with [`crate::Brainfuck::clone_config`] executes code
+    /// using an instruction whose callback could not be copied, such as a custom instruction,
+    /// before the code is executed
+    MissingCallback {
+        /// the instruction, such as `?` with a generator set by [`crate::Brainfuck::with_rng`]
+        opcode: char,
+        /// the position of the first use of the instruction in the code
+        position: Position,
+    },
+    /// returned when a pbrain program calls a procedure that was never defined, see [`crate::Dialect::Pbrain`]
+    UndefinedProcedure {
+        /// the key of the procedure, the value of the current cell at the `:` operation
+        id: u32,
+        /// the state of the execution at the failing `:` operation
+        context: Box<RuntimeContext>,
+    },
+    /// returned when `+` or `-` would have wrapped the current cell around while wrapping is disabled,
+    /// see [`crate::Brainfuck::with_wrapping`]
+    CellOverflow(
+        /// the state of the execution at the failing operation
+        Box<RuntimeContext>
+    ),
+    /// returned when the execution was cancelled through the set cancel token,
+    /// or aborted by the set instruction hook
+    Cancelled(
+        /// the state of the execution at the point it was cancelled
+        Box<RuntimeContext>
+    ),
+    /// returned when a line evaluated by a [`crate::Session`] starts with `:` but is not a meta-command
+    UnknownCommand(
+        /// the unknown meta-command, such as `:foo`
+        String
     ),
 }
 
+impl Error {
+    /// returns the state of the execution at the point of the error,
+    /// or [`None`] if it did not occur during the execution
+    #[must_use]
+    pub fn context(&self) -> Option<&RuntimeContext> {
+        match self {
+            Self::OutputError { context, .. }
+            | Self::MaxInstructionsExceeded { context, .. }
+            | Self::TimedOut { context, .. }
+            | Self::MemoryLimitExceeded { context, .. }
+            | Self::OutputLimitExceeded { context, .. }
+            | Self::InfiniteLoop(context)
+            | Self::LoopIterationsExceeded { context, .. }
+            | Self::UndefinedProcedure { context, .. }
+            | Self::CellOverflow(context)
+            | Self::Cancelled(context) => Some(context),
+            _ => None,
+        }
+    }
+
+    /// returns the position in the code the error points to, if any
+    #[must_use]
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            Self::MismatchedBrackets { position, .. }
+            | Self::UnexpectedCharacter { position, .. }
+            | Self::InvalidToken { position, .. }
+            | Self::LoopDepthExceeded { position, .. }
+            | Self::MissingCallback { position, .. } => Some(*position),
+            _ => self.context()
+                .map(|context| context.position),
+        }
+    }
+}
+
 impl From<IoError> for Error {
     fn from(err: IoError) -> Self {
         Self::IoError(err)
@@ -42,23 +225,98 @@ impl From<IoError> for Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = self.context()
+            .map(|context| format!(
+                " (at line {}, column {}, with the pointer at cell {})",
+                context.position.line, context.position.column, context.pointer,
+            ))
+            .unwrap_or_default();
+
         f.write_str(
             match self {
-                Self::MismatchedBrackets { opening, closing } =>
-                    format!("Mismatched brackets; there were {opening} '[' found but only {closing} ']' found"),
-                Self::FileReadError(err) =>
-                    format!("Failed to read the provided file:\n{err}"),
-                Self::IoError(err) =>
-                    format!("An I/O error occured:\n{err}"),
-                Self::MaxInstructionsExceeded(cap) =>
-                    format!("The amount of instructions executed has reached the set limit of `{cap}`"),
+                Self::ZeroMemorySize =>
+                    String::from("The memory size must be at least `1`"),
+                Self::ZeroMaxCellValue =>
+                    String::from("The max value of a cell must be at least `1`"),
+                Self::InitialPointerOutOfBounds { pointer } =>
+                    format!("The initial pointer `{pointer}` is past the cells the memory array can take"),
+                Self::MismatchedBrackets { opening, closing, bracket, position } => {
+                    let (open, close) = if matches!(bracket, '(' | ')') { ('(', ')') } else { ('[', ']') };
+
+                    format!(
+                        "Mismatched brackets; unmatched '{bracket}' at line {}, column {} (byte offset {}), \
+                        there were {opening} '{open}' and {closing} '{close}' found",
+                        position.line, position.column, position.offset,
+                    )
+                },
+                Self::UnexpectedCharacter { character, position } =>
+                    format!(
+                        "Unexpected character {character:?} at line {}, column {} (byte offset {})",
+                        position.line, position.column, position.offset,
+                    ),
+                Self::InvalidToken { token, position } =>
+                    format!(
+                        "Invalid token `{token}` at line {}, column {} (byte offset {})",
+                        position.line, position.column, position.offset,
+                    ),
+                Self::InvalidTokenTable(reason) =>
+                    format!("Invalid token table: {reason}"),
+                Self::FileReadError(_) =>
+                    String::from("Failed to read the provided file"),
+                Self::FileWriteError(_) =>
+                    String::from("Failed to open the provided file for writing"),
+                Self::IoError(_) =>
+                    String::from("An I/O error occured"),
+                Self::OutputError { .. } =>
+                    format!("Failed to write the output{location}"),
+                Self::MaxInstructionsExceeded { limit, .. } =>
+                    format!("The amount of instructions executed has reached the set limit of `{limit}`{location}"),
+                Self::TimedOut { timeout, .. } =>
+                    format!("The execution has exceeded the set timeout of `{timeout:?}`{location}"),
+                Self::MemoryLimitExceeded { limit, .. } =>
+                    format!("The memory array has grown past the set limit of `{limit}` bytes{location}"),
+                Self::OutputLimitExceeded { limit, .. } =>
+                    format!("The output has exceeded the set limit of `{limit}` bytes{location}"),
+                Self::LoopDepthExceeded { limit, position } =>
+                    format!(
+                        "Loops are nested deeper than the set limit of `{limit}` at line {}, column {} (byte offset {})",
+                        position.line, position.column, position.offset,
+                    ),
+                Self::LoopIterationsExceeded { limit, .. } =>
+                    format!("A loop has iterated more than the set limit of `{limit}` times{location}"),
+                Self::InfiniteLoop(_) =>
+                    format!("The program is stuck in an infinite loop{location}"),
+                Self::MissingCallback { opcode, position } =>
+                    format!(
+                        "The `{opcode}` instruction at line {}, column {} (byte offset {}) has no callback, \
+                        as it could not be copied from the interpreter this one is a copy of",
+                        position.line, position.column, position.offset,
+                    ),
+                Self::UndefinedProcedure { id, .. } =>
+                    format!("The procedure `{id}` was called without being defined{location}"),
+                Self::CellOverflow(_) =>
+                    format!("The current cell would have wrapped around while wrapping is disabled{location}"),
+                Self::Cancelled(context) =>
+                    format!("The execution was cancelled after `{}` instructions{location}", context.info.instructions),
+                Self::UnknownCommand(command) =>
+                    format!("Unknown command `{command}`, enter `:help` for the meta-commands"),
             }
             .as_str()
         )
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::FileReadError(err)
+            | Self::FileWriteError(err)
+            | Self::IoError(err)
+            | Self::OutputError { source: err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
 
 /// result type alias for [`Error`]
 pub type Result<T, E = Error> = std::result::Result<T, E>;
\ No newline at end of file
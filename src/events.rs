@@ -0,0 +1,117 @@
+//! module containing [`Events`], an iterator over the events of a program execution, see [`Brainfuck::iter_events`]
+
+use std::collections::VecDeque;
+use crate::{Action, Brainfuck, ExecutionInfo, HookCtx, Machine, Result};
+
+/// an event of a program execution, yielded by [`Events`]
+#[derive(Debug, Clone)]
+pub enum ExecutionEvent {
+    /// an instruction was executed, the context describing the state right before it was
+    Instruction(HookCtx),
+    /// a `.` operation wrote this byte, a [`char`] above `127` being written as several UTF-8 encoded bytes
+    Output(u8),
+    /// a `,` operation requested input, which is read once the next event is requested
+    InputRequested,
+    /// the end of the code has been reached, being the last event
+    Halted(ExecutionInfo),
+}
+
+/// a lazy iterator over the events of a program execution, created with [`Brainfuck::iter_events`]
+///
+/// the program only executes while events are requested, so it can be paused, throttled or stopped
+/// at any point with the standard iterator combinators; it ends after [`ExecutionEvent::Halted`] or an error
+pub struct Events<'b, 'a> {
+    /// the interpreter holding the configuration of this execution
+    interp: &'b mut Brainfuck<'a>,
+    /// the state of the execution
+    machine: Machine,
+    /// the events produced by the last step that were not yielded yet
+    pending: VecDeque<ExecutionEvent>,
+    /// whether or not the execution halted or failed
+    finished: bool,
+}
+
+impl Iterator for Events<'_, '_> {
+    type Item = Result<ExecutionEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            if self.finished {
+                return None;
+            }
+            // the input of the last `,` operation is only read now that the event after its request is asked for
+            if self.machine.is_awaiting_input() {
+                let value = self.interp.read_input();
+                let value = self.interp.resolve_input(value, &self.machine);
+                self.machine.write_input(value);
+            }
+            let ctx = self.machine.hook_ctx();
+
+            let action = match self.interp.advance(&mut self.machine) {
+                Ok(action) => action,
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err));
+                },
+            };
+            self.pending.extend(ctx.map(ExecutionEvent::Instruction));
+
+            match action {
+                Action::Continue => (),
+                Action::Output(_) if self.interp.discard_output => (),
+                Action::Output(chr) => {
+                    let mut buf = [0; 4];
+                    let bytes = chr.encode_utf8(&mut buf).as_bytes();
+                    self.interp.notify_output(bytes);
+
+                    self.pending.extend(bytes.iter().copied().map(ExecutionEvent::Output));
+                },
+                Action::Input => self.pending.push_back(ExecutionEvent::InputRequested),
+                Action::Halt => {
+                    self.finished = true;
+                    self.pending.push_back(ExecutionEvent::Halted(self.machine.info()));
+                },
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+impl<'a> Brainfuck<'a> {
+    /// starts a lazy execution of the code, returning an iterator over its events,
+    /// such as every instruction executed and every byte written
+    ///
+    /// the `,` operation reads from the configured input stream, or [`std::io::stdin`], like [`Brainfuck::execute`];
+    /// the bytes written by the `.` operation however are only yielded as [`ExecutionEvent::Output`],
+    /// the output stream never being written into
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{Brainfuck, ExecutionEvent};
+    ///
+    /// let mut interp = Brainfuck::new("+[+.]");
+    /// let output = interp
+    ///     .iter_events()?
+    ///     .filter_map(|event| match event {
+    ///         Ok(ExecutionEvent::Output(byte)) => Some(byte),
+    ///         _ => None,
+    ///     })
+    ///     .take(3)
+    ///     .collect::<Vec<u8>>();
+    ///
+    /// assert_eq!(output, [2, 3, 4]);
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// the same as [`Brainfuck::start`], the errors of the execution itself being yielded as the last item
+    pub fn iter_events(&mut self) -> Result<Events<'_, 'a>> {
+        let machine = self.begin()?;
+
+        Ok(Events {
+            interp: self,
+            machine,
+            pending: VecDeque::new(),
+            finished: false,
+        })
+    }
+}
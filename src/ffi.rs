@@ -0,0 +1,227 @@
+//! module containing the C ABI for embedding the interpreter into non-Rust applications, see [`bf_new`]
+//!
+//! requires the `ffi` feature, the matching header being `include/brainfuck_exe.h`
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    io::Cursor,
+    ptr,
+    time::Duration,
+};
+use crate::{Brainfuck, Dialect, EofBehavior, OptimizationLevel};
+
+/// the status returned by the functions of the C ABI
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BfStatus {
+    /// the call succeeded
+    Ok = 0,
+    /// a pointer that must not be null was null, or a string was not valid UTF-8
+    InvalidArgument = 1,
+    /// the name passed to [`bf_set_option`] is not an option
+    UnknownOption = 2,
+    /// the value passed to [`bf_set_option`] is invalid for the option
+    InvalidValue = 3,
+    /// the execution failed, see [`bf_get_error`] for the reason
+    ExecutionFailed = 4,
+}
+
+/// an interpreter owned by C code, created with [`bf_new`] and destroyed with [`bf_free`]
+///
+/// it keeps the output of the last execution and the message of the last error
+pub struct BfInterpreter {
+    /// the configured interpreter, a copy of which is executed every time
+    interp: Brainfuck<'static>,
+    /// the seed of the `?` instruction, which a copy of the interpreter does not keep
+    seed: Option<u64>,
+    /// the output of the last execution
+    output: Vec<u8>,
+    /// the message of the last error, if the last call failed
+    error: Option<CString>,
+}
+
+impl BfInterpreter {
+    /// records the provided error message, returning the provided status
+    fn fail(&mut self, status: BfStatus, message: &str) -> BfStatus {
+        self.error = CString::new(message.replace('\0', "")).ok();
+        status
+    }
+
+    /// sets the option of the provided name, named after the keys of the configuration file of the CLI
+    fn set_option(&mut self, name: &str, value: &str) -> Result<(), BfStatus> {
+        /// parses the value as the type of the option
+        fn parse<T: std::str::FromStr>(value: &str) -> Result<T, BfStatus> {
+            value.parse().map_err(|_| BfStatus::InvalidValue)
+        }
+        let interp = &mut self.interp;
+
+        match name {
+            "max-cell-value" => interp.max_cell_value = parse(value)?,
+            "memory-size" => interp.memory_size = Some(parse(value)?),
+            "instructions-limit" => interp.instructions_limit = Some(parse(value)?),
+            "timeout" => interp.timeout = Some(Duration::from_millis(parse(value)?)),
+            "fallback-char" => interp.fallback_input = Some(parse(value)?),
+            "no-wrap" => interp.wrapping = !parse::<bool>(value)?,
+            "strict" => interp.strict_syntax = parse(value)?,
+            "detect-infinite-loops" => interp.detect_infinite_loops = parse(value)?,
+            "seed" => self.seed = Some(parse(value)?),
+            "eof" => interp.eof = EofBehavior::from_name(value).ok_or(BfStatus::InvalidValue)?,
+            "dialect" => interp.dialect = Dialect::from_name(value).ok_or(BfStatus::InvalidValue)?,
+            "opt-level" => interp.optimization = match value {
+                "0" => OptimizationLevel::None,
+                "1" => OptimizationLevel::Basic,
+                "2" => OptimizationLevel::Aggressive,
+                _ => return Err(BfStatus::InvalidValue),
+            },
+            _ => return Err(BfStatus::UnknownOption),
+        }
+        Ok(())
+    }
+}
+
+/// reads the provided C string as UTF-8, or returns [`None`] if it is null or invalid
+///
+/// # Safety
+/// `string` must be null or point to a valid NUL-terminated string
+unsafe fn read_str<'s>(string: *const c_char) -> Option<&'s str> {
+    if string.is_null() {
+        return None;
+    }
+    unsafe { CStr::from_ptr(string) }
+        .to_str()
+        .ok()
+}
+
+/// creates an interpreter of the provided NUL-terminated code, returning null if it is null or not valid UTF-8
+///
+/// # Safety
+/// `code` must be null or point to a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn bf_new(code: *const c_char) -> *mut BfInterpreter {
+    let Some(code) = (unsafe { read_str(code) }) else {
+        return ptr::null_mut();
+    };
+    Box::into_raw(Box::new(BfInterpreter {
+        interp: Brainfuck::new(code),
+        seed: None,
+        output: Vec::new(),
+        error: None,
+    }))
+}
+
+/// sets an option of the interpreter from its NUL-terminated name and value,
+/// named after the keys of the configuration file of the CLI, such as `max-cell-value` set to `65535`:
+/// - `max-cell-value`, `memory-size`, `instructions-limit`, `timeout` (in milliseconds) and `seed` as integers
+/// - `fallback-char` as a single character
+/// - `no-wrap`, `strict` and `detect-infinite-loops` as `true` or `false`
+/// - `eof` as one of `fallback`, `max` or `unchanged`
+/// - `opt-level` as one of `0`, `1` or `2`
+/// - `dialect` as one of `brainfuck`, `pbrain`, `brainfork`, `extended-type-i` or `boolfuck`
+///
+/// # Safety
+/// `interp` must be null or have been returned by [`bf_new`] and not yet freed,
+/// and `name` and `value` must be null or point to valid NUL-terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn bf_set_option(
+    interp: *mut BfInterpreter,
+    name: *const c_char,
+    value: *const c_char,
+) -> BfStatus {
+    let Some(interp) = (unsafe { interp.as_mut() }) else {
+        return BfStatus::InvalidArgument;
+    };
+    let (Some(name), Some(value)) = (unsafe { read_str(name) }, unsafe { read_str(value) }) else {
+        return interp.fail(BfStatus::InvalidArgument, "the name and the value must be valid UTF-8");
+    };
+
+    match interp.set_option(name, value) {
+        Ok(()) => {
+            interp.error = None;
+            BfStatus::Ok
+        },
+        Err(BfStatus::UnknownOption) => interp.fail(BfStatus::UnknownOption, &format!("`{name}` is not an option")),
+        Err(status) => interp.fail(status, &format!("`{value}` is not a valid value for `{name}`")),
+    }
+}
+
+/// executes the program with the provided input of `input_len` bytes, replacing the output of the last execution
+///
+/// # Safety
+/// `interp` must be null or have been returned by [`bf_new`] and not yet freed,
+/// and `input` must be null or point to `input_len` readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn bf_execute(
+    interp: *mut BfInterpreter,
+    input: *const u8,
+    input_len: usize,
+) -> BfStatus {
+    let Some(interp) = (unsafe { interp.as_mut() }) else {
+        return BfStatus::InvalidArgument;
+    };
+    let input = if input.is_null() || input_len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(input, input_len) }.to_vec()
+    };
+
+    let mut output = Vec::new();
+    let mut copy = interp.interp.clone_config();
+    if let Some(seed) = interp.seed {
+        copy = copy.with_random_seed(seed);
+    }
+    let result = copy
+        .with_input(Cursor::new(input))
+        .with_output_ref(&mut output)
+        .execute();
+    interp.output = output;
+
+    match result {
+        Ok(_) => {
+            interp.error = None;
+            BfStatus::Ok
+        },
+        Err(err) => interp.fail(BfStatus::ExecutionFailed, &err.to_string()),
+    }
+}
+
+/// returns the output of the last execution, writing its length into `len`,
+/// the bytes being valid until the next execution or until the interpreter is freed
+///
+/// returns null, and writes `0` into `len`, if `interp` is null
+///
+/// # Safety
+/// `interp` must be null or have been returned by [`bf_new`] and not yet freed,
+/// and `len` must be null or point to a writable `size_t`
+#[no_mangle]
+pub unsafe extern "C" fn bf_get_output(interp: *const BfInterpreter, len: *mut usize) -> *const u8 {
+    let (output, output_len) = unsafe { interp.as_ref() }
+        .map_or((ptr::null(), 0), |interp| (interp.output.as_ptr(), interp.output.len()));
+
+    if let Some(len) = unsafe { len.as_mut() } {
+        *len = output_len;
+    }
+    output
+}
+
+/// returns the NUL-terminated message of the error of the last call, or null if it succeeded,
+/// the message being valid until the next call or until the interpreter is freed
+///
+/// # Safety
+/// `interp` must be null or have been returned by [`bf_new`] and not yet freed
+#[no_mangle]
+pub unsafe extern "C" fn bf_get_error(interp: *const BfInterpreter) -> *const c_char {
+    unsafe { interp.as_ref() }
+        .and_then(|interp| interp.error.as_ref())
+        .map_or(ptr::null(), |error| error.as_ptr())
+}
+
+/// frees the interpreter, doing nothing if it is null
+///
+/// # Safety
+/// `interp` must be null or have been returned by [`bf_new`] and not yet freed
+#[no_mangle]
+pub unsafe extern "C" fn bf_free(interp: *mut BfInterpreter) {
+    if !interp.is_null() {
+        drop(unsafe { Box::from_raw(interp) });
+    }
+}
@@ -0,0 +1,89 @@
+//! module containing [`FlushPolicy`], deciding when the output written by the `.` operation is flushed
+
+use std::io::{self, BufWriter, Write};
+
+/// when the output written by the `.` operation is flushed into the output stream or [`std::io::stdout`]
+///
+/// the output is buffered for the whole execution, and flushed at its end whatever the policy,
+/// including when it fails
+///
+/// ```rust
+/// use brainfuck_exe::{Brainfuck, FlushPolicy};
+///
+/// let mut output = Vec::new();
+/// Brainfuck::new("++++++++++[>+++++++>+<<-]>++.>.")
+///     .with_flush_policy(FlushPolicy::OnNewline)
+///     .with_output_ref(&mut output)
+///     .execute()?;
+/// assert_eq!(output, b"H\n");
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FlushPolicy {
+    /// flushes after every `.` operation, so that the output appears as soon as it is written,
+    /// the slowest policy for programs writing a lot
+    #[default]
+    EveryWrite,
+    /// flushes whenever a newline is written, like a terminal would,
+    /// keeping interactive programs prompting line by line responsive
+    OnNewline,
+    /// flushes whenever at least the provided amount of bytes were written since the last flush
+    EveryNBytes(usize),
+    /// flushes only at the end of the execution, the fastest policy
+    AtEnd,
+}
+
+impl FlushPolicy {
+    /// returns whether or not to flush after the provided bytes were written,
+    /// `pending` being the amount of bytes written since the last flush, which it accounts the bytes to
+    pub(crate) fn should_flush(self, bytes: &[u8], pending: &mut usize) -> bool {
+        *pending += bytes.len();
+
+        let flush = match self {
+            Self::EveryWrite => true,
+            Self::OnNewline => bytes.contains(&b'\n'),
+            Self::EveryNBytes(amount) => *pending >= amount,
+            Self::AtEnd => false,
+        };
+        if flush {
+            *pending = 0;
+        }
+        flush
+    }
+}
+
+/// a buffered output stream, flushed according to a [`FlushPolicy`]
+pub(crate) struct PolicyWriter<W: Write> {
+    /// the buffered output stream
+    inner: BufWriter<W>,
+    /// when to flush the buffer
+    policy: FlushPolicy,
+    /// the amount of bytes written since the last flush
+    pending: usize,
+}
+
+impl<W: Write> PolicyWriter<W> {
+    /// wraps the provided output stream, flushing it according to the provided policy
+    pub(crate) fn new(inner: W, policy: FlushPolicy) -> Self {
+        Self { inner: BufWriter::new(inner), policy, pending: 0 }
+    }
+
+    /// writes the bytes of a `.` operation, flushing them if the policy says so
+    pub(crate) fn write_output(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.inner.write_all(bytes)?;
+        if self.policy.should_flush(bytes, &mut self.pending) {
+            self.inner.flush()?;
+        }
+        Ok(())
+    }
+
+    /// flushes whatever was written since the last flush, at the end of the execution
+    pub(crate) fn finish(&mut self) -> io::Result<()> {
+        if self.pending == 0 {
+            return Ok(());
+        }
+        self.pending = 0;
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,314 @@
+//! module containing the source formatter and minifier, see [`format_source`] and [`minify_source`]
+
+use crate::{
+    ast::{parse, Node, NodeKind},
+    machine::{Machine, INSTRUCTIONS},
+    Result,
+};
+
+/// the options of [`format_source`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormatOptions {
+    /// the amount of spaces to indent the body of a loop with, defaults to `4`
+    pub indent: usize,
+    /// the maximum width of a line, including its indentation, defaults to `80`
+    ///
+    /// a single comment word wider than this is kept whole
+    pub width: usize,
+    /// whether or not to keep the comments, defaults to `true`
+    pub comments: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatOptions {
+    /// creates the default options
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { indent: 4, width: 80, comments: true }
+    }
+
+    /// builder method to specify the amount of spaces to indent the body of a loop with
+    #[must_use]
+    pub const fn with_indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// builder method to specify the maximum width of a line
+    #[must_use]
+    pub const fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// builder method to specify whether or not to keep the comments
+    #[must_use]
+    pub const fn with_comments(mut self, comments: bool) -> Self {
+        self.comments = comments;
+        self
+    }
+}
+
+/// a unit of the code laid out by the formatter
+enum Token {
+    /// a `[` whose loop spans multiple lines
+    Open,
+    /// the `]` of a loop spanning multiple lines
+    Close,
+    /// a run of instructions, including loops short enough to be kept on a single line
+    Code(String),
+    /// a single word of a comment
+    Word(String),
+}
+
+/// lays out the tokens into lines
+struct Writer {
+    options: FormatOptions,
+    /// the formatted code thus far
+    out: String,
+    /// the contents of the current line, without its indentation
+    line: String,
+    /// the current loop depth
+    depth: usize,
+}
+
+impl Writer {
+    /// returns the amount of characters that still fit on the current line,
+    /// accounting for the space separating them from the contents before them
+    fn available(&self) -> usize {
+        let used = self.depth * self.options.indent
+            + self.line.chars().count()
+            + usize::from(!self.line.is_empty());
+        self.options.width.saturating_sub(used)
+    }
+
+    /// appends the provided text to the current line, separated by a space if the line is not empty
+    fn append(&mut self, text: &str) {
+        if !self.line.is_empty() {
+            self.line.push(' ');
+        }
+        self.line += text;
+    }
+
+    /// ends the current line, if it is not empty
+    fn flush(&mut self) {
+        if !self.line.is_empty() {
+            let line = std::mem::take(&mut self.line);
+            self.emit(&line);
+        }
+    }
+
+    /// writes the provided line at the current indentation
+    fn emit(&mut self, line: &str) {
+        self.out += &" ".repeat(self.depth * self.options.indent);
+        self.out += line;
+        self.out.push('\n');
+    }
+
+    /// writes a run of instructions, wrapping it across as many lines as needed
+    fn code(&mut self, code: &str) {
+        let mut rest = code;
+
+        while !rest.is_empty() {
+            let available = self.available();
+            if available == 0 && !self.line.is_empty() {
+                self.flush();
+                continue;
+            }
+            let mut len = available.clamp(1, rest.len());
+
+            // avoids splitting an inline loop across lines, unless it is wider than a line on its own
+            if let Some(open) = rest[..len].rfind('[').filter(|open| !rest[*open..len].contains(']')) {
+                if open > 0 {
+                    len = open;
+                } else if !self.line.is_empty() {
+                    self.flush();
+                    continue;
+                }
+            }
+            let (chunk, remaining) = rest.split_at(len);
+
+            self.append(chunk);
+            rest = remaining;
+        }
+    }
+
+    /// writes a single comment word, moving it to the next line if it does not fit
+    fn word(&mut self, word: &str) {
+        if word.chars().count() > self.available() {
+            self.flush();
+        }
+        self.append(word);
+    }
+}
+
+/// appends the provided instructions to the run of instructions at the end of the tokens, if any
+fn push_code<'c>(tokens: &mut Vec<Token>, code: impl Iterator<Item = &'c char>) {
+    if let Some(Token::Code(last)) = tokens.last_mut() {
+        last.extend(code);
+    } else {
+        tokens.push(Token::Code(code.collect()));
+    }
+}
+
+/// splits the code into tokens, stripping the comments unless they are kept
+fn tokenize(code: &[char], jumps: &[usize], comments: bool) -> Vec<Token> {
+    let is_code = |chr: &char| INSTRUCTIONS.contains(*chr);
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+
+    while idx < code.len() {
+        let chr = code[idx];
+
+        match chr {
+            '[' => {
+                let close = jumps[idx];
+                let body = &code[idx + 1..close];
+
+                // loops without nested loops or comments are kept on a single line
+                if body.iter().all(|chr| (is_code(chr) && *chr != '[') || chr.is_whitespace()) {
+                    push_code(&mut tokens, code[idx..=close]
+                        .iter()
+                        .filter(|chr| is_code(chr)));
+                    idx = close;
+                } else {
+                    tokens.push(Token::Open);
+                }
+            },
+            ']' => tokens.push(Token::Close),
+            chr if is_code(&chr) => push_code(&mut tokens, std::iter::once(&code[idx])),
+            chr if chr.is_whitespace() => (),
+            _ => {
+                let len = code[idx..]
+                    .iter()
+                    .position(|chr| is_code(chr) || chr.is_whitespace())
+                    .unwrap_or(code.len() - idx);
+
+                if comments {
+                    tokens.push(Token::Word(code[idx..idx + len].iter().collect()));
+                }
+                idx += len;
+                continue;
+            },
+        }
+        idx += 1;
+    }
+    tokens
+}
+
+/// formats the provided code, placing every loop spanning multiple lines on its own lines,
+/// indenting its body by its depth
+///
+/// - runs of instructions are wrapped at [`FormatOptions::width`]
+/// - loops without nested loops or comments in them, such as `[-]`, are kept inline
+/// - comments are reflowed word by word, or stripped if [`FormatOptions::comments`] is disabled
+///
+/// formatting never changes the behavior of the code, and formatting formatted code does not change it
+///
+/// # Errors
+/// - [`crate::Error::MismatchedBrackets`]: the code contains an unmatched `[` or a stray `]`
+///
+/// ```rust
+/// use brainfuck_exe::{format_source, FormatOptions};
+///
+/// let formatted = format_source("++[>++[>+<-]<-] done", FormatOptions::new())?;
+///
+/// assert_eq!(formatted, "++\n[\n    >++[>+<-]<-\n]\ndone\n");
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+pub fn format_source(code: &str, options: FormatOptions) -> Result<String> {
+    let code = code
+        .chars()
+        .collect::<Vec<char>>();
+    let jumps = Machine::match_brackets(&code)?;
+
+    let mut writer = Writer {
+        options,
+        out: String::new(),
+        line: String::new(),
+        depth: 0,
+    };
+
+    for token in tokenize(&code, &jumps, options.comments) {
+        match token {
+            Token::Open => {
+                writer.flush();
+                writer.emit("[");
+                writer.depth += 1;
+            },
+            Token::Close => {
+                writer.flush();
+                writer.depth -= 1;
+                writer.emit("]");
+            },
+            Token::Code(code) => writer.code(&code),
+            Token::Word(word) => writer.word(&word),
+        }
+    }
+    writer.flush();
+
+    Ok(writer.out)
+}
+
+/// writes the minified nodes into `out`
+///
+/// `zero` is whether or not the current cell is known to be `0` before the first node,
+/// in which case the loops are never entered and can be removed
+fn minify_nodes(nodes: &[Node], out: &mut String, mut zero: bool) {
+    for node in nodes {
+        match node.kind {
+            NodeKind::Inc(amount) => {
+                *out += &node.to_string();
+                zero &= amount == 0;
+            },
+            NodeKind::Move(amount) => {
+                *out += &node.to_string();
+                zero &= amount == 0;
+            },
+            NodeKind::Loop(ref body) => {
+                if !zero {
+                    out.push('[');
+                    minify_nodes(body, out, false);
+                    out.push(']');
+                }
+                // the current cell is always `0` right after a loop
+                zero = true;
+            },
+            NodeKind::Input => {
+                out.push(',');
+                zero = false;
+            },
+            NodeKind::Output => out.push('.'),
+            NodeKind::Emit(_) => *out += &node.to_string(),
+        }
+    }
+}
+
+/// minifies the provided code into the smallest equivalent code this can find
+///
+/// - comments and whitespace are stripped
+/// - runs of `+` and `-`, and of `>` and `<`, are reduced to their net amount, removing cancelling pairs such as `+-`
+/// - loops that can never be entered are removed, such as those at the start of the code or right after another loop
+///
+/// # Errors
+/// - [`crate::Error::MismatchedBrackets`]: the code contains an unmatched `[` or a stray `]`
+///
+/// ```rust
+/// use brainfuck_exe::minify_source;
+///
+/// let minified = minify_source("[comment] +++-- >< [-][+] print it: .")?;
+///
+/// assert_eq!(minified, "+[-].");
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+pub fn minify_source(code: &str) -> Result<String> {
+    let mut out = String::new();
+    minify_nodes(&parse(code)?, &mut out, true);
+
+    Ok(out)
+}
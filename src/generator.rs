@@ -0,0 +1,183 @@
+//! module containing the generators of random valid programs for property testing, see [`ProgramGenerator`]
+//!
+//! requires the `arbitrary` feature
+
+use arbitrary::{Arbitrary, Unstructured};
+use crate::ast::Program;
+
+/// the relative weights of the instructions picked by a [`ProgramGenerator`]
+///
+/// an instruction with a weight of `0` is never picked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InstructionMix {
+    /// the weight of `+`
+    pub increment: u32,
+    /// the weight of `-`
+    pub decrement: u32,
+    /// the weight of `>`
+    pub move_right: u32,
+    /// the weight of `<`
+    pub move_left: u32,
+    /// the weight of `.`
+    pub output: u32,
+    /// the weight of `,`
+    pub input: u32,
+    /// the weight of opening a loop, and of closing the innermost loop once one is open
+    pub loops: u32,
+}
+
+impl InstructionMix {
+    /// weighs the arithmetic and the moves over the I/O and the loops, as most programs do
+    const DEFAULT: Self = Self {
+        increment: 4,
+        decrement: 4,
+        move_right: 3,
+        move_left: 3,
+        output: 1,
+        input: 1,
+        loops: 1,
+    };
+}
+
+impl Default for InstructionMix {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// a generator of random programs from the bytes of an [`Unstructured`], such as those of a fuzzer,
+/// with balanced brackets
+///
+/// ```rust
+/// use arbitrary::Unstructured;
+/// use brainfuck_exe::{ast::Program, generator::ProgramGenerator};
+///
+/// let bytes = (0..=255).collect::<Vec<u8>>();
+/// let code = ProgramGenerator::new()
+///     .with_max_len(16)
+///     .generate(&mut Unstructured::new(&bytes))?;
+///
+/// assert!(Program::parse(&code).is_ok());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProgramGenerator {
+    /// the max amount of instructions picked, not counting the `]` closing the loops left open at the end
+    pub max_len: usize,
+    /// the max depth the loops can be nested to
+    pub max_depth: usize,
+    /// the relative weights of the instructions
+    pub mix: InstructionMix,
+}
+
+impl Default for ProgramGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgramGenerator {
+    /// creates a generator of programs of up to `64` instructions with loops nested up to `4` deep,
+    /// with the default [`InstructionMix`]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_len: 64,
+            max_depth: 4,
+            mix: InstructionMix::DEFAULT,
+        }
+    }
+
+    /// builder method to set the max amount of instructions picked
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// builder method to set the max depth the loops can be nested to
+    #[must_use]
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// builder method to set the relative weights of the instructions
+    #[must_use]
+    pub const fn with_mix(mut self, mix: InstructionMix) -> Self {
+        self.mix = mix;
+        self
+    }
+
+    /// generates a program from the provided bytes, closing every loop left open at the end
+    ///
+    /// # Errors
+    /// only propagates the errors of [`Unstructured`], which does not fail on running out of bytes
+    pub fn generate(&self, u: &mut Unstructured) -> arbitrary::Result<String> {
+        let len = u.int_in_range(0..=self.max_len)?;
+        let mut code = String::with_capacity(len);
+        let mut depth = 0;
+
+        for _ in 0..len {
+            let can_open = depth < self.max_depth;
+            let choices = [
+                ('+', self.mix.increment),
+                ('-', self.mix.decrement),
+                ('>', self.mix.move_right),
+                ('<', self.mix.move_left),
+                ('.', self.mix.output),
+                (',', self.mix.input),
+                ('[', if can_open { self.mix.loops } else { 0 }),
+                (']', if depth > 0 { self.mix.loops } else { 0 }),
+            ];
+            let total = choices
+                .iter()
+                .map(|&(_, weight)| weight)
+                .sum::<u32>();
+            if total == 0 {
+                break;
+            }
+
+            let mut pick = u.int_in_range(0..=total - 1)?;
+            let Some(&(instruction, _)) = choices
+                .iter()
+                .find(|&&(_, weight)| {
+                    let found = pick < weight;
+                    pick = pick.saturating_sub(weight);
+                    found
+                })
+            else {
+                break;
+            };
+
+            match instruction {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => (),
+            }
+            code.push(instruction);
+        }
+        code.extend(std::iter::repeat_n(']', depth));
+        Ok(code)
+    }
+}
+
+/// valid brainfuck code generated by the default [`ProgramGenerator`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidProgram(pub String);
+
+impl<'a> Arbitrary<'a> for ValidProgram {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        ProgramGenerator::new()
+            .generate(u)
+            .map(Self)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Program {
+    /// parses the code generated by the default [`ProgramGenerator`]
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let code = ProgramGenerator::new().generate(u)?;
+        Self::parse(&code).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
@@ -0,0 +1,119 @@
+//! module containing the export of the control-flow graph of a [`Program`] as Graphviz DOT
+
+use std::fmt::Write;
+use crate::ast::{Node, NodeKind, Program};
+
+/// the maximum amount of characters of code shown in a single block
+const BLOCK_CODE_LEN: usize = 40;
+
+/// a pending edge into the next vertex of the graph, from a vertex with an optional label
+type Edge = (usize, Option<&'static str>);
+
+/// builds the DOT graph
+struct Graph {
+    /// the declarations of the vertices
+    vertices: String,
+    /// the declarations of the edges
+    edges: String,
+    /// the amount of vertices thus far
+    len: usize,
+}
+
+impl Graph {
+    /// declares a new vertex with the provided attributes, returning its id
+    fn vertex(&mut self, attributes: &str) -> usize {
+        let id = self.len;
+        self.len += 1;
+
+        let _ = writeln!(self.vertices, "    n{id} [{attributes}];");
+        id
+    }
+
+    /// connects every one of the pending edges to the provided vertex
+    fn link(&mut self, from: &[Edge], to: usize) {
+        for (id, label) in from {
+            let _ = match label {
+                Some(label) => writeln!(self.edges, "    n{id} -> n{to} [label=\"{label}\"];"),
+                None => writeln!(self.edges, "    n{id} -> n{to};"),
+            };
+        }
+    }
+
+    /// adds the provided nodes to the graph, continuing from the pending edges,
+    /// returning the pending edges out of the last of them
+    fn sequence(&mut self, nodes: &[Node], mut from: Vec<Edge>) -> Vec<Edge> {
+        let mut idx = 0;
+
+        while idx < nodes.len() {
+            if let NodeKind::Loop(ref body) = nodes[idx].kind {
+                let start = nodes[idx].span.start;
+                let header = self.vertex(&format!("label=\"loop\\n{start}\", shape=diamond"));
+                self.link(&from, header);
+
+                let back = self.sequence(body, vec![(header, Some("!= 0"))]);
+                self.link(&back, header);
+
+                from = vec![(header, Some("== 0"))];
+                idx += 1;
+                continue;
+            }
+
+            // a straight-line block spans every node up until the next loop
+            let len = nodes[idx..]
+                .iter()
+                .position(|node| matches!(node.kind, NodeKind::Loop(_)))
+                .unwrap_or(nodes.len() - idx);
+            let block = &nodes[idx..idx + len];
+
+            let mut code = block
+                .iter()
+                .map(ToString::to_string)
+                .collect::<String>();
+            if code.chars().count() > BLOCK_CODE_LEN {
+                code = code.chars().take(BLOCK_CODE_LEN).collect::<String>() + "...";
+            }
+
+            let vertex = self.vertex(&format!("label=\"{code}\\n{}\"", block[0].span.start));
+            self.link(&from, vertex);
+
+            from = vec![(vertex, None)];
+            idx += len;
+        }
+        from
+    }
+}
+
+impl Program {
+    /// renders the control-flow graph of the program in the Graphviz DOT format
+    ///
+    /// every straight-line run of instructions is a box labeled with its code and position,
+    /// and every loop is a diamond branching into its body while the current cell is not `0`
+    ///
+    /// ```rust
+    /// use brainfuck_exe::ast::Program;
+    ///
+    /// let dot = Program::parse("++[>+<-]>.")?.to_dot();
+    ///
+    /// assert!(dot.starts_with("digraph cfg {"));
+    /// assert!(dot.contains("shape=diamond"));
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut graph = Graph {
+            vertices: String::new(),
+            edges: String::new(),
+            len: 0,
+        };
+
+        let start = graph.vertex("label=\"start\", shape=oval");
+        let from = graph.sequence(&self.nodes, vec![(start, None)]);
+        let end = graph.vertex("label=\"end\", shape=oval");
+        graph.link(&from, end);
+
+        format!(
+            "digraph cfg {{\n    node [shape=box, fontname=\"monospace\"];\n{}{}}}\n",
+            graph.vertices, graph.edges,
+        )
+    }
+}
@@ -0,0 +1,89 @@
+//! module containing the types used for recording and rendering how often each cell was accessed
+//!
+//! see [`crate::Brainfuck::with_cell_tracking`]
+
+use std::fmt::Write;
+use crate::ExecutionInfo;
+
+/// the characters used by [`HeatmapStyle::Ascii`], from the least to the most accessed
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// the 256-color palette codes used by [`HeatmapStyle::Ansi`], from the least to the most accessed
+const ANSI_RAMP: &[u8] = &[236, 17, 19, 27, 33, 45, 226, 214, 208, 196];
+
+/// the amount of times a single cell was read from and written to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CellAccess {
+    /// the amount of times the cell was read by the `.` and `[` operations
+    pub reads: usize,
+    /// the amount of times the cell was written to by the `+`, `-` and `,` operations
+    pub writes: usize,
+}
+
+impl CellAccess {
+    /// returns the total amount of accesses of the cell
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.reads + self.writes
+    }
+}
+
+/// the style of a heatmap rendered by [`ExecutionInfo::heatmap`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeatmapStyle {
+    /// plain characters, from ` ` for unaccessed cells up to `@` for the most accessed ones
+    Ascii,
+    /// colored blocks using ANSI 256-color escape codes, for terminal output
+    Ansi,
+}
+
+/// renders a heatmap of the provided cell accesses,
+/// laying out `width` cells per row, each prefixed with the index of its first cell
+///
+/// the heat of every cell is its total amount of accesses relative to the most accessed cell
+#[must_use]
+pub fn render_heatmap(accesses: &[CellAccess], style: HeatmapStyle, width: usize) -> String {
+    let width = width.max(1);
+    let max = accesses
+        .iter()
+        .map(CellAccess::total)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let digits = accesses.len()
+        .to_string()
+        .len();
+
+    let mut heatmap = String::new();
+    for (row, chunk) in accesses.chunks(width).enumerate() {
+        let _ = write!(heatmap, "{:>digits$} |", row * width);
+
+        for access in chunk {
+            // scales the heat so that only unaccessed cells fall into the first level
+            let level = (access.total() * (ASCII_RAMP.len() - 1))
+                .div_ceil(max);
+
+            match style {
+                HeatmapStyle::Ascii => heatmap.push(char::from(ASCII_RAMP[level])),
+                HeatmapStyle::Ansi => {
+                    let _ = write!(heatmap, "\x1b[48;5;{}m \x1b[0m", ANSI_RAMP[level]);
+                },
+            }
+        }
+        heatmap.push_str("|\n");
+    }
+    heatmap
+}
+
+impl ExecutionInfo {
+    /// renders a heatmap of how often each cell was accessed, 32 cells per row
+    ///
+    /// returns [`None`] if cell access tracking was not enabled,
+    /// see [`crate::Brainfuck::with_cell_tracking`]
+    #[must_use]
+    pub fn heatmap(&self, style: HeatmapStyle) -> Option<String> {
+        self.cell_accesses
+            .as_deref()
+            .map(|accesses| render_heatmap(accesses, style, 32))
+    }
+}
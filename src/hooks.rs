@@ -0,0 +1,103 @@
+//! module containing the types used for hooking into a program's execution
+
+use std::ops::ControlFlow;
+use crate::ExecutionInfo;
+
+/// the context passed into an [`InstructionHook`] before an instruction is executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HookCtx {
+    /// the instruction about to be executed, such as `+`
+    pub opcode: char,
+    /// the index of the instruction within the code
+    pub code_idx: usize,
+    /// the current pointer index
+    pub pointer: usize,
+    /// the value of the current cell
+    pub cell: u32,
+    /// the amount of instructions executed thus far
+    pub instructions: usize,
+}
+
+/// a callback invoked before every instruction is executed
+///
+/// returning [`ControlFlow::Break`] aborts the execution with [`crate::Error::Cancelled`]
+pub type InstructionHook<'a> = Box<dyn FnMut(&HookCtx) -> ControlFlow<()> + Send + 'a>;
+
+/// a callback invoked with every byte read by `,` or written by `.`
+pub type IoHook<'a> = Box<dyn FnMut(u8) + Send + 'a>;
+
+/// the context passed into a [`CustomOp`] when its instruction is executed
+#[derive(Debug)]
+pub struct OpCtx<'m> {
+    /// the instruction being executed
+    pub opcode: char,
+    /// the memory array, which can be modified and grown
+    ///
+    /// values above `max_cell_value` are wrapped around, and the length of a fixed size memory array is restored
+    pub cells: &'m mut Vec<u32>,
+    /// the current pointer index, which can be moved
+    ///
+    /// the memory array grows to include it, or it is wrapped around if the memory array has a fixed size
+    pub pointer: &'m mut usize,
+    /// the maximum value of a cell
+    pub max_cell_value: u32,
+    /// the characters to write into the output stream once the operation is done,
+    /// each taking a step of its own as if written by `.`
+    pub output: String,
+}
+
+/// a callback executing a custom instruction, see [`crate::Brainfuck::with_custom_op`]
+///
+/// returning [`ControlFlow::Break`] aborts the execution with [`crate::Error::Cancelled`]
+pub type CustomOp<'a> = Box<dyn FnMut(&mut OpCtx) -> ControlFlow<()> + Send + 'a>;
+
+/// a generator of the random values of the `?` operation,
+/// which are wrapped into the range of a cell, `0..=max_cell_value`
+pub type RandomSource<'a> = Box<dyn FnMut() -> u32 + Send + 'a>;
+
+/// an observer of the lifecycle of a program's execution
+///
+/// every method has an empty default implementation, so only the events of interest need to be implemented;
+/// this is useful for stateful tooling such as profilers, coverage tools and visualizers
+///
+/// it is also implemented for `&mut T`, so an observer can be borrowed and inspected after the execution
+///
+/// see [`crate::Brainfuck::with_observer`]
+pub trait ExecutionObserver {
+    /// invoked once before the first instruction is executed
+    fn on_start(&mut self) {}
+
+    /// invoked before every instruction is executed
+    fn on_instruction(&mut self, _ctx: &HookCtx) {}
+
+    /// invoked when a loop is entered from outside of it, with the code index of its `[`
+    fn on_loop_enter(&mut self, _code_idx: usize) {}
+
+    /// invoked when a loop that was entered finishes, with the code index of its `[`
+    fn on_loop_exit(&mut self, _code_idx: usize) {}
+
+    /// invoked once the end of the code is reached
+    fn on_halt(&mut self, _info: &ExecutionInfo) {}
+}
+
+impl<T: ExecutionObserver + ?Sized> ExecutionObserver for &mut T {
+    fn on_start(&mut self) {
+        (**self).on_start();
+    }
+
+    fn on_instruction(&mut self, ctx: &HookCtx) {
+        (**self).on_instruction(ctx);
+    }
+
+    fn on_loop_enter(&mut self, code_idx: usize) {
+        (**self).on_loop_enter(code_idx);
+    }
+
+    fn on_loop_exit(&mut self, code_idx: usize) {
+        (**self).on_loop_exit(code_idx);
+    }
+
+    fn on_halt(&mut self, info: &ExecutionInfo) {
+        (**self).on_halt(info);
+    }
+}
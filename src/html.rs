@@ -0,0 +1,224 @@
+//! module containing the syntax-highlighted HTML exporter, see [`render_html`]
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+use crate::{LoopProfile, Profiler};
+
+/// the amount of colors cycled through by the loops as they nest deeper
+const DEPTH_COLORS: usize = 6;
+
+/// the stylesheet of the classes used by [`render_html`], embedded into standalone documents
+///
+/// it can be included once into a page embedding several fragments rendered without [`HtmlOptions::with_standalone`]
+pub const HTML_STYLESHEET: &str = "\
+pre.brainfuck { background: #1e1e2e; color: #cdd6f4; padding: 1em; border-radius: 6px; line-height: 1.4; }
+pre.brainfuck .bf-comment { color: #6c7086; font-style: italic; }
+pre.brainfuck .bf-cell { color: #a6e3a1; }
+pre.brainfuck .bf-pointer { color: #89b4fa; }
+pre.brainfuck .bf-io { color: #f9e2af; font-weight: bold; }
+pre.brainfuck .bf-bracket { font-weight: bold; }
+pre.brainfuck .bf-depth-0 > .bf-bracket { color: #f38ba8; }
+pre.brainfuck .bf-depth-1 > .bf-bracket { color: #fab387; }
+pre.brainfuck .bf-depth-2 > .bf-bracket { color: #f9e2af; }
+pre.brainfuck .bf-depth-3 > .bf-bracket { color: #a6e3a1; }
+pre.brainfuck .bf-depth-4 > .bf-bracket { color: #89dceb; }
+pre.brainfuck .bf-depth-5 > .bf-bracket { color: #cba6f7; }
+pre.brainfuck .bf-loop:hover { background: rgba(205, 214, 244, 0.08); }
+pre.brainfuck .bf-unmatched { color: #1e1e2e; background: #f38ba8; }
+pre.brainfuck .bf-count { color: #6c7086; font-size: 0.75em; vertical-align: super; margin-left: 0.1em; }
+pre.brainfuck .bf-cold > .bf-bracket { opacity: 0.5; }
+";
+
+/// the options of [`render_html`]
+#[derive(Debug, Clone, Default)]
+pub struct HtmlOptions {
+    /// whether or not to render a whole document embedding [`HTML_STYLESHEET`], defaults to `false`,
+    /// rendering only the `<pre>` element
+    pub standalone: bool,
+    /// the title of the standalone document, defaults to `brainfuck`
+    pub title: Option<String>,
+    /// the profile of every loop of the code, annotating every loop with its amount of iterations
+    pub profile: Option<Vec<LoopProfile>>,
+}
+
+impl HtmlOptions {
+    /// creates the default options
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { standalone: false, title: None, profile: None }
+    }
+
+    /// builder method to specify whether or not to render a whole document
+    #[must_use]
+    pub const fn with_standalone(mut self, standalone: bool) -> Self {
+        self.standalone = standalone;
+        self
+    }
+
+    /// builder method to specify the title of the standalone document
+    #[must_use]
+    pub fn with_title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// builder method to annotate every loop with its amount of iterations and instructions
+    /// from the provided profiler, which must have observed an execution of the same code
+    #[must_use]
+    pub fn with_profile(mut self, profiler: &Profiler) -> Self {
+        self.profile = Some(profiler.loops());
+        self
+    }
+}
+
+/// escapes the characters of the provided text that are special within HTML
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for chr in text.chars() {
+        match chr {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(chr),
+        }
+    }
+    escaped
+}
+
+/// returns the class of the provided instruction, or [`None`] if it is a comment
+const fn class(chr: char) -> Option<&'static str> {
+    match chr {
+        '+' | '-' => Some("bf-cell"),
+        '<' | '>' => Some("bf-pointer"),
+        '.' | ',' => Some("bf-io"),
+        '[' | ']' => Some("bf-bracket"),
+        _ => None,
+    }
+}
+
+/// renders the provided code as syntax-highlighted HTML, within a `<pre class="brainfuck">` element
+///
+/// every loop is wrapped in a `<span>` with the class `bf-depth-N`, `N` cycling through `0` to `5` as the loops nest,
+/// the brackets of every loop being colored by its depth, and unmatched brackets being highlighted as `bf-unmatched`;
+/// comments are kept as they are with the class `bf-comment`
+///
+/// if a profile is provided, every loop is annotated with the amount of times it iterated after its `]`,
+/// and with its amount of instructions in its `title`, loops that were never entered being dimmed as `bf-cold`
+///
+/// ```rust
+/// use brainfuck_exe::{render_html, HtmlOptions};
+///
+/// let html = render_html("+[-] done", &HtmlOptions::new());
+/// assert!(html.starts_with("<pre class=\"brainfuck\">"));
+/// assert!(html.contains("<span class=\"bf-loop bf-depth-0\">"));
+/// assert!(html.contains("<span class=\"bf-comment\"> done</span>"));
+/// ```
+#[must_use]
+pub fn render_html(code: &str, options: &HtmlOptions) -> String {
+    let code = code
+        .chars()
+        .collect::<Vec<char>>();
+    let profiles = options.profile
+        .iter()
+        .flatten()
+        .map(|profile| (profile.code_idx, profile))
+        .collect::<HashMap<usize, &LoopProfile>>();
+
+    // the code index of every `[` that is matched, to know which brackets to highlight as unmatched
+    let mut matched = HashSet::new();
+    let mut open = Vec::new();
+    for (idx, &chr) in code.iter().enumerate() {
+        match chr {
+            '[' => open.push(idx),
+            ']' => if let Some(start) = open.pop() {
+                matched.insert(start);
+            },
+            _ => (),
+        }
+    }
+
+    let mut html = String::from("<pre class=\"brainfuck\">");
+    // the code index of the `[` of every loop currently open
+    let mut loops: Vec<usize> = Vec::new();
+    let mut idx = 0;
+
+    while idx < code.len() {
+        let chr = code[idx];
+        let Some(kind) = class(chr) else {
+            let end = code[idx..]
+                .iter()
+                .position(|&chr| class(chr).is_some())
+                .map_or(code.len(), |len| idx + len);
+            let comment = code[idx..end]
+                .iter()
+                .collect::<String>();
+
+            let _ = write!(html, "<span class=\"bf-comment\">{}</span>", escape(&comment));
+            idx = end;
+            continue;
+        };
+
+        match chr {
+            '[' if matched.contains(&idx) => {
+                let mut attributes = format!("class=\"bf-loop bf-depth-{}", loops.len() % DEPTH_COLORS);
+                if let Some(profile) = profiles.get(&idx) {
+                    if profile.entries == 0 {
+                        attributes.push_str(" bf-cold");
+                    }
+                    let _ = write!(
+                        attributes,
+                        "\" title=\"{}:{}, entered {} times, {} iterations, {} instructions ({} self)",
+                        profile.line, profile.column, profile.entries,
+                        profile.iterations, profile.instructions, profile.self_instructions,
+                    );
+                }
+                let _ = write!(html, "<span {attributes}\"><span class=\"bf-bracket\">[</span>");
+                loops.push(idx);
+            },
+            ']' if !loops.is_empty() => {
+                html.push_str("<span class=\"bf-bracket\">]</span>");
+
+                let start = loops.pop().unwrap_or_default();
+                if let Some(profile) = profiles.get(&start).filter(|profile| profile.entries > 0) {
+                    let _ = write!(html, "<span class=\"bf-count\">&times;{}</span>", profile.iterations);
+                }
+                html.push_str("</span>");
+            },
+            '[' | ']' => {
+                let _ = write!(html, "<span class=\"bf-bracket bf-unmatched\" title=\"unmatched bracket\">{chr}</span>");
+            },
+            _ => {
+                // consecutive instructions of the same class are grouped into a single element
+                let end = code[idx..]
+                    .iter()
+                    .position(|&next| class(next) != Some(kind))
+                    .map_or(code.len(), |len| idx + len);
+                let run = code[idx..end]
+                    .iter()
+                    .collect::<String>();
+
+                let _ = write!(html, "<span class=\"{kind}\">{}</span>", escape(&run));
+                idx = end;
+                continue;
+            },
+        }
+        idx += 1;
+    }
+    html.push_str("</pre>\n");
+
+    if options.standalone {
+        let title = options.title
+            .as_deref()
+            .unwrap_or("brainfuck");
+
+        html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{HTML_STYLESHEET}</style>\n</head>\n<body>\n{html}</body>\n</html>\n",
+            escape(title),
+        );
+    }
+    html
+}
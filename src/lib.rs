@@ -18,14 +18,34 @@
 //! brainfuck-exe = { version = "*", default-features = false }
 //! ```
 //!
+//! Other optional features can be enabled as following:
+//! ```toml
+//! brainfuck-exe = { version = "*", features = ["async"] }
+//! ```
+//! - `async`: [`tokio`](https://docs.rs/tokio) based async execution with `Brainfuck::execute_async`
+//! - `parallel`: [`rayon`](https://docs.rs/rayon) based batch execution with `Brainfuck::execute_batch`
+//! - `tracing`: emits [`tracing`](https://docs.rs/tracing) spans for parsing and execution, and events for I/O and limit hits
+//! - `metrics`: publishes counters for instructions, I/O bytes and completed or failed runs through the [`metrics`](https://docs.rs/metrics) facade
+//!   - `brainfuck_instructions_total`
+//!   - `brainfuck_input_bytes_total`
+//!   - `brainfuck_output_bytes_total`
+//!   - `brainfuck_runs_completed_total`
+//!   - `brainfuck_runs_failed_total` (labeled by `kind`)
+//!   - `brainfuck_memory_cells` (gauge)
+//! - `toml`: loads the token table of a substitution dialect from a [`toml`](https://docs.rs/toml) file with `TokenTable::from_toml`
+//! - `test-utils`: one-line output assertions for the tests of programs with `Brainfuck::assert_output` and `Brainfuck::assert_output_with_input`, panicking with a diff
+//! - `wasm`: [`wasm-bindgen`](https://docs.rs/wasm-bindgen) bindings for web playgrounds, exporting `run(code, input, options)` to JS, built with `wasm-pack build -- --no-default-features --features wasm`
+//! - `ffi`: a C ABI for embedding the interpreter into other languages (`bf_new`, `bf_set_option`, `bf_execute`, `bf_get_output`, `bf_get_error` and `bf_free`), declared in [`include/brainfuck_exe.h`](include/brainfuck_exe.h), which is generated with `cbindgen --config cbindgen.toml --output include/brainfuck_exe.h`
+//! - `server`: the `serve-http` subcommand of the CLI, a JSON API for web playgrounds executing the programs posted to `POST /run` within the limits of the server
+//! - `http`: `Brainfuck::from_url`, fetching the code from an HTTP or HTTPS URL with a size limit and a timeout
+//!
 //! ## Example
 //! Below is a basic example on how to use the crate
 //!
 //! ```rust
 //!
-//! use std::fs::File;
 //! // import Result typealias and interpreter struct
-//! use brainfuck_exe::{Result, Brainfuck};
+//! use brainfuck_exe::{Result, Brainfuck, OpenMode};
 //!
 //! fn main() -> Result<()> {
 //!     // brainfuck code to print "Hello, World!"
@@ -34,12 +54,7 @@
 //!     // instantiate a new interpreter instance with the code
 //!     Brainfuck::new(code)
 //!         // optional builder method to write the output into a file not STDOUT
-//!         .with_output(
-//!             File::options()
-//!                 .write(true)
-//!                 .open("tests/output.txt")
-//!                 .unwrap()
-//!         )
+//!         .with_output_path("tests/output.txt", OpenMode::Truncate)?
 //!         // executes the code
 //!         .execute()?;
 //!
@@ -60,41 +75,232 @@
 //! # usage
 //! $ brainfuck --help
 //! $ brainfuck [CODE] [-f FILE] [OPTIONS]
+//! # report errors on STDERR as JSON instead, the exit codes are listed in `--help`
+//! $ brainfuck [CODE] [-f FILE] --error-format json [OPTIONS]
+//! # print the optimized program instead of executing it, reporting the removed code
+//! $ brainfuck [CODE] [-f FILE] --emit optimized-bf [OPTIONS]
+//! # print which optimization passes fired and how many instructions they eliminated
+//! $ brainfuck [CODE] [-f FILE] --emit optimization-report [OPTIONS]
+//! # execute a program written in a trivial substitution dialect, with its tokens in a TOML file
+//! $ brainfuck [CODE] [-f FILE] --tokens TOKENS.toml [OPTIONS]
+//! # execute a program written in an extension of brainfuck
+//! $ brainfuck [CODE] [-f FILE] --dialect brainfuck|pbrain|brainfork|extended-type-i|boolfuck [OPTIONS]
+//! # make `?` set the current cell to a random value, optionally seeded to make it deterministic
+//! $ brainfuck [CODE] [-f FILE] --random [--seed N] [OPTIONS]
+//! # make a source file directly executable, with `#!/usr/bin/env -S brainfuck -f` as its first line
+//! $ chmod +x FILE && ./FILE [OPTIONS]
+//! # execute a program again every time its file or input file is modified
+//! $ brainfuck -f FILE --watch [OPTIONS]
+//! # only print the program output, the execution information is otherwise printed on STDERR
+//! $ brainfuck [CODE] [-f FILE] --quiet [OPTIONS]
+//! # print the execution information on STDERR as JSON, along with the cells if `--print-info` is specified
+//! $ brainfuck [CODE] [-f FILE] --json [--print-info] [OPTIONS]
+//! # print the memory array on STDERR, optionally only some of the cells and without the runs of zeros
+//! $ brainfuck [CODE] [-f FILE] --print-cells [--cells-format dec|hex|ascii] [--cells-range 0..64] [--skip-zeros] [OPTIONS]
+//! # write the memory array to a file, every cell as a little-endian integer as wide as the cell size
+//! $ brainfuck [CODE] [-f FILE] --dump-memory FILE [OPTIONS]
+//! # print a summary of the execution on STDERR, such as the instructions executed per second
+//! $ brainfuck [CODE] [-f FILE] --stats [OPTIONS]
+//! # print every instruction executed on STDERR, optionally only every Nth one or only I/O and loops
+//! $ brainfuck [CODE] [-f FILE] --trace [--trace-every N] [--trace-only io|loops] [OPTIONS]
+//! # redraw the memory array after every Kth instruction, with a delay in milliseconds in between
+//! $ brainfuck [CODE] [-f FILE] --visualize [--visualize-cells N] [--visualize-every K] [--visualize-delay MS] [OPTIONS]
+//! # read the defaults of the options from a TOML file, such as `max-cell-value = 65535`,
+//! # `brainfuck.toml` in the current directory or `brainfuck/config.toml` in the XDG config directory otherwise
+//! $ brainfuck [CODE] [-f FILE] [--config CONFIG.toml | --no-config] [OPTIONS]
+//! # follow the conventions of other interpreters for the size of a cell, EOF and wrapping
+//! $ brainfuck [CODE] [-f FILE] [--cell-size 8|16|32] [--eof 0|max|unchanged] [--no-wrap] [OPTIONS]
+//! # append the output to a file, or refuse to overwrite it, creating its directories if needed
+//! $ brainfuck [CODE] [-f FILE] -o OUTPUT [--append | --no-clobber] [OPTIONS]
+//! # fail with a diff unless the program outputs the expected output, such as in CI
+//! $ brainfuck [CODE] [-f FILE] [--expect OUTPUT | --expect-file GOLDEN] [OPTIONS]
+//! # exit with the value of the current cell once the program finishes, clamped to 255
+//! $ brainfuck [CODE] [-f FILE] --exit-cell [OPTIONS]
+//! # feed exact byte values into `,`, in decimal or with a `0x`, `0o` or `0b` prefix
+//! $ brainfuck [CODE] [-f FILE] --input-bytes 72,101,0xFF [OPTIONS]
+//! # read the inputs of `,` from a file
+//! $ brainfuck [CODE] [-f FILE] -I INPUT [OPTIONS]
+//! # chain programs, feeding the output of each into the input of the next
+//! $ brainfuck -f FIRST -f SECOND [OPTIONS]
+//! # serve a program over TCP, one interpreter per connection
+//! $ brainfuck serve FILE [--listen ADDR] [OPTIONS]
+//! # serve a JSON API executing `{"code", "input", "instructionsLimit", "timeout", ...}` posted to `/run`, requires the `server` feature
+//! $ brainfuck serve-http [--port 8080] [--workers N] [--max-instructions N] [--max-timeout MS] [--max-output BYTES]
+//! # summarize a program without executing it, such as its instructions, loop depth and tape usage
+//! $ brainfuck stats FILE
+//! # report common smells in a program, with suggested fixes
+//! $ brainfuck lint FILE
+//! # validate programs without executing them, failing only on errors unless warnings are denied
+//! $ brainfuck check FILE... [--no-lints] [--deny-warnings] [--strict]
+//! # serve diagnostics, bracket matching, loop effects on hover and formatting to editors over STDIN and STDOUT
+//! $ brainfuck lsp [--stdio]
+//! # format a program in place, or only check whether it is formatted
+//! $ brainfuck fmt FILE [--indent N] [--width N] [--strip-comments] [--check]
+//! # optimize a program into plain brainfuck for other interpreters, optionally reporting the passes that fired
+//! $ brainfuck optimize FILE [-o OUTPUT] [--report] [OPTIONS]
+//! # check that two programs behave the same over the provided inputs and N random ones
+//! $ brainfuck equiv LEFT RIGHT [-i INPUT]... [--fuzz N] [--fuzz-len N] [--fuzz-seed N] [OPTIONS]
+//! # shrink a program into the smallest equivalent program
+//! $ brainfuck minify FILE [-o OUTPUT]
+//! # export the control-flow graph of a program as Graphviz DOT
+//! $ brainfuck graph FILE [-o OUTPUT]
+//! # export a program as syntax-highlighted HTML, optionally annotating its loops with how often they iterated
+//! $ brainfuck html FILE [--profile] [-i INPUT] [--fragment] [--title TITLE] [-o OUTPUT] [OPTIONS]
+//! # translate a program between brainfuck and its derivatives, `.ook` and `.spoon` files can also be executed directly
+//! $ brainfuck translate FILE [--from LANGUAGE] --to bf|ook|alphuck|reversefuck|spoon [-o OUTPUT]
+//! # emit a program printing the provided text
+//! $ brainfuck generate TEXT [-o OUTPUT]
+//! # obfuscate a program, checking that it still behaves the same with every sample input
+//! $ brainfuck obfuscate FILE [--seed N] [--sample INPUT]... [-o OUTPUT]
+//! # execute lines interactively against a persistent memory array, see `:help` for the meta-commands
+//! $ brainfuck repl [OPTIONS]
+//! # execute every `NAME.bf` of a directory with `NAME.in` as its input, comparing its output against `NAME.out`
+//! # with the options of `NAME.toml` if it exists, in the format of the configuration file
+//! $ brainfuck test [DIR] [--filter PATTERN] [OPTIONS]
+//! # time a program over N runs, saving the timings as a baseline or comparing them against one
+//! $ brainfuck bench FILE [-n N] [--warmup N] [--save-baseline FILE] [--baseline FILE] [OPTIONS]
+//! # execute every `*.bf` and `*.b` program of a directory in parallel, reporting the failures and the limits reached
+//! $ brainfuck corpus [DIR] [-j N] [--instructions-limit N] [--timeout MS] [OPTIONS]
+//! # report the hottest loops of a program
+//! $ brainfuck profile FILE [--top N] [--timing] [--format text|callgrind|folded] [-o OUTPUT] [OPTIONS]
+//! ```
+//!
+//! ### WASI
+//! The CLI can also be built as a [WASI](https://wasi.dev) module, executing programs in a sandbox
+//! with access to only the directories granted to it
+//! ```bash
+//! $ rustup target add wasm32-wasip1
+//! $ cargo build --release --target wasm32-wasip1
+//! # chained programs and the `corpus` subcommand execute on a single thread, `serve` and `--watch` are unavailable
+//! $ wasmtime --dir . target/wasm32-wasip1/release/brainfuck.wasm -f tests/hello_world.bf
 //! ```
 
 use std::{
-    fs::File,
+    collections::HashMap,
+    fmt,
+    fs::{File, OpenOptions},
     path::Path,
-    io::{Read, Write},
-    ops::{Deref, DerefMut},
-    time::{Instant, Duration},
+    io::{BufReader, BufWriter, Read, Write},
+    ops::{ControlFlow, Deref, DerefMut},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
 };
-pub use error::{Error, Result};
+pub use error::{Error, Result, RuntimeContext};
+pub use flush::FlushPolicy;
+use flush::PolicyWriter;
+pub use formatter::{format_source, minify_source, FormatOptions};
+pub use optimizer::OptimizationLevel;
+pub use heatmap::{render_heatmap, CellAccess, HeatmapStyle};
+pub use limits::ExecutionLimits;
+pub use html::{render_html, HtmlOptions, HTML_STYLESHEET};
+pub use hooks::{CustomOp, ExecutionObserver, HookCtx, InstructionHook, IoHook, OpCtx, RandomSource};
+use machine::{Action, LoopEvent, Machine};
+pub use analysis::{Analysis, LoopAnalysis};
+pub use check::{Diagnostic, Severity};
+pub use coverage::Coverage;
+pub use dialect::Dialect;
+pub use diff::{CellDiff, InfoDiff};
+pub use events::{Events, ExecutionEvent};
+pub use pipeline::Pipeline;
+pub use position::{Position, Span};
+pub use profile::{LoopProfile, Profiler};
+pub use sans_io::{Execution, Step};
+pub use scheduler::{ProgramState, Scheduler};
+pub use session::{EvalOutcome, Session};
+pub use source_map::SourceMap;
+pub use spawn::ExecutionHandle;
+pub use stream::BrainfuckStream;
+use translate::Language;
+#[cfg(feature = "parallel")]
+pub use batch::BatchResult;
+#[cfg(feature = "async")]
+pub use async_exec::ASYNC_YIELD_INTERVAL;
 
+mod analysis;
+pub mod ast;
+mod check;
+pub mod codegen;
+pub mod corpus;
+mod coverage;
+mod dialect;
+mod diff;
+pub mod equivalence;
 pub mod error;
+mod events;
+mod flush;
+mod formatter;
+mod graph;
+mod heatmap;
+mod html;
+pub mod lint;
+mod limits;
+mod hooks;
+mod machine;
+pub mod optimizer;
+mod pipeline;
+mod position;
+mod profile;
+mod random;
+mod sans_io;
+mod scheduler;
+mod session;
+mod source_map;
+mod spawn;
+mod stream;
+mod telemetry;
+pub mod translate;
+#[cfg(feature = "async")]
+mod async_exec;
+#[cfg(feature = "parallel")]
+mod batch;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "arbitrary")]
+pub mod generator;
+#[cfg(feature = "test-utils")]
+mod test_utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 
 /// default max value a cell can have
 ///
 /// it is `255`, the same as [`std::u8::MAX`]
 pub const DEFAULT_MAX_CELL_VALUE: u32 = 255;
 
+/// whether or not the standard library can read the clock on the target,
+/// which it cannot on `wasm32-unknown-unknown`
+pub(crate) const HAS_CLOCK: bool = !cfg!(all(target_family = "wasm", target_os = "unknown"));
+
+/// whether or not the standard library can spawn threads on the target,
+/// which it cannot on WASI, nor on `wasm32-unknown-unknown`, without the `atomics` target feature
+pub(crate) const HAS_THREADS: bool = !cfg!(all(target_family = "wasm", not(target_feature = "atomics")));
+
 
 /// a helper wrapper enum that is used for storing the input stream
 /// this allows for it to be passed by value OR reference
+///
+/// the stream is required to be [`Send`] so that a configured interpreter can be moved across threads
 pub enum Reader<'a> {
     /// used when passing in the input stream by value
-    Value(Box<dyn Read>),
+    Value(Box<dyn Read + Send>),
     /// used when passing in the input stream as a mutable reference
-    Ref(&'a mut dyn Read),
+    Ref(&'a mut (dyn Read + Send)),
 }
 
 /// a helper wrapper enum that is used for storing the output stream
 /// this allows for it to be passed by value OR reference
+///
+/// the stream is required to be [`Send`] so that a configured interpreter can be moved across threads
 pub enum Writer<'a> {
     /// used when passing in the output stream by value
-    Value(Box<dyn Write>),
+    Value(Box<dyn Write + Send>),
     /// used when passing in the output stream as a mutable reference
-    Ref(&'a mut dyn Write),
+    Ref(&'a mut (dyn Write + Send)),
 }
 
 impl<'a> Deref for Reader<'a> {
@@ -153,10 +359,241 @@ pub struct ExecutionInfo {
     ///
     /// this also can be retrieved with `Brainfuck::instructions_count`
     pub instructions: usize,
+    /// the amount of times each of the instructions were executed
+    pub opcode_counts: OpcodeCounts,
+    /// the amount of times each cell of `cells` was read from and written to
+    ///
+    /// it is [`None`] unless it was specified in [`Brainfuck`] to `track_cell_accesses`
+    pub cell_accesses: Option<Vec<CellAccess>>,
     /// the time it took for the program execution as a [`Duration`]
     ///
     /// it is [`None`] if it was not specified in [`Brainfuck`] to `bench_execution`
     pub time: Option<Duration>,
+    /// the statistics of every thread, in the order they were forked in, starting with the main thread
+    ///
+    /// it is empty unless the code is written in [`Dialect::Brainfork`],
+    /// in which case `cells` and `pointer` are those of the main thread
+    pub threads: Vec<ThreadStats>,
+}
+
+impl ExecutionInfo {
+    /// the max amount of non-zero cells listed by the [`fmt::Display`] implementation
+    const DISPLAYED_CELLS: usize = 16;
+
+    /// returns the index and value of every cell that is not `0`
+    fn non_zero_cells(&self) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.cells
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(_, cell)| cell != 0)
+    }
+
+    /// summarizes the execution in a single line, such as
+    /// `389 instructions in 0.042 ms, pointer at 3, 4 non-zero cells out of 30000`,
+    /// see the [`fmt::Display`] implementation for a multi-line report
+    ///
+    /// ```rust
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// let info = Brainfuck::new("++>+++>")
+    ///     .with_mem_size(8)
+    ///     .with_bench_execution(false)
+    ///     .execute()?;
+    /// assert_eq!(info.summary(), "7 instructions, pointer at 2, 2 non-zero cells out of 8");
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let time = self.time
+            .map(|time| format!(" in {:.3} ms", time.as_secs_f64() * 1000.0))
+            .unwrap_or_default();
+
+        format!(
+            "{} instructions{time}, pointer at {}, {} non-zero cells out of {}",
+            self.instructions,
+            self.pointer,
+            self.non_zero_cells().count(),
+            self.mem_size,
+        )
+    }
+}
+
+/// a readable multi-line report of the execution, listing the first 16 non-zero cells
+/// rather than the whole memory array
+///
+/// ```rust
+/// use brainfuck_exe::Brainfuck;
+///
+/// let info = Brainfuck::new("++>+++>")
+///     .with_mem_size(8)
+///     .with_bench_execution(false)
+///     .execute()?;
+/// assert_eq!(info.to_string(), "\
+/// instructions: 7
+/// pointer:      2
+/// memory size:  8
+/// cells:        [0] = 2, [1] = 3");
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+impl fmt::Display for ExecutionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "instructions: {}", self.instructions)?;
+        if let Some(time) = self.time {
+            writeln!(f, "time:         {:.3} ms", time.as_secs_f64() * 1000.0)?;
+        }
+        if !self.threads.is_empty() {
+            writeln!(f, "threads:      {}", self.threads.len())?;
+        }
+        writeln!(f, "pointer:      {}", self.pointer)?;
+        writeln!(f, "memory size:  {}", self.mem_size)?;
+
+        let cells = self.non_zero_cells()
+            .take(Self::DISPLAYED_CELLS)
+            .map(|(idx, cell)| format!("[{idx}] = {cell}"))
+            .collect::<Vec<String>>();
+        let remaining = self.non_zero_cells().count() - cells.len();
+
+        if cells.is_empty() {
+            write!(f, "cells:        all zero")
+        } else if remaining > 0 {
+            write!(f, "cells:        {}, and {remaining} more", cells.join(", "))
+        } else {
+            write!(f, "cells:        {}", cells.join(", "))
+        }
+    }
+}
+
+/// what the `,` operation sets the current cell to once the input stream reached EOF
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EofBehavior {
+    /// sets the cell to the `fallback_input` of the interpreter, or `0` if there is none
+    #[default]
+    Fallback,
+    /// sets the cell to the max value of a cell, which is `-1` in interpreters with signed cells
+    Max,
+    /// leaves the cell unchanged
+    Unchanged,
+}
+
+impl EofBehavior {
+    /// returns the behavior of the provided lowercase name, such as `unchanged`, as taken by the bindings
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fallback" => Some(Self::Fallback),
+            "max" => Some(Self::Max),
+            "unchanged" => Some(Self::Unchanged),
+            _ => None,
+        }
+    }
+}
+
+/// how [`Brainfuck::with_output_path`] opens the output file, which is created if it does not exist
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OpenMode {
+    /// overwrites the contents of the file
+    #[default]
+    Truncate,
+    /// writes at the end of the contents of the file
+    Append,
+    /// fails if the file already exists
+    CreateNew,
+}
+
+impl OpenMode {
+    /// returns the options opening a file for writing in this mode
+    #[must_use]
+    pub fn options(self) -> OpenOptions {
+        let mut options = File::options();
+        match self {
+            Self::Truncate => options.write(true).create(true).truncate(true),
+            Self::Append => options.append(true).create(true),
+            Self::CreateNew => options.write(true).create_new(true),
+        };
+        options
+    }
+}
+
+/// the statistics of a single thread of a [`Dialect::Brainfork`] program
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThreadStats {
+    /// the index of the thread that forked this thread, or [`None`] for the main thread
+    pub parent: Option<usize>,
+    /// the amount of instructions the thread executed
+    pub instructions: usize,
+    /// whether or not the thread reached the end of the code
+    pub halted: bool,
+}
+
+/// a histogram of the amount of times each instruction was executed
+///
+/// every `]` that jumps back re-evaluates its `[`,
+/// so `[` is counted once per loop iteration as well
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpcodeCounts {
+    /// the amount of `+` operations executed
+    pub increment: usize,
+    /// the amount of `-` operations executed
+    pub decrement: usize,
+    /// the amount of `<` operations executed
+    pub move_left: usize,
+    /// the amount of `>` operations executed
+    pub move_right: usize,
+    /// the amount of `.` operations executed
+    pub output: usize,
+    /// the amount of `,` operations executed
+    pub input: usize,
+    /// the amount of `[` operations executed
+    pub loop_start: usize,
+    /// the amount of `]` operations executed
+    pub loop_end: usize,
+}
+
+impl OpcodeCounts {
+    /// returns the count of the provided instruction,
+    /// or [`None`] if it is not one of `+ - < > . , [ ]`
+    #[must_use]
+    pub const fn get(&self, opcode: char) -> Option<usize> {
+        match opcode {
+            '+' => Some(self.increment),
+            '-' => Some(self.decrement),
+            '<' => Some(self.move_left),
+            '>' => Some(self.move_right),
+            '.' => Some(self.output),
+            ',' => Some(self.input),
+            '[' => Some(self.loop_start),
+            ']' => Some(self.loop_end),
+            _ => None,
+        }
+    }
+
+    /// returns the total amount of instructions counted
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.increment + self.decrement
+            + self.move_left + self.move_right
+            + self.output + self.input
+            + self.loop_start + self.loop_end
+    }
+
+    /// increments the count of the provided instruction, ignoring any other characters
+    pub(crate) fn record(&mut self, opcode: char) {
+        let count = match opcode {
+            '+' => &mut self.increment,
+            '-' => &mut self.decrement,
+            '<' => &mut self.move_left,
+            '>' => &mut self.move_right,
+            '.' => &mut self.output,
+            ',' => &mut self.input,
+            '[' => &mut self.loop_start,
+            ']' => &mut self.loop_end,
+            _ => return,
+        };
+        *count += 1;
+    }
 }
 
 /// The struct representing a brainfuck interpreter instance
@@ -173,11 +610,16 @@ pub struct Brainfuck<'a> {
     ///
     /// defaults to [`None`], which is "infinite"
     pub memory_size: Option<usize>,
-    /// indicates whether or not to manually flush the output buffer every write
+    /// when to flush the output written by the `.` operation, which is buffered for the whole execution
     ///
-    /// if set to `false` it will let the process automatically flush (end of program or at every newline),
-    /// defaults to `true`
-    pub flush_output: bool,
+    /// defaults to [`FlushPolicy::EveryWrite`], see [`FlushPolicy`]
+    pub flush_policy: FlushPolicy,
+    /// indicates whether or not to discard the writes of the `.` operation entirely,
+    /// without writing them into the output stream nor invoking the output hook,
+    /// for measuring the speed of the interpreter alone
+    ///
+    /// defaults to `false`, see [`Brainfuck::with_no_output`]
+    pub discard_output: bool,
     /// this field is only of use if the input stream used is [`std::io::stdin`]
     ///
     /// it specifies whether or not to retrieve all the input data needed in one prompt the first time
@@ -192,13 +634,102 @@ pub struct Brainfuck<'a> {
     /// specifies whether or not to bench the execution
     ///
     /// useful for use cases in `WASM` where the system clock cannot be accessed,
-    /// defaults to `true`
+    /// defaults to `true`, unless the target cannot read the clock such as `wasm32-unknown-unknown`
     pub bench_execution: bool,
+    /// specifies whether or not to reject any character in the code that is neither an instruction nor whitespace
+    ///
+    /// when disabled, such characters are treated as comments,
+    /// defaults to `false`
+    pub strict_syntax: bool,
+    /// specifies how aggressively to optimize the code before executing it, see [`optimizer::Optimizer`]
+    ///
+    /// defaults to [`OptimizationLevel::None`]
+    pub optimization: OptimizationLevel,
+    /// the extension of brainfuck the code is written in, defaults to [`Dialect::Brainfuck`]
+    pub dialect: Dialect,
     /// an optional fallback [`char`] for the input operation
     /// in instances of EOF (end of input) on the input stream
     pub fallback_input: Option<char>,
+    /// what the input operation sets the current cell to on EOF,
+    /// defaults to [`EofBehavior::Fallback`]
+    pub eof: EofBehavior,
+    /// specifies whether or not `+` and `-` wrap the current cell around past its max value and below `0`
+    ///
+    /// when disabled, doing so aborts the execution with [`Error::CellOverflow`] instead,
+    /// defaults to `true`
+    pub wrapping: bool,
+    /// sets the limit on the wall-clock time the program can execute for
+    ///
+    /// defaults to [`None`], which is *no* limit
+    pub timeout: Option<Duration>,
+    /// sets the limit on the amount of bytes the memory array can take, see [`ExecutionLimits::memory_bytes`]
+    ///
+    /// defaults to [`None`], which is *no* limit
+    pub memory_limit: Option<usize>,
+    /// sets the limit on the amount of bytes written by the `.` operation
+    ///
+    /// defaults to [`None`], which is *no* limit
+    pub output_limit: Option<usize>,
+    /// sets the limit on the amount of loops nested within each other in the code
+    ///
+    /// defaults to [`None`], which is *no* limit
+    pub loop_depth_limit: Option<usize>,
+    /// an optional token that can be used to cancel the execution from another thread
+    ///
+    /// once it is set to `true`, the execution stops and returns [`Error::Cancelled`]
+    pub cancel_token: Option<Arc<AtomicBool>>,
+    /// specifies whether or not to abort the execution with [`Error::InfiniteLoop`]
+    /// once the program reaches the exact same state twice without any I/O in between
+    ///
+    /// this compares the whole memory array at the start of every loop iteration, which slows down the execution,
+    /// defaults to `false`
+    pub detect_infinite_loops: bool,
+    /// sets the limit on the amount of times a loop can iterate every time it is entered,
+    /// aborting the execution with [`Error::LoopIterationsExceeded`] once a loop iterates more
+    ///
+    /// defaults to [`None`], which is *no* limit
+    pub max_loop_iterations: Option<usize>,
+    /// specifies whether or not to record how often each cell is read from and written to
+    ///
+    /// the accesses are then available in [`ExecutionInfo::cell_accesses`],
+    /// defaults to `false`
+    pub track_cell_accesses: bool,
+    /// an optional callback invoked before every instruction is executed
+    ///
+    /// see [`Brainfuck::with_instruction_hook`]
+    pub instruction_hook: Option<InstructionHook<'a>>,
+    /// an optional callback invoked with every byte written by the `.` operation
+    ///
+    /// see [`Brainfuck::on_output`]
+    pub output_hook: Option<IoHook<'a>>,
+    /// an optional callback invoked with every byte read by the `,` operation
+    ///
+    /// see [`Brainfuck::on_input`]
+    pub input_hook: Option<IoHook<'a>>,
+    /// an optional observer notified of the execution's lifecycle events
+    ///
+    /// see [`Brainfuck::with_observer`]
+    pub observer: Option<Box<dyn ExecutionObserver + Send + 'a>>,
+    /// an optional generator of random values, making `?` an instruction setting the current cell to a random value
+    ///
+    /// see [`Brainfuck::with_random_seed`]
+    pub random: Option<RandomSource<'a>>,
+    /// the callbacks executing the custom instructions, keyed by their character
+    ///
+    /// see [`Brainfuck::with_custom_op`]
+    pub custom_ops: HashMap<char, CustomOp<'a>>,
+    /// the memory array and pointer the execution starts with, such as those of a previous [`ExecutionInfo`]
+    ///
+    /// defaults to [`None`], which is a memory array of `0`s with the pointer at the first cell
+    pub initial_state: Option<(Vec<u32>, usize)>,
     /// an instructions counter to count the number of instructions executed thus far
     instructions_ctn: usize,
+    /// the pause and kill state shared with an [`ExecutionHandle`], if it was spawned
+    control: Option<Arc<spawn::Control>>,
+    /// how the built-in generator set as `random` was seeded, if it was, for [`Brainfuck::clone_config`] to reseed it
+    random_seed: Option<random::Seed>,
+    /// the instructions whose callbacks [`Brainfuck::clone_config`] could not copy from the interpreter this one is a copy of
+    uncloned: Vec<char>,
 }
 
 impl<'a> Default for Brainfuck<'a> {
@@ -223,28 +754,152 @@ impl<'a> Brainfuck<'a> {
             output: None,
             max_cell_value: DEFAULT_MAX_CELL_VALUE,
             memory_size: None,
-            flush_output: true,
+            flush_policy: FlushPolicy::EveryWrite,
+            discard_output: false,
             prompt_stdin_once: false,
             instructions_limit: None,
-            bench_execution: true,
+            bench_execution: HAS_CLOCK,
+            strict_syntax: false,
+            optimization: OptimizationLevel::None,
+            dialect: Dialect::Brainfuck,
             fallback_input: None,
+            eof: EofBehavior::Fallback,
+            wrapping: true,
+            timeout: None,
+            memory_limit: None,
+            output_limit: None,
+            loop_depth_limit: None,
+            cancel_token: None,
+            detect_infinite_loops: false,
+            max_loop_iterations: None,
+            track_cell_accesses: false,
+            instruction_hook: None,
+            output_hook: None,
+            input_hook: None,
+            observer: None,
+            random: None,
+            custom_ops: HashMap::new(),
+            initial_state: None,
             instructions_ctn: 0,
+            control: None,
+            random_seed: None,
+            uncloned: Vec::new(),
         }
     }
 
     /// an alternative to `Self::new`,
     /// used when the code is in a source file instead of being directly accessible as a string in the code
     ///
+    /// files in another language known by their extension, such as `.ook` for Ook!,
+    /// are translated into brainfuck, see [`translate::Language::from_extension`]
+    ///
+    /// a first line starting with `#!`, such as `#!/usr/bin/env -S brainfuck -f`, is skipped,
+    /// so that it is not executed as code by a dialect or custom instruction using `#` or `!`
+    ///
+    /// the file is read like [`Brainfuck::from_reader`], so it does not need to be valid UTF-8
+    ///
     /// # Errors
     /// - [`Error::FileReadError`]: propogated from [`std::io::Error`]
     ///   when opening or reading the source file
+    /// - [`Error::InvalidToken`]: the file is in another language, and contains a token with no brainfuck equivalent
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let mut buf = String::new();
-        let mut file = File::open(path)
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(Error::FileReadError)?;
+        let mut buf = Self::from_reader(file)?.code;
+
+        if let Some(language) = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Language::from_extension)
+        {
+            buf = language.decode(&buf)?;
+        }
+        Ok(Self::new(buf))
+    }
+
+    /// an alternative to `Self::new`,
+    /// used when the code is read from a stream, such as a socket, a decompressor or [`std::io::stdin`]
+    ///
+    /// the code is read up to EOF, and does not need to be valid UTF-8,
+    /// invalid bytes only ever being comments and read as [`char::REPLACEMENT_CHARACTER`];
+    /// a first line starting with `#!` is skipped, like in [`Brainfuck::from_file`]
+    ///
+    /// # Errors
+    /// - [`Error::FileReadError`]: propogated from [`std::io::Error`] when reading the stream
+    ///
+    /// ```rust
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// let interp = Brainfuck::from_reader(&b"#!/usr/bin/env brainfuck\n+++\xFF."[..])?;
+    /// assert_eq!(interp.code, "\n+++\u{FFFD}.");
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)
             .map_err(Error::FileReadError)?;
+        let mut buf = String::from_utf8_lossy(&bytes).into_owned();
+
+        // keeps the newline ending the shebang, for the lines of the code to stay the same
+        if buf.starts_with("#!") {
+            buf.drain(..buf.find('\n').unwrap_or(buf.len()));
+        }
+        Ok(Self::new(buf))
+    }
+
+    /// an alternative to `Self::new`,
+    /// used when the code is hosted at an HTTP or HTTPS URL, such as the raw view of a pastebin
+    ///
+    /// the body is read like [`Brainfuck::from_reader`], failing once it exceeds `max_size` bytes,
+    /// and the whole request fails once `timeout` has elapsed;
+    /// code in another language known by the extension of the URL's path, such as `.ook`, is translated into brainfuck
+    ///
+    /// requires the `http` feature
+    ///
+    /// # Errors
+    /// - [`Error::FileReadError`]: the request failed, the response had an error status,
+    ///   or the body exceeded `max_size` bytes
+    /// - [`Error::InvalidToken`]: the code is in another language, and contains a token with no brainfuck equivalent
+    #[cfg(feature = "http")]
+    pub fn from_url(url: &str, max_size: usize, timeout: Duration) -> Result<Self> {
+        use std::io::{Error as IoError, ErrorKind};
+
+        let response = ureq::AgentBuilder::new()
+            .timeout(timeout)
+            .build()
+            .get(url)
+            .call()
+            .map_err(|err| Error::FileReadError(match err {
+                ureq::Error::Status(status, _) => IoError::other(format!("the server responded with `{status}`")),
+                ureq::Error::Transport(err) => IoError::other(err),
+            }))?;
 
-        file.read_to_string(&mut buf)
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .take(max_size as u64 + 1)
+            .read_to_end(&mut bytes)
             .map_err(Error::FileReadError)?;
+        if bytes.len() > max_size {
+            return Err(Error::FileReadError(IoError::new(
+                ErrorKind::InvalidData,
+                format!("the code exceeded the limit of {max_size} bytes"),
+            )));
+        }
+        let mut buf = Self::from_reader(bytes.as_slice())?.code;
+
+        let path = url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default();
+        if let Some(language) = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Language::from_extension)
+        {
+            buf = language.decode(&buf)?;
+        }
         Ok(Self::new(buf))
     }
 
@@ -261,11 +916,9 @@ impl<'a> Brainfuck<'a> {
     #[must_use]
     pub fn with_input<I>(mut self, input: I) -> Self
     where
-        I: Read + 'static
+        I: Read + Send + 'static
     {
-        self.input = Some(
-            Reader::Value(Box::new(input))
-        );
+        self.set_input(input);
         self
     }
 
@@ -273,11 +926,9 @@ impl<'a> Brainfuck<'a> {
     #[must_use]
     pub fn with_output<O>(mut self, output: O) -> Self
     where
-        O: Write + 'static
+        O: Write + Send + 'static
     {
-        self.output = Some(
-            Writer::Value(Box::new(output))
-        );
+        self.set_output(output);
         self
     }
 
@@ -285,11 +936,9 @@ impl<'a> Brainfuck<'a> {
     #[must_use]
     pub fn with_input_ref<I>(mut self, input: &'a mut I) -> Self
     where
-        I: Read + 'static
+        I: Read + Send + 'static
     {
-        self.input = Some(
-            Reader::Ref(input)
-        );
+        self.set_input_ref(input);
         self
     }
 
@@ -297,12 +946,119 @@ impl<'a> Brainfuck<'a> {
     #[must_use]
     pub fn with_output_ref<O>(mut self, output: &'a mut O) -> Self
     where
-        O: Write + 'static
+        O: Write + Send + 'static
+    {
+        self.set_output_ref(output);
+        self
+    }
+
+    /// sets the input stream **passing by value**, for the `,` operation,
+    /// replacing the one of the previous executions
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use brainfuck_exe::{Brainfuck, Writer};
+    ///
+    /// let mut interp = Brainfuck::new(",[.,]");
+    ///
+    /// for input in ["abc", "def"] {
+    ///     interp.set_input(Cursor::new(input));
+    ///     interp.set_output(Vec::new());
+    ///     interp.execute()?;
+    ///
+    ///     // the output stream is taken back, leaving the interpreter with none
+    ///     let output = interp.take_output();
+    ///     assert!(matches!(output, Some(Writer::Value(_))));
+    /// }
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    pub fn set_input<I>(&mut self, input: I)
+    where
+        I: Read + Send + 'static
+    {
+        self.input = Some(
+            Reader::Value(Box::new(input))
+        );
+    }
+
+    /// sets the output stream **passing by value**, for the `.` operation,
+    /// replacing the one of the previous executions
+    pub fn set_output<O>(&mut self, output: O)
+    where
+        O: Write + Send + 'static
+    {
+        self.output = Some(
+            Writer::Value(Box::new(output))
+        );
+    }
+
+    /// sets the input stream **passing by reference**, for the `,` operation,
+    /// replacing the one of the previous executions
+    pub fn set_input_ref<I>(&mut self, input: &'a mut I)
+    where
+        I: Read + Send + 'static
+    {
+        self.input = Some(
+            Reader::Ref(input)
+        );
+    }
+
+    /// sets the output stream **passing by reference**, for the `.` operation,
+    /// replacing the one of the previous executions
+    pub fn set_output_ref<O>(&mut self, output: &'a mut O)
+    where
+        O: Write + Send + 'static
     {
         self.output = Some(
             Writer::Ref(output)
         );
-        self
+    }
+
+    /// takes back the input stream, if any, leaving the `,` operation reading from [`std::io::stdin`]
+    pub fn take_input(&mut self) -> Option<Reader<'a>> {
+        self.input.take()
+    }
+
+    /// takes back the output stream, if any, leaving the `.` operation writing into [`std::io::stdout`]
+    ///
+    /// the stream can still be written into or flushed through [`Writer`], which dereferences to [`Write`]
+    pub fn take_output(&mut self) -> Option<Writer<'a>> {
+        self.output.take()
+    }
+
+    /// builder method to specify the file to read the input from, for the `,` operation
+    ///
+    /// # Errors
+    /// - [`Error::FileReadError`]: propogated from [`std::io::Error`] when opening the file
+    pub fn with_input_path<P: AsRef<Path>>(self, path: P) -> Result<Self> {
+        let file = File::open(path)
+            .map_err(Error::FileReadError)?;
+        Ok(self.with_input(BufReader::new(file)))
+    }
+
+    /// builder method to specify the file to write the output into, for the `.` operation,
+    /// opened in the provided mode
+    ///
+    /// the writes are buffered, the buffer being flushed according to [`Self::with_flush_policy`]
+    ///
+    /// # Errors
+    /// - [`Error::FileWriteError`]: propogated from [`std::io::Error`] when opening the file,
+    ///   such as when it already exists with [`OpenMode::CreateNew`]
+    ///
+    /// ```rust,no_run
+    /// use brainfuck_exe::{Brainfuck, OpenMode};
+    ///
+    /// Brainfuck::from_file("tests/hello_world.bf")?
+    ///     .with_output_path("output.txt", OpenMode::Truncate)?
+    ///     .execute()?;
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    pub fn with_output_path<P: AsRef<Path>>(self, path: P, mode: OpenMode) -> Result<Self> {
+        let file = mode
+            .options()
+            .open(path)
+            .map_err(Error::FileWriteError)?;
+        Ok(self.with_output(BufWriter::new(file)))
     }
 
     /// builder method to specify the max value of a cell
@@ -319,10 +1075,71 @@ impl<'a> Brainfuck<'a> {
         self
     }
 
-    /// builder method to indicate whether or not to flush the output stream on every write
+    /// builder method to specify the memory array and pointer the execution starts with,
+    /// such as those of a previous [`ExecutionInfo`] to resume from
+    ///
+    /// the memory array is resized to the set memory size, if any, or grown to include the pointer,
+    /// and values exceeding the max value of a cell wrap around;
+    /// the pointer wraps around the set memory size, while executing fails with [`Error::InitialPointerOutOfBounds`]
+    /// if the growable memory array cannot be grown to include it, within the set limit of bytes or at all;
+    /// the code is never optimized, as the optimizer assumes the memory starts out as `0`s
+    ///
+    /// ```rust
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// let info = Brainfuck::new("+++>++").execute()?;
+    /// let info = Brainfuck::new("[-<+>]")
+    ///     .with_initial_state(info.cells, info.pointer)
+    ///     .execute()?;
+    ///
+    /// assert_eq!(info.cells, [5, 0]);
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn with_initial_state(mut self, cells: Vec<u32>, pointer: usize) -> Self {
+        self.initial_state = Some((cells, pointer));
+        self
+    }
+
+    /// builder method to indicate whether or not to flush the output stream on every write,
+    /// a shorthand for [`FlushPolicy::EveryWrite`] or [`FlushPolicy::OnNewline`]
+    #[must_use]
+    pub const fn with_flush(self, flush: bool) -> Self {
+        self.with_flush_policy(if flush {
+            FlushPolicy::EveryWrite
+        } else {
+            FlushPolicy::OnNewline
+        })
+    }
+
+    /// builder method to specify when to flush the output written by the `.` operation
+    #[must_use]
+    pub const fn with_flush_policy(mut self, policy: FlushPolicy) -> Self {
+        self.flush_policy = policy;
+        self
+    }
+
+    /// builder method to discard the writes of the `.` operation entirely, for benchmarks and batch analyses
+    /// measuring the speed of the interpreter alone
+    ///
+    /// the `.` operation still counts as an executed instruction, but its output is dropped by every way of executing the code:
+    /// nothing is written into the configured output stream, [`std::io::stdout`] or the one passed to
+    /// [`Brainfuck::execute_with_io`] and `Brainfuck::execute_async`, nothing is yielded by [`Brainfuck::start`],
+    /// [`Brainfuck::iter_events`] and [`Brainfuck::into_stream`], nothing is collected by a [`Scheduler`],
+    /// and the output hook is not invoked
+    ///
+    /// ```rust
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// let info = Brainfuck::new("+[.+]")
+    ///     .with_no_output()
+    ///     .execute()?;
+    /// assert_eq!(info.cells[0], 0);
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
     #[must_use]
-    pub const fn with_flush(mut self, flush: bool) -> Self {
-        self.flush_output = flush;
+    pub const fn with_no_output(mut self) -> Self {
+        self.discard_output = true;
         self
     }
 
@@ -346,6 +1163,35 @@ impl<'a> Brainfuck<'a> {
         self.bench_execution = bench;
         self
     }
+    /// builder method to specify whether or not to reject any character that is neither an instruction nor whitespace
+    ///
+    /// executing such code returns [`Error::UnexpectedCharacter`]
+    #[must_use]
+    pub const fn with_strict_syntax(mut self, strict: bool) -> Self {
+        self.strict_syntax = strict;
+        self
+    }
+
+    /// builder method to specify how aggressively to optimize the code before executing it
+    ///
+    /// the optimized code executes fewer instructions, but behaves the same otherwise;
+    /// the code indices and positions reported to hooks, observers and in errors still refer to the original code
+    ///
+    /// the code is only optimized on a memory array of a fixed size, see [`Brainfuck::with_mem_size`],
+    /// as the growable one wraps `<` on the first cell around to the last cell allocated so far
+    #[must_use]
+    pub const fn with_optimization(mut self, level: OptimizationLevel) -> Self {
+        self.optimization = level;
+        self
+    }
+
+    /// builder method to specify the extension of brainfuck the code is written in, see [`Dialect`]
+    #[must_use]
+    pub const fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
     /// builder method to set a fallback [`char`] for instances of EOF on the input stream
     #[must_use]
     pub const fn with_fallback_input(mut self, fallback: char) -> Self {
@@ -353,6 +1199,239 @@ impl<'a> Brainfuck<'a> {
         self
     }
 
+    /// builder method to specify what the `,` operation sets the current cell to on EOF
+    #[must_use]
+    pub const fn with_eof(mut self, eof: EofBehavior) -> Self {
+        self.eof = eof;
+        self
+    }
+
+    /// builder method to specify whether or not `+` and `-` wrap the current cell around,
+    /// rather than aborting the execution with [`Error::CellOverflow`]
+    ///
+    /// the code is never optimized with wrapping disabled, as the optimizer folds the instructions assuming they wrap
+    #[must_use]
+    pub const fn with_wrapping(mut self, wrapping: bool) -> Self {
+        self.wrapping = wrapping;
+        self
+    }
+
+    /// builder method to set the maximum wall-clock time the program can execute for
+    ///
+    /// this is independent of `self.bench_execution` and `self.instructions_limit`,
+    /// and is ignored on targets that cannot read the clock such as `wasm32-unknown-unknown`
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// builder method to set a token that cancels the execution once it is set to `true`
+    ///
+    /// the token is checked before every step, so a host thread can abort a running program cleanly
+    #[must_use]
+    pub fn with_cancel_token(mut self, token: Arc<AtomicBool>) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// builder method to specify whether or not to detect infinite loops
+    ///
+    /// a program reaching the exact same state twice without any I/O in between never terminates,
+    /// so the execution is aborted with [`Error::InfiniteLoop`] instead;
+    /// this is friendlier than an instructions limit for untrusted code, but cannot detect every infinite loop
+    #[must_use]
+    pub const fn with_infinite_loop_detection(mut self, detect: bool) -> Self {
+        self.detect_infinite_loops = detect;
+        self
+    }
+
+    /// builder method to set the max amount of times a loop can iterate every time it is entered
+    ///
+    /// a loop iterating more aborts the execution with [`Error::LoopIterationsExceeded`], along with the span of the loop;
+    /// this pinpoints the loop stuck in an accidental infinite loop, unlike an instructions limit,
+    /// while the loops that are entered many times, iterating a few times every time, are left alone
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{Brainfuck, Error};
+    ///
+    /// let code = "++[>+++[-]<-]\n+[+]";
+    /// let err = Brainfuck::new(code)
+    ///     .with_max_loop_iterations(100)
+    ///     .execute()
+    ///     .unwrap_err();
+    ///
+    /// let Error::LoopIterationsExceeded { span, .. } = err else { unreachable!() };
+    /// assert_eq!(&code[span.range()], "[+]");
+    /// ```
+    #[must_use]
+    pub const fn with_max_loop_iterations(mut self, limit: usize) -> Self {
+        self.max_loop_iterations = Some(limit);
+        self
+    }
+
+    /// builder method to specify whether or not to record how often each cell is read from and written to
+    ///
+    /// the accesses can be rendered with [`ExecutionInfo::heatmap`]
+    #[must_use]
+    pub const fn with_cell_tracking(mut self, track: bool) -> Self {
+        self.track_cell_accesses = track;
+        self
+    }
+
+    /// builder method to set a callback that is invoked before every instruction is executed
+    ///
+    /// it receives a [`HookCtx`] with the instruction, code index, pointer and current cell,
+    /// and can abort the execution by returning [`ControlFlow::Break`],
+    /// making it return [`Error::Cancelled`]
+    ///
+    /// ```rust
+    /// use std::ops::ControlFlow;
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// // aborts as soon as any cell reaches `3`
+    /// let result = Brainfuck::new("+[+]")
+    ///     .with_instruction_hook(|ctx|
+    ///         if ctx.cell >= 3 {
+    ///             ControlFlow::Break(())
+    ///         } else {
+    ///             ControlFlow::Continue(())
+    ///         }
+    ///     )
+    ///     .execute();
+    /// assert!(result.is_err());
+    /// ```
+    #[must_use]
+    pub fn with_instruction_hook<F>(mut self, hook: F) -> Self
+    where
+        F: FnMut(&HookCtx) -> ControlFlow<()> + Send + 'a
+    {
+        self.instruction_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// builder method to make `?` an instruction setting the current cell to a random value,
+    /// generated by the provided generator and wrapped into `0..=max_cell_value`
+    ///
+    /// the code is never optimized while `?` is an instruction, as the optimizer would treat it as a comment
+    #[must_use]
+    pub fn with_rng<F>(mut self, random: F) -> Self
+    where
+        F: FnMut() -> u32 + Send + 'a
+    {
+        self.random = Some(Box::new(random));
+        self.random_seed = None;
+        self
+    }
+
+    /// builder method to make `?` an instruction setting the current cell to a random value,
+    /// generated by a built-in generator seeded with the provided seed
+    ///
+    /// executing the same program with the same seed always produces the same values, which is useful for tests
+    ///
+    /// ```rust
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// let first = Brainfuck::new("?>?>?").with_random_seed(42).execute()?;
+    /// let second = Brainfuck::new("?>?>?").with_random_seed(42).execute()?;
+    ///
+    /// assert_eq!(first.cells, second.cells);
+    /// assert!(first.cells.iter().all(|cell| *cell <= 255));
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn with_random_seed(self, seed: u64) -> Self {
+        self.with_seed(random::Seed::Fixed(seed))
+    }
+
+    /// builder method to make `?` an instruction setting the current cell to a random value,
+    /// generated by a built-in generator seeded differently for every interpreter
+    #[must_use]
+    pub fn with_random(self) -> Self {
+        self.with_seed(random::Seed::Entropy)
+    }
+
+    /// helper method to set the built-in generator seeded the provided way
+    fn with_seed(mut self, seed: random::Seed) -> Self {
+        self.random = Some(seed.generator());
+        self.random_seed = Some(seed);
+        self
+    }
+
+    /// builder method to add a custom instruction, executed by the provided callback
+    ///
+    /// it receives an [`OpCtx`] with mutable access to the memory array and pointer, and the output to write,
+    /// and can abort the execution by returning [`ControlFlow::Break`], making it return [`Error::Cancelled`];
+    /// the built-in instructions, including those of the set [`Dialect`], cannot be overridden
+    ///
+    /// the code is never optimized while there are custom instructions, as the optimizer would treat them as comments
+    ///
+    /// ```rust
+    /// use std::ops::ControlFlow;
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// // `*` doubles the current cell, and `#` prints it as a number
+    /// let mut output = Vec::new();
+    /// Brainfuck::new("+++**#")
+    ///     .with_custom_op('*', |ctx| {
+    ///         ctx.cells[*ctx.pointer] *= 2;
+    ///         ControlFlow::Continue(())
+    ///     })
+    ///     .with_custom_op('#', |ctx| {
+    ///         ctx.output = ctx.cells[*ctx.pointer].to_string();
+    ///         ControlFlow::Continue(())
+    ///     })
+    ///     .with_output_ref(&mut output)
+    ///     .execute()?;
+    ///
+    /// assert_eq!(output, b"12");
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn with_custom_op<F>(mut self, opcode: char, op: F) -> Self
+    where
+        F: FnMut(&mut OpCtx) -> ControlFlow<()> + Send + 'a
+    {
+        self.custom_ops.insert(opcode, Box::new(op));
+        self
+    }
+
+    /// builder method to set an observer that is invoked with every byte written by the `.` operation
+    ///
+    /// it fires alongside the configured output stream, which is still written into as usual
+    #[must_use]
+    pub fn on_output<F>(mut self, hook: F) -> Self
+    where
+        F: FnMut(u8) + Send + 'a
+    {
+        self.output_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// builder method to set an observer that is invoked with every byte read by the `,` operation
+    ///
+    /// it fires alongside the configured input stream, but not when the fallback input is used on EOF
+    #[must_use]
+    pub fn on_input<F>(mut self, hook: F) -> Self
+    where
+        F: FnMut(u8) + Send + 'a
+    {
+        self.input_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// builder method to set an [`ExecutionObserver`] that is notified of the execution's lifecycle events
+    ///
+    /// pass in `&mut observer` to be able to inspect its state after the execution
+    #[must_use]
+    pub fn with_observer<O>(mut self, observer: O) -> Self
+    where
+        O: ExecutionObserver + Send + 'a
+    {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
     /// a getter that returns the number of instructions executed thus far
     #[must_use]
     pub const fn instructions_count(&self) -> usize {
@@ -373,6 +1452,82 @@ impl<'a> Brainfuck<'a> {
         self.output
     }
 
+    /// creates a new interpreter with the same code and configuration as this one,
+    /// without any of the input and output streams, nor any of the callbacks such as hooks and custom instructions
+    ///
+    /// the built-in generator of `?`, set with [`Brainfuck::with_random_seed`] or [`Brainfuck::with_random`],
+    /// is carried over, the copy starting over from the same seed or from a new random one respectively;
+    /// a generator set with [`Brainfuck::with_rng`] and the custom instructions cannot be copied,
+    /// so executing code using them fails with [`Error::MissingCallback`] until they are set again on the copy
+    ///
+    /// ```rust
+    /// use std::ops::ControlFlow;
+    /// use brainfuck_exe::{Brainfuck, Error};
+    ///
+    /// let interp = Brainfuck::new("?").with_random_seed(7);
+    /// assert_eq!(interp.clone_config().execute()?.cells, interp.clone_config().execute()?.cells);
+    ///
+    /// let interp = Brainfuck::new("+*").with_custom_op('*', |_| ControlFlow::Continue(()));
+    /// assert!(matches!(interp.clone_config().execute(), Err(Error::MissingCallback { opcode: '*', .. })));
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn clone_config<'b>(&self) -> Brainfuck<'b> {
+        let random_seed = self.random
+            .as_ref()
+            .and(self.random_seed);
+        let mut uncloned = self.custom_ops
+            .keys()
+            .copied()
+            .chain((self.random.is_some() && random_seed.is_none()).then_some('?'))
+            .chain(self.uncloned
+                .iter()
+                .copied()
+                .filter(|opcode| !self.is_instruction(*opcode))
+            )
+            .collect::<Vec<char>>();
+        uncloned.sort_unstable();
+        uncloned.dedup();
+
+        Brainfuck {
+            code: self.code.clone(),
+            input: None,
+            output: None,
+            max_cell_value: self.max_cell_value,
+            memory_size: self.memory_size,
+            flush_policy: self.flush_policy,
+            discard_output: self.discard_output,
+            prompt_stdin_once: self.prompt_stdin_once,
+            instructions_limit: self.instructions_limit,
+            bench_execution: self.bench_execution,
+            strict_syntax: self.strict_syntax,
+            optimization: self.optimization,
+            dialect: self.dialect,
+            fallback_input: self.fallback_input,
+            eof: self.eof,
+            wrapping: self.wrapping,
+            timeout: self.timeout,
+            memory_limit: self.memory_limit,
+            output_limit: self.output_limit,
+            loop_depth_limit: self.loop_depth_limit,
+            cancel_token: self.cancel_token.clone(),
+            detect_infinite_loops: self.detect_infinite_loops,
+            max_loop_iterations: self.max_loop_iterations,
+            track_cell_accesses: self.track_cell_accesses,
+            instruction_hook: None,
+            output_hook: None,
+            input_hook: None,
+            observer: None,
+            random: random_seed.map(random::Seed::generator),
+            custom_ops: HashMap::new(),
+            initial_state: self.initial_state.clone(),
+            instructions_ctn: 0,
+            control: None,
+            random_seed,
+            uncloned,
+        }
+    }
+
     /// basic helper function to retrieve the fallback char for the input stream
     #[inline]
     fn get_fallback_char(&self) -> u32 {
@@ -384,36 +1539,368 @@ impl<'a> Brainfuck<'a> {
     ///
     /// it accomplishes such in one prompt, retrieving all the data at once
     /// as a fallback to if no other input stream is specified for the `,` operation
+    ///
+    /// returns [`None`] on EOF
     #[must_use]
-    fn read_from_stdin_once(&self) -> u32 {
+    fn read_from_stdin_once() -> Option<u32> {
         let mut buffer = [0];
-        match std::io::stdin()
+        std::io::stdin()
             .read_exact(&mut buffer[0..1])
-        {
-            Ok(()) => u32::from(buffer[0]),
-            Err(_) => self.get_fallback_char(),
-        }
+            .ok()
+            .map(|()| u32::from(buffer[0]))
     }
 
     /// helper method to read from [`std::io::stdin`]
     ///
     /// it prompts every time this function is called however
     /// as a fallback to if no other input stream is specified for the `,` operation
+    ///
+    /// returns [`None`] on EOF
     #[must_use]
-    fn read_from_stdin(&self) -> u32 {
+    fn read_from_stdin() -> Option<u32> {
         let mut buffer = String::new();
-        match std::io::stdin()
+        std::io::stdin()
             .read_line(&mut buffer)
+            .ok()
+            .and_then(|_| buffer.chars().next())
+            .map(u32::from)
+    }
+
+    /// helper method to read 1 byte for the `,` operation from the provided input stream
+    ///
+    /// returns [`None`] on EOF
+    fn read_byte<R: Read + ?Sized>(reader: &mut R) -> Option<u32> {
+        let mut buffer = [0];
+        reader.read_exact(&mut buffer[0..1])
+            .ok()
+            .map(|()| u32::from(buffer[0]))
+    }
+
+    /// helper method to read 1 byte for the `,` operation from `self.input`
+    /// defaulting to [`std::io::stdin`]
+    ///
+    /// returns [`None`] on EOF
+    fn read_input(&mut self) -> Option<u32> {
+        if let Some(ref mut reader) = self.input {
+            Self::read_byte(&mut **reader)
+        } else if self.prompt_stdin_once {
+            Self::read_from_stdin_once()
+        } else {
+            Self::read_from_stdin()
+        }
+    }
+
+    /// helper method to resolve the value of a `,` operation from what was read from the input stream
+    ///
+    /// it invokes the input hook, or follows the [`EofBehavior`] in the case of EOF ([`None`])
+    fn resolve_input(&mut self, value: Option<u32>, machine: &Machine) -> u32 {
+        let Some(value) = value else {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(eof = ?self.eof, "input reached EOF");
+
+            return match self.eof {
+                EofBehavior::Fallback => self.get_fallback_char(),
+                EofBehavior::Max => self.max_cell_value,
+                EofBehavior::Unchanged => machine.current_cell(),
+            };
+        };
+        #[cfg(feature = "tracing")]
+        tracing::trace!(value, "read input");
+        telemetry::record_input(1);
+
+        if let (Some(hook), Ok(byte)) = (
+            self.input_hook.as_mut(),
+            u8::try_from(value),
+        ) {
+            hook(byte);
+        }
+        value
+    }
+
+    /// helper method to invoke the output hook for every byte written by a `.` operation
+    fn notify_output(&mut self, bytes: &[u8]) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(?bytes, "wrote output");
+        telemetry::record_output(bytes.len());
+
+        if let Some(ref mut hook) = self.output_hook {
+            bytes.iter()
+                .for_each(|byte| hook(*byte));
+        }
+    }
+
+    /// helper method driving the execution of [`Brainfuck::execute`] and [`Brainfuck::execute_with_io`],
+    /// reading the `,` operation with `read` and writing the `.` operation into `output`,
+    /// which is buffered according to `self.flush_policy` and flushed at the end even if the execution fails
+    fn run<W, F>(&mut self, machine: &mut Machine, output: &mut W, mut read: F) -> Result<()>
+    where
+        W: Write + ?Sized,
+        F: FnMut(&mut Self) -> Option<u32>,
+    {
+        let mut output = PolicyWriter::new(output, self.flush_policy);
+
+        let result = loop {
+            let action = match self.advance(machine) {
+                Ok(action) => action,
+                Err(err) => break Err(err),
+            };
+            match action {
+                Action::Continue => (),
+                Action::Output(_) if self.discard_output => (),
+                Action::Output(chr) => {
+                    let mut buf = [0; 4];
+                    let bytes = chr.encode_utf8(&mut buf).as_bytes();
+                    self.notify_output(bytes);
+
+                    if let Err(err) = output.write_output(bytes) {
+                        break Err(Self::output_failed(machine, err));
+                    }
+                },
+                Action::Input => {
+                    let value = read(self);
+                    let value = self.resolve_input(value, machine);
+                    machine.write_input(value);
+                },
+                Action::Halt => break Ok(()),
+            }
+        };
+        // the error of the execution takes precedence over failing to flush the output written before it
+        let flushed = output.finish();
+        result?;
+        flushed.map_err(|err| Self::output_failed(machine, err))
+    }
+
+    /// validates the configuration, which is also done at the start of every execution
+    ///
+    /// # Errors
+    /// - [`Error::ZeroMemorySize`]: the memory size was set to `0`
+    /// - [`Error::ZeroMaxCellValue`]: the max value of a cell was set to `0`
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{Brainfuck, Error};
+    ///
+    /// let bf = Brainfuck::new("+.").with_mem_size(0);
+    /// assert!(matches!(bf.validate(), Err(Error::ZeroMemorySize)));
+    /// ```
+    pub const fn validate(&self) -> Result<()> {
+        if matches!(self.memory_size, Some(0)) {
+            return Err(Error::ZeroMemorySize);
+        }
+        if self.max_cell_value == 0 {
+            return Err(Error::ZeroMaxCellValue);
+        }
+        Ok(())
+    }
+
+    /// returns whether or not the provided character is an instruction of the dialect,
+    /// `?` when random values are enabled, or a custom instruction
+    pub(crate) fn is_instruction(&self, chr: char) -> bool {
+        self.dialect.instructions().contains(chr)
+            || (self.random.is_some() && chr == '?')
+            || self.custom_ops.contains_key(&chr)
+    }
+
+    /// helper method to prepare a new execution of the code
+    ///
+    /// it resets the instructions counter and notifies the observer
+    fn begin(&mut self) -> Result<Machine> {
+        let machine = Machine::new(self)
+            .inspect_err(|err| telemetry::record_failure(err, 0))?;
+        self.instructions_ctn = 0;
+
+        if let Some(ref mut observer) = self.observer {
+            observer.on_start();
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!("execution started");
+
+        Ok(machine)
+    }
+
+    /// helper function to build the error of a failed `.` operation, recording the failure
+    fn output_failed(machine: &Machine, source: std::io::Error) -> Error {
+        let err = Error::OutputError {
+            source,
+            context: machine.context(machine.last_code_idx),
+        };
+        telemetry::record_failure(&err, machine.instructions);
+        err
+    }
+
+    /// helper method to take a single step of the execution
+    ///
+    /// it invokes the instruction hook and observer, steps the machine and then checks the limits
+    fn advance(&mut self, machine: &mut Machine) -> Result<Action> {
+        if self.instruction_hook.is_some() || self.observer.is_some() {
+            if let Some(ctx) = machine.hook_ctx() {
+                if let Some(ref mut observer) = self.observer {
+                    observer.on_instruction(&ctx);
+                }
+                if let Some(ref mut hook) = self.instruction_hook {
+                    if hook(&ctx).is_break() {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!("execution aborted by the instruction hook");
+
+                        let err = Error::Cancelled(
+                            machine.context(machine.code_idx)
+                        );
+                        telemetry::record_failure(&err, machine.instructions);
+                        return Err(err);
+                    }
+                }
+            }
+        }
+        let action = machine.step();
+        if let Action::Output(chr) = action {
+            machine.output_bytes += chr.len_utf8();
+        }
+
+        if let Some(opcode) = machine.pending_op.take() {
+            if let Some(op) = self.custom_ops.get_mut(&opcode) {
+                let mut ctx = OpCtx {
+                    opcode,
+                    cells: &mut machine.cells,
+                    pointer: &mut machine.ptr,
+                    max_cell_value: self.max_cell_value,
+                    output: String::new(),
+                };
+                let flow = op(&mut ctx);
+                let output = ctx.output;
+                machine.finish_custom_op(&output);
+
+                if flow.is_break() {
+                    let err = Error::Cancelled(
+                        machine.context(machine.last_code_idx)
+                    );
+                    telemetry::record_failure(&err, machine.instructions);
+                    return Err(err);
+                }
+            }
+        }
+        if machine.is_awaiting_random() {
+            let value = self.random
+                .as_mut()
+                .map_or(0, |random| random());
+            machine.write_random(value);
+        }
+
+        if let Some(ref mut observer) = self.observer {
+            match machine.loop_event {
+                Some(LoopEvent::Enter(idx)) => observer.on_loop_enter(machine.origin(idx)),
+                Some(LoopEvent::Exit(idx)) => observer.on_loop_exit(machine.origin(idx)),
+                None => (),
+            }
+            if action == Action::Halt {
+                observer.on_halt(&machine.info());
+            }
+        }
+        if action == Action::Halt {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(instructions = machine.instructions, "execution halted");
+            telemetry::record_completion(machine);
+        }
+        self.check_limits(machine)
+            .inspect_err(|err| telemetry::record_failure(err, machine.instructions))?;
+
+        Ok(action)
+    }
+
+    /// helper method to sync the instructions counter with the machine's
+    /// and to check it against `self.instructions_limit`, `self.timeout` and `self.cancel_token`
+    fn check_limits(&mut self, machine: &Machine) -> Result<()> {
+        self.instructions_ctn = machine.instructions;
+
+        if let Some(cap) = self.instructions_limit {
+            if self.instructions_ctn > cap {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(limit = cap, "instructions limit exceeded");
+
+                return Err(Error::MaxInstructionsExceeded {
+                    limit: cap,
+                    context: machine.context(machine.last_code_idx),
+                });
+            }
+        }
+        if let Some(timeout) = self.timeout {
+            if machine.timed_out() {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(?timeout, "execution timed out");
+
+                return Err(Error::TimedOut {
+                    timeout,
+                    context: machine.context(machine.last_code_idx),
+                });
+            }
+        }
+        if let Some(limit) = self.memory_limit {
+            if machine.memory_bytes() > limit {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(limit, "memory limit exceeded");
+
+                return Err(Error::MemoryLimitExceeded {
+                    limit,
+                    context: machine.context(machine.last_code_idx),
+                });
+            }
+        }
+        if let Some(limit) = self.output_limit {
+            if machine.output_bytes > limit {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(limit, "output limit exceeded");
+
+                return Err(Error::OutputLimitExceeded {
+                    limit,
+                    context: machine.context(machine.last_code_idx),
+                });
+            }
+        }
+        if machine.overflowed {
+            return Err(Error::CellOverflow(
+                machine.context(machine.last_code_idx)
+            ));
+        }
+        if let Some(id) = machine.undefined_procedure {
+            return Err(Error::UndefinedProcedure {
+                id,
+                context: machine.context(machine.last_code_idx),
+            });
+        }
+        if let (Some(limit), Some(code_idx)) = (self.max_loop_iterations, machine.exceeded_loop) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(limit, "loop iterations limit exceeded");
+
+            return Err(Error::LoopIterationsExceeded {
+                limit,
+                span: machine.loop_span(code_idx),
+                context: machine.context(machine.last_code_idx),
+            });
+        }
+        if machine.repeated_state {
+            #[cfg(feature = "tracing")]
+            tracing::warn!("infinite loop detected");
+
+            return Err(Error::InfiniteLoop(
+                machine.context(machine.last_code_idx)
+            ));
+        }
+        if let Some(ref control) = self.control {
+            control.wait_while_paused();
+        }
+        if self.cancel_token
+            .as_ref()
+            .is_some_and(|token| token.load(Ordering::Relaxed))
+            || self.control
+                .as_ref()
+                .is_some_and(|control| control.is_killed())
         {
-            Ok(_) => buffer
-                .chars()
-                .next()
-                .map_or_else(
-                    || self.get_fallback_char(),
-                    u32::from,
-                ),
-            Err(_) => self.get_fallback_char(),
+            #[cfg(feature = "tracing")]
+            tracing::warn!("execution cancelled");
+
+            return Err(Error::Cancelled(
+                machine.context(machine.last_code_idx)
+            ));
         }
+        Ok(())
     }
 
     /// executes the provided brainfuck code
@@ -444,174 +1931,81 @@ impl<'a> Brainfuck<'a> {
     /// - `]`: the closing bracket for a loop, paired with `[`
     ///   if the current cell != 0, jump back to corresponding `[`
     ///
+    /// - `?`: only an instruction if a random generator is set, see [`Brainfuck::with_rng`]
+    ///   sets the current cell to a random value
+    /// - any custom instruction, executed by its callback, see [`Brainfuck::with_custom_op`]
+    ///
+    /// the instructions of the set [`Dialect`], if any, are described by it
+    ///
     /// returns [`ExecutionInfo`]: a struct containing various information on the program's execution
     /// such as the used memory array, the final pointer, instructions count etc.
     ///
     /// # Errors
-    /// - [`Error::MismatchedBrackets`]: the amount of `[` in the code does not equal the amount of `]`
-    /// - [`Error::IoError`]: Propogated from [`std::io::Error`] in the `.` operation
+    /// - [`Error::ZeroMemorySize`] and [`Error::ZeroMaxCellValue`]: the configuration is invalid, see [`Brainfuck::validate`]
+    /// - [`Error::InitialPointerOutOfBounds`]: the pointer of the initial state is past the cells the memory array can take
+    /// - [`Error::MismatchedBrackets`]: the code contains an unmatched `[` or a stray `]`
+    /// - [`Error::UnexpectedCharacter`]: the code contains a character that is neither an instruction
+    ///   nor whitespace, while `strict_syntax` is enabled
+    /// - [`Error::MissingCallback`]: the code uses an instruction whose callback [`Brainfuck::clone_config`]
+    ///   could not copy
+    /// - [`Error::OutputError`]: Propogated from [`std::io::Error`] in the `.` operation
+    /// - [`Error::MaxInstructionsExceeded`]: the set instructions limit was exceeded
+    /// - [`Error::Cancelled`]: the set `cancel_token` was set to `true`, or the instruction hook aborted
+    /// - [`Error::TimedOut`]: the set timeout has elapsed
+    /// - [`Error::MemoryLimitExceeded`], [`Error::OutputLimitExceeded`] and [`Error::LoopDepthExceeded`]:
+    ///   one of the set [`ExecutionLimits`] was exceeded
+    /// - [`Error::InfiniteLoop`]: the program is stuck in an infinite loop, if they are being detected
+    /// - [`Error::LoopIterationsExceeded`]: a loop iterated more than the set max amount of loop iterations
+    /// - [`Error::UndefinedProcedure`]: the program called a procedure that was never defined, in [`Dialect::Pbrain`]
+    /// - [`Error::CellOverflow`]: `+` or `-` would have wrapped the current cell around, while `wrapping` is disabled
     ///
-    #[allow(clippy::too_many_lines)]
     pub fn execute(&mut self) -> Result<ExecutionInfo> {
-        let (opening, closing) = (
-            self.code.chars()
-                .filter(|c| *c == '[')
-                .count(),
-            self.code.chars()
-                .filter(|c| *c == ']')
-                .count()
-        );
-
-        if opening != closing {
-            return Err(Error::MismatchedBrackets {
-                opening, closing
-            });
-        }
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("execute").entered();
 
-        let mut cells =
-            self.memory_size
-                .map_or_else(
-                    || vec![0],
-                    |mem_size| vec![0; mem_size],
-                );
+        let mut machine = self.begin()?;
+        // the output stream is taken out for the duration of the execution, for it to be borrowed alongside `self`
+        let mut stream = self.output.take();
+        let result = match stream {
+            Some(ref mut writer) => self.run(&mut machine, &mut **writer, Self::read_input),
+            None => self.run(&mut machine, &mut std::io::stdout(), Self::read_input),
+        };
+        self.output = stream;
 
-        self.instructions_ctn = 0;
-        let mut code_idx = 0usize;
-        let mut ptr = 0usize;
-        let time = self.bench_execution
-            .then(Instant::now);
-
-        while code_idx < self.code
-            .chars()
-            .count()
-        {
-            let mut incr_inst = true;
-
-            match self.code
-                .chars()
-                .nth(code_idx)
-            {
-                Some('+') =>
-                    if cells[ptr] >= self.max_cell_value {
-                        cells[ptr] = 0;
-                    } else {
-                        cells[ptr] += 1;
-                    },
-                Some('-') =>
-                    if cells[ptr] == 0 {
-                        cells[ptr] = self.max_cell_value;
-                    } else {
-                        cells[ptr] -= 1;
-                    },
-                Some('<') =>
-                    if ptr == 0 {
-                        ptr = cells.len() - 1;
-                    } else {
-                        ptr -= 1;
-                    },
-                Some('>') => {
-                    ptr += 1;
-                    if let Some(mem_size) = self.memory_size {
-                        if ptr >= mem_size {
-                            ptr = 0;
-                        }
-                    } else if ptr >= cells.len() {
-                        cells.push(0);
-                    }
-                },
-                Some('.') =>
-                    if let Some(chr) =
-                        std::char::from_u32(cells[ptr])
-                    {
-                        if let Some(ref mut writer) =
-                            self.output
-                        {
-                            let mut buf = vec![0; chr.len_utf8()];
-                            chr.encode_utf8(&mut buf);
-
-                            writer.write_all(&buf)?;
-                            if self.flush_output {
-                                writer.flush()?;
-                            }
-                        } else {
-                            print!("{chr}");
-                            if self.flush_output {
-                                std::io::stdout()
-                                    .flush()?;
-                            }
-                        }
-                    },
-                #[allow(clippy::option_if_let_else)]
-                Some(',') =>
-                    cells[ptr] = if let Some(ref mut reader) =
-                        self.input
-                    {
-                        let mut buffer = [0];
-                        match reader
-                            .read_exact(&mut buffer[0..1])
-                        {
-                            Ok(()) => u32::from(buffer[0]),
-                            Err(_) => self.get_fallback_char(),
-                        }
-                    } else if self.prompt_stdin_once {
-                        self.read_from_stdin_once()
-                    } else {
-                        self.read_from_stdin()
-                    },
-                Some('[') =>
-                    if cells[ptr] == 0 {
-                        let mut loop_ = 1;
-                        while loop_ > 0 {
-                            code_idx += 1;
-                            match self.code
-                                .chars()
-                                .nth(code_idx)
-                            {
-                                Some('[') => loop_ += 1,
-                                Some(']') => loop_ -= 1,
-                                _ => (),
-                            }
-                        }
-                    },
-                Some(']') => {
-                    let mut loop_ = 1;
-                    while loop_ > 0 {
-                        code_idx -= 1;
-                        match self.code
-                            .chars()
-                            .nth(code_idx)
-                        {
-                            Some('[') => loop_ -= 1,
-                            Some(']') => loop_ += 1,
-                            _ => (),
-                        }
-                    }
-                    code_idx -= 1;
-                },
-                _ => incr_inst = false,
-            }
-            code_idx += 1;
+        result?;
+        Ok(machine.into_info())
+    }
 
-            if incr_inst {
-                self.instructions_ctn += 1;
-            }
+    /// executes the code like [`Brainfuck::execute`], reading the `,` operation from `input`
+    /// and writing the `.` operation into `output` for this execution only,
+    /// leaving the configured input and output streams untouched
+    ///
+    /// # Errors
+    /// the same as [`Brainfuck::execute`], [`Error::OutputError`] being propogated from `output`
+    ///
+    /// ```rust
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// let mut interp = Brainfuck::new(",[+.,]");
+    ///
+    /// for input in ["abc", "HAL"] {
+    ///     let mut output = Vec::new();
+    ///     interp.execute_with_io(&mut input.as_bytes(), &mut output)?;
+    ///     println!("{}", String::from_utf8_lossy(&output));
+    /// }
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    pub fn execute_with_io<R, W>(&mut self, input: &mut R, output: &mut W) -> Result<ExecutionInfo>
+    where
+        R: Read + ?Sized,
+        W: Write + ?Sized,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("execute").entered();
 
-            if let Some(cap) = self.instructions_limit {
-                if self.instructions_ctn > cap {
-                    return Err(Error::MaxInstructionsExceeded(cap));
-                }
-            }
-        }
-        let mem_size = cells.len();
+        let mut machine = self.begin()?;
+        self.run(&mut machine, output, |_| Self::read_byte(input))?;
 
-        Ok(ExecutionInfo {
-            cells,
-            mem_size,
-            pointer: ptr,
-            code_len: code_idx,
-            instructions: self.instructions_count(),
-            time: time
-                .map(|t| t.elapsed()),
-        })
+        Ok(machine.into_info())
     }
-}
\ No newline at end of file
+}
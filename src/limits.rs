@@ -0,0 +1,126 @@
+//! module containing [`ExecutionLimits`], every resource limit of an execution bundled together
+
+use std::time::Duration;
+use crate::Brainfuck;
+
+/// the resource limits of an execution, set all at once with [`Brainfuck::with_limits`],
+/// every limit being disabled when [`None`]
+///
+/// ```rust
+/// use brainfuck_exe::{Brainfuck, Error, ExecutionLimits};
+///
+/// let limits = ExecutionLimits::untrusted()
+///     .with_output_bytes(4);
+/// let result = Brainfuck::new("+[.]")
+///     .with_limits(limits)
+///     .with_output(std::io::sink())
+///     .execute();
+///
+/// assert!(matches!(result, Err(Error::OutputLimitExceeded { limit: 4, .. })));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ExecutionLimits {
+    /// the max amount of instructions executed, see [`Brainfuck::with_instructions_limit`]
+    pub instructions: Option<usize>,
+    /// the max wall-clock time the program can execute for, see [`Brainfuck::with_timeout`]
+    pub timeout: Option<Duration>,
+    /// the max amount of bytes the memory array can take, every cell taking 4 bytes,
+    /// the memory arrays of every thread of a [`crate::Dialect::Brainfork`] program included
+    pub memory_bytes: Option<usize>,
+    /// the max amount of bytes written by the `.` operation
+    pub output_bytes: Option<usize>,
+    /// the max amount of loops nested within each other in the code, checked before executing it
+    pub loop_depth: Option<usize>,
+}
+
+impl ExecutionLimits {
+    /// creates limits with every limit disabled
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            instructions: None,
+            timeout: None,
+            memory_bytes: None,
+            output_bytes: None,
+            loop_depth: None,
+        }
+    }
+
+    /// the limits of a service executing code it does not trust, such as a web playground:
+    /// `10_000_000` instructions, `5` seconds, `1` MiB of memory, `64` KiB of output and `256` nested loops
+    #[must_use]
+    pub const fn untrusted() -> Self {
+        Self {
+            instructions: Some(10_000_000),
+            timeout: Some(Duration::from_secs(5)),
+            memory_bytes: Some(1 << 20),
+            output_bytes: Some(64 << 10),
+            loop_depth: Some(256),
+        }
+    }
+
+    /// builder method to set the max amount of instructions executed
+    #[must_use]
+    pub const fn with_instructions(mut self, limit: usize) -> Self {
+        self.instructions = Some(limit);
+        self
+    }
+
+    /// builder method to set the max wall-clock time the program can execute for
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// builder method to set the max amount of bytes the memory array can take
+    #[must_use]
+    pub const fn with_memory_bytes(mut self, limit: usize) -> Self {
+        self.memory_bytes = Some(limit);
+        self
+    }
+
+    /// builder method to set the max amount of bytes written by the `.` operation
+    #[must_use]
+    pub const fn with_output_bytes(mut self, limit: usize) -> Self {
+        self.output_bytes = Some(limit);
+        self
+    }
+
+    /// builder method to set the max amount of loops nested within each other in the code
+    #[must_use]
+    pub const fn with_loop_depth(mut self, limit: usize) -> Self {
+        self.loop_depth = Some(limit);
+        self
+    }
+}
+
+impl Brainfuck<'_> {
+    /// builder method to set every resource limit of the execution at once,
+    /// replacing the instructions limit and the timeout set before
+    ///
+    /// the execution fails with [`crate::Error::MaxInstructionsExceeded`], [`crate::Error::TimedOut`],
+    /// [`crate::Error::MemoryLimitExceeded`], [`crate::Error::OutputLimitExceeded`]
+    /// or [`crate::Error::LoopDepthExceeded`] once one of them is exceeded
+    #[must_use]
+    pub const fn with_limits(mut self, limits: ExecutionLimits) -> Self {
+        self.instructions_limit = limits.instructions;
+        self.timeout = limits.timeout;
+        self.memory_limit = limits.memory_bytes;
+        self.output_limit = limits.output_bytes;
+        self.loop_depth_limit = limits.loop_depth;
+        self
+    }
+
+    /// returns every resource limit of the execution
+    #[must_use]
+    pub const fn limits(&self) -> ExecutionLimits {
+        ExecutionLimits {
+            instructions: self.instructions_limit,
+            timeout: self.timeout,
+            memory_bytes: self.memory_limit,
+            output_bytes: self.output_limit,
+            loop_depth: self.loop_depth_limit,
+        }
+    }
+}
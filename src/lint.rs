@@ -0,0 +1,216 @@
+//! module containing the lints reporting common smells in brainfuck code
+//!
+//! see [`Brainfuck::lint`] for more information
+
+use crate::{
+    check::is_endless,
+    machine::INSTRUCTIONS,
+    Brainfuck,
+    Diagnostic,
+    Position,
+    Severity,
+};
+
+/// the name of the lint reporting loops without any instructions in them
+pub const EMPTY_LOOP: &str = "empty_loop";
+/// the name of the lint reporting adjacent instructions that cancel each other out
+pub const CANCELLING_PAIR: &str = "cancelling_pair";
+/// the name of the lint reporting modifications of a cell that is cleared right after
+pub const CLEARED_WRITE: &str = "cleared_write";
+/// the name of the lint reporting code after a loop that is entered and never terminates
+pub const UNREACHABLE_CODE: &str = "unreachable_code";
+
+/// the state shared by the lint passes
+struct Linter<'c> {
+    /// the code split into its characters
+    code: &'c [char],
+    /// the position of every character of the code
+    positions: Vec<Position>,
+    /// the code index of every instruction, in order
+    ops: Vec<usize>,
+    /// the maximum value of a cell
+    max_cell_value: u32,
+    /// the lints found thus far
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter<'_> {
+    /// returns the instruction at the provided index of `ops`, if any
+    fn op(&self, idx: usize) -> Option<char> {
+        self.ops
+            .get(idx)
+            .map(|code_idx| self.code[*code_idx])
+    }
+
+    /// reports a lint spanning the instructions at the provided indices of `ops`, both inclusive
+    fn report(&mut self, lint: &'static str, message: &str, first: usize, last: usize, suggestion: &str) {
+        let mut diagnostic = Diagnostic::new(
+            Severity::Warning, message, &self.positions, self.ops[first], self.ops[last] + 1,
+        );
+        diagnostic.lint = Some(lint);
+        diagnostic.suggestion = Some(suggestion.to_string());
+
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// reports `[]` loops
+    fn empty_loops(&mut self) {
+        for idx in 0..self.ops.len() {
+            if self.op(idx) == Some('[') && self.op(idx + 1) == Some(']') {
+                self.report(
+                    EMPTY_LOOP,
+                    "this loop is empty, so it either does nothing or never terminates",
+                    idx, idx + 1, "",
+                );
+            }
+        }
+    }
+
+    /// reports `+-`, `-+`, `<>` and `><`
+    fn cancelling_pairs(&mut self) {
+        let mut idx = 0;
+
+        while idx + 1 < self.ops.len() {
+            if let (Some(first), Some(second)) = (self.op(idx), self.op(idx + 1)) {
+                if matches!((first, second), ('+', '-') | ('-', '+') | ('<', '>') | ('>', '<')) {
+                    self.report(
+                        CANCELLING_PAIR,
+                        &format!("`{first}` is immediately cancelled out by `{second}`"),
+                        idx, idx + 1, "",
+                    );
+                    idx += 2;
+                    continue;
+                }
+            }
+            idx += 1;
+        }
+    }
+
+    /// reports runs of `+` and `-` that are immediately followed by a `[-]` or `[+]` clearing the cell
+    fn cleared_writes(&mut self) {
+        for idx in 1..self.ops.len() {
+            let is_clear = self.op(idx) == Some('[')
+                && matches!(self.op(idx + 1), Some('+' | '-'))
+                && self.op(idx + 2) == Some(']');
+
+            if !is_clear || !matches!(self.op(idx - 1), Some('+' | '-')) {
+                continue;
+            }
+            let first = (0..idx)
+                .rev()
+                .take_while(|op| matches!(self.op(*op), Some('+' | '-')))
+                .last()
+                .unwrap_or(idx - 1);
+
+            self.report(
+                CLEARED_WRITE,
+                "these modifications of the cell are discarded, as it is cleared right after",
+                first, idx - 1, "",
+            );
+        }
+    }
+
+    /// reports the code after the first loop that is certainly entered and never terminates
+    ///
+    /// the value of the current cell is tracked through the straight-line code,
+    /// knowing that it is always `0` right after a loop
+    fn unreachable_code(&mut self) {
+        let mut known = Some(0);
+
+        for idx in 0..self.ops.len() {
+            match self.op(idx) {
+                Some('+') => known = known.map(|value: u32|
+                    if value >= self.max_cell_value { 0 } else { value + 1 }
+                ),
+                Some('-') => known = known.map(|value|
+                    if value == 0 { self.max_cell_value } else { value - 1 }
+                ),
+                Some('<' | '>' | ',') => known = None,
+                Some('[') => {
+                    let close = self.matching(idx);
+
+                    if let Some(close) = close.filter(|_| known.is_some_and(|value| value != 0)) {
+                        let body = &self.code[self.ops[idx] + 1..self.ops[close]];
+
+                        if is_endless(body) && close + 1 < self.ops.len() {
+                            self.report(
+                                UNREACHABLE_CODE,
+                                "this code is unreachable, as the loop before it is entered and never terminates",
+                                close + 1, self.ops.len() - 1, "",
+                            );
+                            return;
+                        }
+                    }
+                    known = None;
+                },
+                Some(']') => known = Some(0),
+                _ => (),
+            }
+        }
+    }
+
+    /// returns the index in `ops` of the `]` matching the `[` at the provided index, if any
+    fn matching(&self, open: usize) -> Option<usize> {
+        let mut depth = 0_usize;
+
+        for idx in open..self.ops.len() {
+            match self.op(idx) {
+                Some('[') => depth += 1,
+                Some(']') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(idx);
+                    }
+                },
+                _ => (),
+            }
+        }
+        None
+    }
+}
+
+impl Brainfuck<'_> {
+    /// reports common smells in the code, each as a [`Severity::Warning`] with a suggested fix
+    ///
+    /// - [`EMPTY_LOOP`]: `[]`, which either does nothing or never terminates
+    /// - [`CANCELLING_PAIR`]: `+-`, `-+`, `<>` and `><`, which do nothing
+    /// - [`CLEARED_WRITE`]: `+` and `-` right before a `[-]` or `[+]`, whose effect is discarded
+    /// - [`UNREACHABLE_CODE`]: code after a loop that is certainly entered and never terminates
+    ///
+    /// every suggestion is the code to replace the spanned code with; comments in between instructions are ignored
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{lint, Brainfuck};
+    ///
+    /// let lints = Brainfuck::new("+++[-]><.").lint();
+    ///
+    /// assert_eq!(lints.len(), 2);
+    /// assert_eq!(lints[0].lint, Some(lint::CLEARED_WRITE));
+    /// assert_eq!(lints[1].lint, Some(lint::CANCELLING_PAIR));
+    /// ```
+    #[must_use]
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        let code = self.code
+            .chars()
+            .collect::<Vec<char>>();
+
+        let mut linter = Linter {
+            positions: Position::locate_all(&code),
+            ops: (0..code.len())
+                .filter(|idx| INSTRUCTIONS.contains(code[*idx]))
+                .collect(),
+            code: &code,
+            max_cell_value: self.max_cell_value,
+            diagnostics: Vec::new(),
+        };
+
+        linter.empty_loops();
+        linter.cancelling_pairs();
+        linter.cleared_writes();
+        linter.unreachable_code();
+
+        let mut diagnostics = linter.diagnostics;
+        diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+        diagnostics
+    }
+}
@@ -0,0 +1,970 @@
+//! module containing the internal state machine that drives a single program execution
+//!
+//! the machine itself never touches any I/O streams,
+//! instead it hands back an [`Action`] whenever the program wants to read or write,
+//! which allows the different execution modes (sync, async) to share the same core
+
+use std::{collections::{HashMap, HashSet, VecDeque}, time::Instant};
+use crate::{
+    ast::Program,
+    optimizer::Optimizer,
+    Brainfuck,
+    CellAccess,
+    Dialect,
+    EofBehavior,
+    Error,
+    ExecutionInfo,
+    HookCtx,
+    HAS_CLOCK,
+    ThreadStats,
+    OpcodeCounts,
+    OptimizationLevel,
+    Position,
+    Result,
+    RuntimeContext,
+    Span,
+};
+
+/// every character that is a brainfuck instruction
+pub(crate) const INSTRUCTIONS: &str = "+-<>.,[]";
+
+/// what the machine requires from its driver after a single step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Action {
+    /// nothing is required, the next step can be taken
+    Continue,
+    /// the program executed a `.` producing this [`char`]
+    Output(char),
+    /// the program executed a `,` and the driver must call [`Machine::write_input`]
+    Input,
+    /// the end of the code has been reached
+    Halt,
+}
+
+/// a change in the loop nesting caused by a `[` operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LoopEvent {
+    /// the loop starting at this code index was entered from outside of it
+    Enter(usize),
+    /// the loop starting at this code index finished after at least one iteration
+    Exit(usize),
+}
+
+/// the amount of loop iteration states remembered before they are forgotten,
+/// bounding the memory used by infinite loop detection
+const MAX_REMEMBERED_STATES: usize = 4096;
+
+/// the state of a suspended thread of a [`Dialect::Brainfork`] program
+struct Thread {
+    /// the index of the thread
+    id: usize,
+    /// the memory array of the thread
+    cells: Vec<u32>,
+    /// the pointer of the thread
+    ptr: usize,
+    /// the index of the next character in `code` the thread processes
+    code_idx: usize,
+    /// whether or not the last step of the thread was a `]` jumping back onto its `[`
+    jumped_back: bool,
+}
+
+/// the state of an in-progress brainfuck program execution
+pub(crate) struct Machine {
+    /// the brainfuck code split into its characters
+    code: Vec<char>,
+    /// maps the index of every bracket to the index of its matching bracket
+    jumps: Vec<usize>,
+    /// the memory array of the program
+    pub cells: Vec<u32>,
+    /// the current pointer index
+    pub ptr: usize,
+    /// the index of the next character in `code` to be processed
+    pub code_idx: usize,
+    /// the index of the last character in `code` that was processed
+    pub last_code_idx: usize,
+    /// the amount of instructions executed thus far
+    pub instructions: usize,
+    /// the amount of steps (characters processed) taken thus far
+    pub steps: usize,
+    /// the amount of times each instruction was executed thus far
+    opcode_counts: OpcodeCounts,
+    /// the amount of times each cell was accessed, if it is being tracked
+    accesses: Option<Vec<CellAccess>>,
+    /// whether or not a `,` operation is waiting on [`Machine::write_input`]
+    awaiting_input: bool,
+    /// whether or not the last step was a `]` jumping back onto its `[`
+    jumped_back: bool,
+    /// the loop event caused by the last step, if any
+    pub loop_event: Option<LoopEvent>,
+    /// the maximum value of a cell
+    max_cell_value: u32,
+    /// the maximum length of the memory array, if any
+    memory_size: Option<usize>,
+    /// the output of the start of the code evaluated by the optimizer,
+    /// or the output of a custom instruction, that is yet to be written
+    folded_output: VecDeque<char>,
+    /// the code the optimized code stems from, along with the index of the character
+    /// every character of the optimized code stems from, if the code was optimized
+    origins: Option<(Vec<char>, Vec<usize>)>,
+    /// the time at which the execution started, if it is being benched
+    start: Option<Instant>,
+    /// the point in time after which the execution is considered timed out, if any
+    deadline: Option<Instant>,
+    /// the states seen at the start of loop iterations since the last I/O operation,
+    /// if infinite loops are being detected
+    seen_states: Option<HashSet<(usize, usize, Vec<u32>)>>,
+    /// whether or not the last step started a loop iteration in an exact state that was seen before
+    pub repeated_state: bool,
+    /// the amount of iterations of the current entry of every loop, indexed by the code index of its `[`,
+    /// if the iterations of the loops are being limited
+    loop_iterations: Option<Vec<usize>>,
+    /// the max amount of iterations of a single entry of a loop
+    max_loop_iterations: usize,
+    /// the code index of the `[` of the loop that iterated more than the max amount of iterations, if any
+    pub exceeded_loop: Option<usize>,
+    /// the extension of brainfuck the code is written in
+    dialect: Dialect,
+    /// the code index of the `(` of every procedure defined thus far, keyed by the value of the cell it was defined at
+    procedures: HashMap<u32, usize>,
+    /// the code index of every `:` operation whose procedure is currently executing, innermost last
+    calls: Vec<usize>,
+    /// the key of the procedure the last step failed to call as it was never defined, if any
+    pub undefined_procedure: Option<u32>,
+    /// whether or not `+` and `-` wrap the current cell around, see [`Brainfuck::wrapping`]
+    wrapping: bool,
+    /// whether or not `+` or `-` would have wrapped the current cell around while wrapping is disabled
+    pub overflowed: bool,
+    /// the amount of bytes written by the `.` operation thus far
+    pub output_bytes: usize,
+    /// the index of the thread currently executing
+    thread: usize,
+    /// every other thread that is yet to reach the end of the code, in the order they take their turns
+    threads: VecDeque<Thread>,
+    /// whether or not the thread taking the next step was already switched to, by [`Machine::hook_ctx`]
+    thread_switched: bool,
+    /// the memory array and pointer of the main thread once it reached the end of the code before other threads
+    main_thread: Option<(Vec<u32>, usize)>,
+    /// the statistics of every thread, if the code is written in [`Dialect::Brainfork`]
+    thread_stats: Vec<ThreadStats>,
+    /// the storage cell of [`Dialect::ExtendedTypeI`]
+    storage: u32,
+    /// whether or not `?` is an instruction setting the current cell to a random value
+    random: bool,
+    /// whether or not a `?` operation is waiting on [`Machine::write_random`]
+    awaiting_random: bool,
+    /// the characters of the custom instructions
+    custom_ops: Vec<char>,
+    /// the custom instruction processed by the last step, waiting on [`Machine::finish_custom_op`], if any
+    pub pending_op: Option<char>,
+    /// the bits of the last byte read by [`Dialect::Boolfuck`] that are yet to be read by `,`, least significant first
+    input_bits: VecDeque<u32>,
+    /// the bits written by [`Dialect::Boolfuck`] that are yet to form a byte, along with their amount
+    output_bits: (u8, u32),
+}
+
+impl Machine {
+    /// the amount of steps in between reading the clock for timeouts
+    pub const CLOCK_CHECK_INTERVAL: usize = 1024;
+
+    /// returns the memory array and pointer the execution starts with, see [`Brainfuck::initial_state`]
+    ///
+    /// # Errors
+    /// - [`Error::InitialPointerOutOfBounds`]: the pointer is past the cells the memory array can take
+    fn initial_state(bf: &Brainfuck, max_cell_value: u32) -> Result<(Vec<u32>, usize)> {
+        let Some((ref cells, ptr)) = bf.initial_state else {
+            return Ok((vec![0; bf.memory_size.unwrap_or(1)], 0));
+        };
+        let out_of_bounds = || Error::InitialPointerOutOfBounds { pointer: ptr };
+
+        let mut cells = cells
+            .iter()
+            .map(|&cell| max_cell_value
+                .checked_add(1)
+                .map_or(cell, |modulo| cell % modulo)
+            )
+            .collect::<Vec<u32>>();
+
+        if let Some(mem_size) = bf.memory_size {
+            cells.resize(mem_size, 0);
+            return Ok((cells, ptr % mem_size.max(1)));
+        }
+        // the growable memory array is grown up to the pointer, unless that takes more bytes than the set limit
+        let len = ptr
+            .checked_add(1)
+            .filter(|&len| bf.memory_limit.is_none_or(|limit| len.saturating_mul(size_of::<u32>()) <= limit))
+            .ok_or_else(out_of_bounds)?
+            .max(cells.len());
+        // or than can be allocated, failing rather than aborting
+        cells
+            .try_reserve(len - cells.len())
+            .map_err(|_| out_of_bounds())?;
+        cells.resize(len, 0);
+
+        Ok((cells, ptr))
+    }
+
+    /// prepares a new execution of the provided interpreter's code
+    ///
+    /// # Errors
+    /// - [`Error::MismatchedBrackets`]: the code contains an unmatched `[` or a stray `]`,
+    ///   or another unmatched bracket of the dialect
+    /// - [`Error::UnexpectedCharacter`]: the code contains a character that is neither an instruction
+    ///   nor whitespace, while `strict_syntax` is enabled
+    pub fn new(bf: &Brainfuck) -> Result<Self> {
+        bf.validate()?;
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("parse", len = bf.code.len()).entered();
+
+        let code = bf.code
+            .chars()
+            .collect::<Vec<char>>();
+        if bf.strict_syntax {
+            Self::check_characters(&code, |chr| bf.is_instruction(chr))?;
+        }
+        Self::check_callbacks(&code, bf)?;
+        if let Some(limit) = bf.loop_depth_limit {
+            Self::check_loop_depth(&code, limit)?;
+        }
+        let mut jumps = Self::match_pairs(&code, bf.dialect.brackets())?;
+        let max_cell_value = if bf.dialect == Dialect::Boolfuck { 1 } else { bf.max_cell_value };
+        let (mut cells, mut ptr) = Self::initial_state(bf, max_cell_value)?;
+        let mut folded_output = VecDeque::new();
+        let mut origins = None;
+
+        // the optimizer treats the instructions of the dialects, `?` and the custom instructions as comments,
+        // and assumes the memory starts out as `0`s; on a growable memory array, `<` on the first cell wraps around
+        // to the last cell allocated so far, which merging the moves of the pointer would change,
+        // and `,` keeping the cell on EOF would keep the changes before it the optimizer removes as dead stores
+        let code = if bf.optimization == OptimizationLevel::None
+            || bf.memory_size.is_none()
+            || bf.eof == EofBehavior::Unchanged
+            || bf.dialect != Dialect::Brainfuck
+            || bf.random.is_some()
+            || !bf.custom_ops.is_empty()
+            || bf.initial_state.is_some()
+            || !bf.wrapping
+        {
+            code
+        } else {
+            let optimizer = Optimizer::new()
+                .with_max_value(bf.max_cell_value)
+                .with_mem_size(bf.memory_size);
+            let mut nodes = optimizer
+                .optimize(Program::parse(&bf.code)?)
+                .program
+                .nodes;
+
+            // the start of the code that does not depend on any input is evaluated right away,
+            // rather than being folded into code recreating its result
+            if bf.optimization >= OptimizationLevel::Aggressive {
+                if let Some((evaluator, evaluated)) = optimizer.evaluate_prefix(&nodes) {
+                    cells = evaluator.cells;
+                    ptr = evaluator.ptr;
+                    folded_output = evaluator.output.chars().collect();
+                    nodes.drain(..evaluated);
+                }
+            }
+            let (optimized, map) = Program { nodes }.to_code_with_map();
+            let optimized = optimized
+                .chars()
+                .collect::<Vec<char>>();
+            jumps = Self::match_brackets(&optimized)?;
+
+            // the spans map to byte offsets, while the code is indexed by characters
+            let mut char_indices = vec![0; bf.code.len() + 1];
+            for (idx, (offset, _)) in bf.code.char_indices().enumerate() {
+                char_indices[offset] = idx;
+            }
+            char_indices[bf.code.len()] = code.len();
+
+            origins = Some((
+                code,
+                map.iter()
+                    .map(|span| char_indices[span.start.offset])
+                    .collect(),
+            ));
+            optimized
+        };
+
+        let loop_iterations = bf.max_loop_iterations
+            .map(|_| vec![0; code.len()]);
+
+        Ok(Self {
+            code,
+            jumps,
+            cells,
+            ptr,
+            folded_output,
+            origins,
+            code_idx: 0,
+            last_code_idx: 0,
+            instructions: 0,
+            steps: 0,
+            opcode_counts: OpcodeCounts::default(),
+            accesses: bf.track_cell_accesses
+                .then(Vec::new),
+            awaiting_input: false,
+            jumped_back: false,
+            loop_event: None,
+            max_cell_value,
+            memory_size: bf.memory_size,
+            start: bf.bench_execution
+                .then(Instant::now),
+            deadline: bf.timeout
+                .filter(|_| HAS_CLOCK)
+                // a timeout too far in the future to be represented never passes
+                .and_then(|timeout| Instant::now().checked_add(timeout)),
+            seen_states: bf.detect_infinite_loops
+                .then(HashSet::new),
+            repeated_state: false,
+            loop_iterations,
+            max_loop_iterations: bf.max_loop_iterations.unwrap_or(usize::MAX),
+            exceeded_loop: None,
+            dialect: bf.dialect,
+            procedures: HashMap::new(),
+            calls: Vec::new(),
+            undefined_procedure: None,
+            // flipping a bit in boolfuck is not an overflow
+            wrapping: bf.wrapping || bf.dialect == Dialect::Boolfuck,
+            overflowed: false,
+            output_bytes: 0,
+            thread: 0,
+            threads: VecDeque::new(),
+            thread_switched: false,
+            main_thread: None,
+            thread_stats: if bf.dialect == Dialect::Brainfork {
+                vec![ThreadStats::default()]
+            } else {
+                Vec::new()
+            },
+            storage: 0,
+            random: bf.random.is_some(),
+            awaiting_random: false,
+            custom_ops: bf.custom_ops
+                .keys()
+                .copied()
+                .collect(),
+            pending_op: None,
+            input_bits: VecDeque::new(),
+            output_bits: (0, 0),
+        })
+    }
+
+    /// checks that the code uses none of the instructions whose callbacks could not be copied by
+    /// [`Brainfuck::clone_config`], unless they were set again
+    fn check_callbacks(code: &[char], bf: &Brainfuck) -> Result<()> {
+        let missing = bf.uncloned
+            .iter()
+            .filter(|opcode| !bf.is_instruction(**opcode))
+            .copied()
+            .collect::<Vec<char>>();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        code.iter()
+            .position(|chr| missing.contains(chr))
+            .map_or(Ok(()), |idx| Err(Error::MissingCallback {
+                opcode: code[idx],
+                position: Position::locate(code, idx),
+            }))
+    }
+
+    /// checks that every character of the code is either an instruction or whitespace
+    fn check_characters(code: &[char], is_instruction: impl Fn(char) -> bool) -> Result<()> {
+        code.iter()
+            .position(|chr| !is_instruction(*chr) && !chr.is_whitespace())
+            .map_or(Ok(()), |idx| Err(Error::UnexpectedCharacter {
+                character: code[idx],
+                position: Position::locate(code, idx),
+            }))
+    }
+
+    /// checks that no more than `limit` loops are nested within each other in the code
+    fn check_loop_depth(code: &[char], limit: usize) -> Result<()> {
+        let mut depth = 0_usize;
+
+        for (idx, chr) in code.iter().enumerate() {
+            match chr {
+                '[' => depth += 1,
+                ']' => depth = depth.saturating_sub(1),
+                _ => continue,
+            }
+            if depth > limit {
+                return Err(Error::LoopDepthExceeded {
+                    limit,
+                    position: Position::locate(code, idx),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// builds the jump table for every `[` and `]` in the code
+    pub(crate) fn match_brackets(code: &[char]) -> Result<Vec<usize>> {
+        Self::match_pairs(code, &[('[', ']')])
+    }
+
+    /// builds the jump table for every bracket of the provided pairs in the code,
+    /// which must be nested within each other
+    pub(crate) fn match_pairs(code: &[char], pairs: &[(char, char)]) -> Result<Vec<usize>> {
+        let mismatched = |idx: usize| {
+            let (open, close) = pairs
+                .iter()
+                .find(|(open, close)| code[idx] == *open || code[idx] == *close)
+                .copied()
+                .unwrap_or(('[', ']'));
+
+            Error::MismatchedBrackets {
+                opening: code.iter()
+                    .filter(|c| **c == open)
+                    .count(),
+                closing: code.iter()
+                    .filter(|c| **c == close)
+                    .count(),
+                bracket: code[idx],
+                position: Position::locate(code, idx),
+            }
+        };
+
+        let mut jumps = vec![0; code.len()];
+        let mut stack = Vec::new();
+
+        for (idx, chr) in code.iter().enumerate() {
+            if pairs.iter().any(|(open, _)| open == chr) {
+                stack.push(idx);
+            } else if let Some((open, _)) = pairs.iter().find(|(_, close)| close == chr) {
+                // a closing bracket must close the innermost opening bracket, which must be of the same kind
+                let start = stack
+                    .pop_if(|start| code[*start] == *open)
+                    .ok_or_else(|| stack
+                        .last()
+                        .filter(|start| code[**start] != *open)
+                        .map_or_else(|| mismatched(idx), |start| mismatched(*start))
+                    )?;
+                jumps[start] = idx;
+                jumps[idx] = start;
+            }
+        }
+
+        // the bottom of the stack is the first unmatched opening bracket
+        stack.first()
+            .map_or(Ok(jumps), |idx| Err(mismatched(*idx)))
+    }
+
+    /// sets the current cell to a value retrieved from the input stream
+    ///
+    /// in [`Dialect::Boolfuck`], the value is a byte whose bits are read one at a time instead
+    pub fn write_input(&mut self, value: u32) {
+        self.cells[self.ptr] = value;
+        self.awaiting_input = false;
+
+        if self.dialect == Dialect::Boolfuck {
+            self.input_bits = (0..8)
+                .map(|bit| (value >> bit) & 1)
+                .collect();
+            self.cells[self.ptr] = self.input_bits
+                .pop_front()
+                .unwrap_or_default();
+        }
+    }
+
+    /// returns the value of the current cell
+    pub fn current_cell(&self) -> u32 {
+        self.cells[self.ptr]
+    }
+
+    /// returns whether or not a `,` operation is waiting on [`Machine::write_input`]
+    pub const fn is_awaiting_input(&self) -> bool {
+        self.awaiting_input
+    }
+
+    /// sets the current cell to the provided random value, wrapped into the range of a cell
+    pub fn write_random(&mut self, value: u32) {
+        #[allow(clippy::cast_possible_truncation)]
+        let value = (u64::from(value) % (u64::from(self.max_cell_value) + 1)) as u32;
+        self.cells[self.ptr] = value;
+        self.awaiting_random = false;
+    }
+
+    /// counts an iteration of the loop whose `[` is being processed, if the iterations of the loops are being limited,
+    /// restarting from the first iteration if the loop was just entered
+    fn count_iteration(&mut self, entered: bool) {
+        if let Some(ref mut iterations) = self.loop_iterations {
+            let count = &mut iterations[self.code_idx];
+            *count = if entered { 1 } else { *count + 1 };
+
+            if *count > self.max_loop_iterations {
+                self.exceeded_loop = Some(self.code_idx);
+            }
+        }
+    }
+
+    /// returns the span of the code the loop starting at the provided index of the executed code stems from,
+    /// from its `[` up to and including its `]`
+    pub fn loop_span(&self, code_idx: usize) -> Span {
+        let (code, start, end) = match self.origins {
+            // the optimized code does not keep the `]` of the loop, so it is matched in the code it stems from
+            Some((ref code, ref map)) => {
+                let start = map[code_idx];
+                let end = Self::match_brackets(code)
+                    .ok()
+                    .and_then(|jumps| jumps.get(start).copied())
+                    .unwrap_or(start);
+                (code, start, end)
+            },
+            None => (&self.code, code_idx, self.jumps[code_idx]),
+        };
+
+        Span {
+            start: Position::locate(code, start),
+            end: Position::locate(code, end + 1),
+        }
+    }
+
+    /// returns the amount of bytes taken by the memory arrays of every thread
+    pub fn memory_bytes(&self) -> usize {
+        let cells = self.cells.len() + self.threads
+            .iter()
+            .map(|thread| thread.cells.len())
+            .sum::<usize>();
+        cells * std::mem::size_of::<u32>()
+    }
+
+    /// returns whether or not the output folded by the optimizer or produced by a custom instruction
+    /// is yet to be written, in which case the next step writes it instead of processing the code
+    pub fn has_folded_output(&self) -> bool {
+        !self.folded_output.is_empty()
+    }
+
+    /// returns whether or not a `?` operation is waiting on [`Machine::write_random`]
+    pub const fn is_awaiting_random(&self) -> bool {
+        self.awaiting_random
+    }
+
+    /// returns whether or not the provided character is an instruction
+    fn is_instruction(&self, chr: char) -> bool {
+        self.dialect.instructions().contains(chr)
+            || (self.random && chr == '?')
+            || self.custom_ops.contains(&chr)
+    }
+
+    /// brings the memory array and pointer back into shape after a custom instruction modified them,
+    /// queueing the output it produced
+    pub fn finish_custom_op(&mut self, output: &str) {
+        if let Some(mem_size) = self.memory_size {
+            self.cells.resize(mem_size, 0);
+            self.ptr %= mem_size;
+        } else if self.ptr >= self.cells.len() {
+            self.cells.resize(self.ptr + 1, 0);
+        }
+        let modulo = u64::from(self.max_cell_value) + 1;
+
+        for cell in &mut self.cells {
+            #[allow(clippy::cast_possible_truncation)]
+            let value = (u64::from(*cell) % modulo) as u32;
+            *cell = value;
+        }
+        self.folded_output.extend(output.chars());
+    }
+
+    /// builds the [`HookCtx`] of the instruction the next step executes,
+    /// or [`None`] if it writes the pending output or the next character of the code is not an instruction
+    ///
+    /// under [`Dialect::Brainfork`], it first switches to the thread taking the next step,
+    /// so that the context describes that thread
+    pub fn hook_ctx(&mut self) -> Option<HookCtx> {
+        if self.has_folded_output() {
+            return None;
+        }
+        self.switch_thread();
+
+        self.code
+            .get(self.code_idx)
+            .filter(|chr| self.is_instruction(**chr))
+            .map(|&opcode| HookCtx {
+                opcode,
+                code_idx: self.origin(self.code_idx),
+                pointer: self.ptr,
+                cell: self.cells[self.ptr],
+                instructions: self.instructions,
+            })
+    }
+
+    /// processes the next character of the code
+    ///
+    /// see [`Brainfuck::execute`] for the semantics of each operation
+    pub fn step(&mut self) -> Action {
+        // the output folded by the optimizer or produced by a custom instruction is written before executing the code,
+        // one character per step
+        if let Some(chr) = self.folded_output.pop_front() {
+            self.loop_event = None;
+            self.steps += 1;
+            self.instructions += 1;
+            self.opcode_counts.record('.');
+            return Action::Output(chr);
+        }
+        self.switch_thread();
+        self.thread_switched = false;
+
+        let Some(&chr) = self.code.get(self.code_idx) else {
+            // the remaining bits written by boolfuck are padded into a byte
+            if self.output_bits.1 > 0 {
+                let (byte, _) = std::mem::take(&mut self.output_bits);
+                return Action::Output(char::from(byte));
+            }
+            return self.halt_thread();
+        };
+        let mut action = Action::Continue;
+        let mut is_instruction = true;
+        let jumped_back = std::mem::take(&mut self.jumped_back);
+        self.last_code_idx = self.code_idx;
+        self.loop_event = None;
+        self.steps += 1;
+
+        // `-` and `.` are comments in boolfuck
+        let is_comment = self.dialect == Dialect::Boolfuck && matches!(chr, '-' | '.');
+        if !is_comment {
+            self.opcode_counts.record(chr);
+            self.record_access(chr);
+        }
+
+        match chr {
+            _ if is_comment => is_instruction = false,
+            ',' if self.dialect == Dialect::Boolfuck && !self.input_bits.is_empty() =>
+                self.cells[self.ptr] = self.input_bits
+                    .pop_front()
+                    .unwrap_or_default(),
+            ';' if self.dialect == Dialect::Boolfuck => {
+                let (ref mut byte, ref mut len) = self.output_bits;
+                if self.cells[self.ptr] != 0 {
+                    *byte |= 1 << *len;
+                }
+                *len += 1;
+
+                if *len == 8 {
+                    let (byte, _) = std::mem::take(&mut self.output_bits);
+                    action = Action::Output(char::from(byte));
+                    self.forget_states();
+                }
+            },
+            '+' if !self.wrapping && self.cells[self.ptr] >= self.max_cell_value => self.overflowed = true,
+            '-' if !self.wrapping && self.cells[self.ptr] == 0 => self.overflowed = true,
+            '+' =>
+                if self.cells[self.ptr] >= self.max_cell_value {
+                    self.cells[self.ptr] = 0;
+                } else {
+                    self.cells[self.ptr] += 1;
+                },
+            '-' =>
+                if self.cells[self.ptr] == 0 {
+                    self.cells[self.ptr] = self.max_cell_value;
+                } else {
+                    self.cells[self.ptr] -= 1;
+                },
+            '<' =>
+                if self.ptr == 0 {
+                    self.ptr = self.cells.len() - 1;
+                } else {
+                    self.ptr -= 1;
+                },
+            '>' => {
+                self.ptr += 1;
+                if let Some(mem_size) = self.memory_size {
+                    if self.ptr >= mem_size {
+                        self.ptr = 0;
+                    }
+                } else if self.ptr >= self.cells.len() {
+                    self.cells.push(0);
+                }
+            },
+            '.' =>
+                if let Some(chr) = std::char::from_u32(self.cells[self.ptr]) {
+                    action = Action::Output(chr);
+                    self.forget_states();
+                },
+            ',' => {
+                action = Action::Input;
+                self.awaiting_input = true;
+                self.forget_states();
+            },
+            '[' =>
+                if self.cells[self.ptr] == 0 {
+                    if jumped_back {
+                        self.loop_event = Some(LoopEvent::Exit(self.code_idx));
+                    }
+                    self.code_idx = self.jumps[self.code_idx];
+                } else if !jumped_back {
+                    self.loop_event = Some(LoopEvent::Enter(self.code_idx));
+                    self.count_iteration(true);
+                } else {
+                    self.remember_state();
+                    self.count_iteration(false);
+                },
+            ']' => {
+                // jump back onto the matching `[` so that it gets re-evaluated
+                self.code_idx = self.jumps[self.code_idx];
+                self.jumped_back = true;
+                self.count_instruction();
+                return action;
+            },
+            '(' if self.dialect == Dialect::Pbrain => {
+                // defining a procedure skips over its body
+                self.procedures.insert(self.cells[self.ptr], self.code_idx);
+                self.code_idx = self.jumps[self.code_idx];
+            },
+            ')' if self.dialect == Dialect::Pbrain =>
+                if let Some(call) = self.calls.pop() {
+                    self.code_idx = call;
+                },
+            '?' if self.random => self.awaiting_random = true,
+            chr if self.custom_ops.contains(&chr) && !self.dialect.instructions().contains(chr) =>
+                self.pending_op = Some(chr),
+            'Y' if self.dialect == Dialect::Brainfork => self.fork(),
+            '@' if self.dialect == Dialect::ExtendedTypeI => {
+                self.code_idx = self.code.len();
+                self.count_instruction();
+                return action;
+            },
+            '$' | '!' | '}' | '{' | '~' | '^' | '&' | '|' if self.dialect == Dialect::ExtendedTypeI => {
+                let cell = self.cells[self.ptr];
+                let value = match chr {
+                    '$' => {
+                        self.storage = cell;
+                        cell
+                    },
+                    '!' => self.storage,
+                    '}' => cell >> 1,
+                    '{' => cell << 1,
+                    '~' => self.max_cell_value - cell,
+                    '^' => cell ^ self.storage,
+                    '&' => cell & self.storage,
+                    _ => cell | self.storage,
+                };
+                #[allow(clippy::cast_possible_truncation)]
+                let value = (u64::from(value) % (u64::from(self.max_cell_value) + 1)) as u32;
+                self.cells[self.ptr] = value;
+            },
+            ':' if self.dialect == Dialect::Pbrain =>
+                if let Some(&start) = self.procedures.get(&self.cells[self.ptr]) {
+                    self.calls.push(self.code_idx);
+                    self.code_idx = start;
+                } else {
+                    self.undefined_procedure = Some(self.cells[self.ptr]);
+                },
+            _ => is_instruction = false,
+        }
+        self.code_idx += 1;
+
+        if is_instruction {
+            self.count_instruction();
+        }
+        action
+    }
+
+    /// counts an instruction executed by the current thread
+    fn count_instruction(&mut self) {
+        self.instructions += 1;
+
+        if let Some(stats) = self.thread_stats.get_mut(self.thread) {
+            stats.instructions += 1;
+        }
+    }
+
+    /// switches to the thread taking the next step, unless it was already switched to
+    fn switch_thread(&mut self) {
+        if std::mem::replace(&mut self.thread_switched, true) {
+            return;
+        }
+        // the threads take turns, one step each
+        if let Some(next) = self.threads.pop_front() {
+            let current = self.suspend(next);
+            self.threads.push_back(current);
+        }
+    }
+
+    /// forks the current thread at the `Y` operation being processed, queueing the child thread
+    fn fork(&mut self) {
+        let mut cells = self.cells.clone();
+        let mut ptr = self.ptr + 1;
+
+        if self.memory_size.is_some_and(|mem_size| ptr >= mem_size) {
+            ptr = 0;
+        } else if ptr >= cells.len() {
+            cells.push(0);
+        }
+        cells[ptr] = 1;
+        self.cells[self.ptr] = 0;
+
+        self.threads.push_back(Thread {
+            id: self.thread_stats.len(),
+            cells,
+            ptr,
+            code_idx: self.code_idx + 1,
+            jumped_back: false,
+        });
+        self.thread_stats.push(ThreadStats {
+            parent: Some(self.thread),
+            ..ThreadStats::default()
+        });
+    }
+
+    /// suspends the current thread, resuming the provided one instead
+    fn suspend(&mut self, next: Thread) -> Thread {
+        // the remembered states belong to the suspended thread
+        self.forget_states();
+
+        Thread {
+            id: std::mem::replace(&mut self.thread, next.id),
+            cells: std::mem::replace(&mut self.cells, next.cells),
+            ptr: std::mem::replace(&mut self.ptr, next.ptr),
+            code_idx: std::mem::replace(&mut self.code_idx, next.code_idx),
+            jumped_back: std::mem::replace(&mut self.jumped_back, next.jumped_back),
+        }
+    }
+
+    /// ends the current thread as it reached the end of the code,
+    /// halting the execution unless other threads are yet to reach it
+    fn halt_thread(&mut self) -> Action {
+        if let Some(stats) = self.thread_stats.get_mut(self.thread) {
+            stats.halted = true;
+        }
+        let Some(next) = self.threads.pop_front() else {
+            // the memory array of the main thread is the final one
+            if let Some((cells, ptr)) = self.main_thread.take() {
+                self.cells = cells;
+                self.ptr = ptr;
+                self.thread = 0;
+            }
+            return Action::Halt;
+        };
+        let halted = self.suspend(next);
+
+        if halted.id == 0 {
+            self.main_thread = Some((halted.cells, halted.ptr));
+        }
+        Action::Continue
+    }
+
+    /// remembers the current state at the start of a loop iteration,
+    /// flagging `repeated_state` if the exact same state was seen before
+    ///
+    /// repeating a state without any I/O in between means that the program never terminates
+    fn remember_state(&mut self) {
+        let Some(ref mut seen) = self.seen_states else {
+            return;
+        };
+        if seen.len() >= MAX_REMEMBERED_STATES {
+            seen.clear();
+        }
+        self.repeated_state = !seen.insert((self.code_idx, self.ptr, self.cells.clone()));
+    }
+
+    /// forgets every remembered state, as an I/O operation happened
+    fn forget_states(&mut self) {
+        if let Some(ref mut seen) = self.seen_states {
+            seen.clear();
+        }
+    }
+
+    /// records the access of the current cell by the provided operation, if accesses are being tracked
+    fn record_access(&mut self, chr: char) {
+        let Some(ref mut accesses) = self.accesses else {
+            return;
+        };
+        if accesses.len() <= self.ptr {
+            accesses.resize(self.ptr + 1, CellAccess::default());
+        }
+        let access = &mut accesses[self.ptr];
+
+        match chr {
+            '+' | '-' | ',' => access.writes += 1,
+            '.' | '[' => access.reads += 1,
+            _ => (),
+        }
+    }
+
+    /// returns the tracked cell accesses, if any, padded to the length of the memory array
+    fn cell_accesses(&self) -> Option<Vec<CellAccess>> {
+        self.accesses
+            .as_ref()
+            .map(|accesses| {
+                let mut accesses = accesses.clone();
+                accesses.resize(self.cells.len(), CellAccess::default());
+                accesses
+            })
+    }
+
+    /// checks whether or not the set timeout has elapsed
+    ///
+    /// the clock is only read every [`Self::CLOCK_CHECK_INTERVAL`] steps to keep its overhead negligible
+    pub fn timed_out(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline|
+                self.steps.is_multiple_of(Self::CLOCK_CHECK_INTERVAL)
+                && Instant::now() >= deadline
+            )
+    }
+
+    /// produces the [`RuntimeContext`] of an error occuring at the provided code index
+    pub fn context(&self, code_idx: usize) -> Box<RuntimeContext> {
+        let code_idx = self.origin(code_idx);
+        let code = self.origins
+            .as_ref()
+            .map_or(&self.code, |(code, _)| code);
+
+        Box::new(RuntimeContext {
+            code_idx,
+            position: Position::locate(code, code_idx),
+            pointer: self.ptr,
+            info: self.info(),
+        })
+    }
+
+    /// maps the provided index of the executed code to the index of the character of the code it stems from,
+    /// which only differs if the code was optimized
+    pub fn origin(&self, code_idx: usize) -> usize {
+        self.origins
+            .as_ref()
+            .map_or(code_idx, |(code, indices)| indices
+                .get(code_idx)
+                .copied()
+                .unwrap_or(code.len())
+            )
+    }
+
+    /// produces an [`ExecutionInfo`] snapshot of the execution thus far
+    pub fn info(&self) -> ExecutionInfo {
+        ExecutionInfo {
+            cells: self.cells.clone(),
+            mem_size: self.cells.len(),
+            pointer: self.ptr,
+            code_len: self.code_idx,
+            instructions: self.instructions,
+            opcode_counts: self.opcode_counts,
+            cell_accesses: self.cell_accesses(),
+            time: self.start
+                .map(|t| t.elapsed()),
+            threads: self.thread_stats.clone(),
+        }
+    }
+
+    /// consumes the machine, producing the final [`ExecutionInfo`]
+    pub fn into_info(self) -> ExecutionInfo {
+        let mem_size = self.cells.len();
+        let cell_accesses = self.cell_accesses();
+
+        ExecutionInfo {
+            cells: self.cells,
+            mem_size,
+            pointer: self.ptr,
+            code_len: self.code_idx,
+            instructions: self.instructions,
+            opcode_counts: self.opcode_counts,
+            cell_accesses,
+            time: self.start
+                .map(|t| t.elapsed()),
+            threads: self.thread_stats,
+        }
+    }
+}
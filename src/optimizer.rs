@@ -0,0 +1,597 @@
+//! module containing the [`Optimizer`], rewriting a [`Program`] into an equivalent but faster one
+//!
+//! see [`Optimizer::optimize`] for more information
+
+use std::fmt;
+use crate::{
+    ast::{Node, NodeKind, Program},
+    machine::INSTRUCTIONS,
+    Brainfuck,
+    Result,
+    Span,
+    DEFAULT_MAX_CELL_VALUE,
+};
+
+/// the name of the pass removing code after a loop that is entered and never terminates
+pub const UNREACHABLE_CODE: &str = "unreachable_code";
+/// the name of the pass removing loops that can never be entered, as the current cell is always `0` before them
+pub const DEAD_LOOP: &str = "dead_loop";
+/// the name of the pass removing modifications of a cell that is overwritten before being read
+pub const DEAD_STORE: &str = "dead_store";
+/// the name of the pass evaluating the code at the start of the program that does not depend on any input
+pub const PARTIAL_EVALUATION: &str = "partial_evaluation";
+
+/// the maximum amount of steps taken when partially evaluating a program, see [`PARTIAL_EVALUATION`]
+const PARTIAL_EVALUATION_LIMIT: usize = 1 << 20;
+
+/// how aggressively the [`Optimizer`] rewrites a program
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OptimizationLevel {
+    /// leaves the program as is
+    #[default]
+    None,
+    /// eliminates dead code, see [`UNREACHABLE_CODE`], [`DEAD_LOOP`] and [`DEAD_STORE`]
+    Basic,
+    /// also evaluates the start of the program that does not depend on any input at compile time,
+    /// folding its output into a single [`NodeKind::Emit`], see [`PARTIAL_EVALUATION`]
+    Aggressive,
+}
+
+impl OptimizationLevel {
+    /// returns the level of the provided lowercase name, such as `aggressive`, as taken by the bindings
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Self::None),
+            "basic" => Some(Self::Basic),
+            "aggressive" => Some(Self::Aggressive),
+            _ => None,
+        }
+    }
+}
+
+/// a piece of code removed, or replaced by the result of its evaluation, by the [`Optimizer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Removal {
+    /// the name of the pass that removed the code
+    pub pass: &'static str,
+    /// the range of the removed code
+    pub span: Span,
+    /// the amount of instructions of the program written back as code that the removal eliminated
+    pub instructions: usize,
+}
+
+/// the result of [`Optimizer::optimize`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Optimized {
+    /// the optimized program
+    pub program: Program,
+    /// every piece of code that was removed, in the order they appear in
+    pub removed: Vec<Removal>,
+    /// the amount of operations of the program before it was optimized, see [`Report::operations`]
+    pub operations: usize,
+}
+
+/// what a single pass of the [`Optimizer`] eliminated, see [`Report`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PassReport {
+    /// the name of the pass
+    pub pass: &'static str,
+    /// the amount of pieces of code the pass removed
+    pub removals: usize,
+    /// the amount of instructions the pass eliminated
+    pub instructions: usize,
+}
+
+/// a summary of what the [`Optimizer`] did to a program, see [`Optimized::report`]
+///
+/// an operation is a single node of the syntax tree, with a loop counting as two for its `[` and `]`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Report {
+    /// the amount of instructions of the original code
+    pub instructions: usize,
+    /// the amount of operations the instructions were collapsed into, merging every run of `+` and `-`, and of `>` and `<`
+    pub operations: usize,
+    /// the amount of operations of the optimized program
+    pub optimized_operations: usize,
+    /// the amount of instructions of the optimized program written back as code
+    pub optimized_instructions: usize,
+    /// every pass that removed any code, in the order they run in
+    pub passes: Vec<PassReport>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for pass in &self.passes {
+            writeln!(
+                f, "{}: {} removed, {} instructions eliminated",
+                pass.pass, pass.removals, pass.instructions,
+            )?;
+        }
+        writeln!(
+            f, "run-length encoding: {} instructions collapsed into {} operations",
+            self.instructions, self.operations,
+        )?;
+        write!(
+            f, "total: {} → {} operations, {} → {} instructions",
+            self.operations, self.optimized_operations, self.instructions, self.optimized_instructions,
+        )
+    }
+}
+
+impl Optimized {
+    /// summarizes which passes removed code and how much, along with the amount of instructions and operations
+    /// before and after optimizing, provided the code the program was parsed from
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{ast::Program, optimizer::{self, Optimizer}};
+    ///
+    /// let code = "[.]+++--[-]+[]>.";
+    /// let report = Optimizer::new()
+    ///     .optimize(Program::parse(code)?)
+    ///     .report(code);
+    ///
+    /// assert_eq!(report.instructions, 16);
+    /// assert_eq!(report.operations, 12);
+    /// assert_eq!(report.optimized_instructions, 3);
+    /// assert_eq!(report.passes[0].pass, optimizer::UNREACHABLE_CODE);
+    /// assert_eq!(report.passes[0].instructions, 2);
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn report(&self, code: &str) -> Report {
+        let passes = [UNREACHABLE_CODE, DEAD_LOOP, DEAD_STORE, PARTIAL_EVALUATION]
+            .into_iter()
+            .map(|pass| {
+                let removals = self.removed
+                    .iter()
+                    .filter(|removal| removal.pass == pass);
+
+                PassReport {
+                    pass,
+                    removals: removals.clone().count(),
+                    instructions: removals.map(|removal| removal.instructions).sum(),
+                }
+            })
+            .filter(|pass| pass.removals > 0)
+            .collect();
+
+        Report {
+            instructions: code
+                .chars()
+                .filter(|chr| INSTRUCTIONS.contains(*chr))
+                .count(),
+            operations: self.operations,
+            optimized_operations: operations(&self.program.nodes),
+            optimized_instructions: self.program.to_string().len(),
+            passes,
+        }
+    }
+}
+
+/// what is known about the value of the current cell
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    /// the value is known exactly
+    Known(u32),
+    /// the value is not known, other than not being `0`
+    NonZero,
+    /// nothing is known about the value
+    Unknown,
+}
+
+/// returns whether or not any of the provided nodes can change the current cell, or which cell is current
+fn modifies(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| match node.kind {
+        NodeKind::Inc(amount) => amount != 0,
+        NodeKind::Move(amount) => amount != 0,
+        NodeKind::Loop(ref body) => modifies(body),
+        NodeKind::Input => true,
+        _ => false,
+    })
+}
+
+/// returns whether or not the loop with the provided body always clears the current cell, such as `[-]`
+fn is_clear(body: &[Node]) -> bool {
+    matches!(body, [Node { kind: NodeKind::Inc(1 | -1), .. }])
+}
+
+/// returns the amount of operations of the provided nodes, counting a loop as two for its `[` and `]`
+fn operations(nodes: &[Node]) -> usize {
+    nodes.iter()
+        .map(|node| match node.kind {
+            NodeKind::Loop(ref body) => 2 + operations(body),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// returns the amount of instructions of the provided nodes written back as code
+fn instructions(nodes: &[Node]) -> usize {
+    nodes.iter()
+        .map(|node| node.to_string().len())
+        .sum()
+}
+
+/// returns the span from the start of the first node up until the end of the last node
+fn span_of(nodes: &[Node]) -> Span {
+    Span {
+        start: nodes[0].span.start,
+        end: nodes[nodes.len() - 1].span.end,
+    }
+}
+
+/// the state of the dead code elimination
+struct Eliminator {
+    /// the maximum value of a cell
+    max_cell_value: u32,
+    /// the code removed thus far
+    removed: Vec<Removal>,
+}
+
+impl Eliminator {
+    /// records the removal of the provided nodes
+    fn remove(&mut self, pass: &'static str, nodes: &[Node]) {
+        self.removed.push(Removal {
+            pass,
+            span: span_of(nodes),
+            instructions: instructions(nodes),
+        });
+    }
+
+    /// removes the modification of the current cell right before the provided position in `out`, if any,
+    /// as the cell is about to be overwritten
+    fn dead_store(&mut self, out: &mut Vec<Node>) {
+        if let Some(node) = out.pop_if(|node| matches!(node.kind, NodeKind::Inc(_))) {
+            self.remove(DEAD_STORE, &[node]);
+        }
+    }
+
+    /// eliminates the dead code of the provided sequence of nodes
+    ///
+    /// `cell` is what is known about the current cell before the first node,
+    /// and `pristine` is whether or not every cell is still `0`;
+    /// also returns whether or not the sequence never terminates once reached
+    fn sequence(&mut self, nodes: Vec<Node>, mut cell: Cell, mut pristine: bool) -> (Vec<Node>, bool) {
+        let mut out = Vec::with_capacity(nodes.len());
+        let mut nodes = nodes.into_iter();
+
+        while let Some(node) = nodes.next() {
+            match node.kind {
+                NodeKind::Inc(amount) => {
+                    cell = match cell {
+                        Cell::Known(value) => {
+                            let modulo = i64::from(self.max_cell_value) + 1;
+                            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                            let value = (i64::from(value) + amount).rem_euclid(modulo) as u32;
+                            Cell::Known(value)
+                        },
+                        _ => Cell::Unknown,
+                    };
+                    pristine &= amount == 0;
+                    out.push(node);
+                },
+                NodeKind::Move(amount) => {
+                    if amount != 0 {
+                        cell = if pristine { Cell::Known(0) } else { Cell::Unknown };
+                    }
+                    out.push(node);
+                },
+                NodeKind::Input => {
+                    self.dead_store(&mut out);
+                    cell = Cell::Unknown;
+                    pristine = false;
+                    out.push(node);
+                },
+                NodeKind::Loop(_) if cell == Cell::Known(0) => {
+                    self.remove(DEAD_LOOP, &[node]);
+                },
+                NodeKind::Loop(body) => {
+                    if is_clear(&body) {
+                        self.dead_store(&mut out);
+                    }
+                    let endless = !modifies(&body);
+                    let (body, diverges) = self.sequence(body, Cell::NonZero, false);
+                    out.push(Node { kind: NodeKind::Loop(body), span: node.span });
+
+                    if cell != Cell::Unknown && (endless || diverges) {
+                        let rest = nodes.collect::<Vec<Node>>();
+                        if !rest.is_empty() {
+                            self.remove(UNREACHABLE_CODE, &rest);
+                        }
+                        return (out, true);
+                    }
+                    // the current cell is always `0` right after a loop
+                    cell = Cell::Known(0);
+                    pristine = false;
+                },
+                _ => out.push(node),
+            }
+        }
+        (out, false)
+    }
+}
+
+/// the state of a partial evaluation of a program, see [`PARTIAL_EVALUATION`]
+#[derive(Clone)]
+pub(crate) struct Evaluator {
+    /// the memory array
+    pub(crate) cells: Vec<u32>,
+    /// the pointer
+    pub(crate) ptr: usize,
+    /// the output written thus far
+    pub(crate) output: String,
+    /// the amount of steps taken thus far
+    steps: usize,
+    /// the maximum value of a cell
+    max_cell_value: u32,
+    /// the fixed size of the memory array, if any
+    memory_size: Option<usize>,
+}
+
+impl Evaluator {
+    /// evaluates the provided node, returning `false` if it cannot be evaluated at compile time,
+    /// as it reads input, takes too many steps or moves the pointer out of the memory array
+    fn eval(&mut self, node: &Node) -> bool {
+        self.steps += match node.kind {
+            NodeKind::Inc(amount) => usize::try_from(amount.unsigned_abs()).unwrap_or(usize::MAX),
+            NodeKind::Move(amount) => amount.unsigned_abs(),
+            _ => 1,
+        };
+        if self.steps > PARTIAL_EVALUATION_LIMIT {
+            return false;
+        }
+
+        match node.kind {
+            NodeKind::Inc(amount) => {
+                let modulo = i64::from(self.max_cell_value) + 1;
+                let cell = &mut self.cells[self.ptr];
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let value = (i64::from(*cell) + amount).rem_euclid(modulo) as u32;
+                *cell = value;
+            },
+            NodeKind::Move(amount) => {
+                // moving out of the memory array wraps the pointer around, which is left to the execution
+                let Some(ptr) = self.ptr
+                    .checked_add_signed(amount)
+                    .filter(|ptr| self.memory_size.is_none_or(|size| *ptr < size))
+                else {
+                    return false;
+                };
+                if ptr >= self.cells.len() {
+                    self.cells.resize(ptr + 1, 0);
+                }
+                self.ptr = ptr;
+            },
+            NodeKind::Loop(ref body) => while self.cells[self.ptr] != 0 {
+                if !body.iter().all(|node| self.eval(node)) {
+                    return false;
+                }
+                self.steps += 1;
+                if self.steps > PARTIAL_EVALUATION_LIMIT {
+                    return false;
+                }
+            },
+            NodeKind::Input => return false,
+            NodeKind::Output => if let Some(chr) = char::from_u32(self.cells[self.ptr]) {
+                self.output.push(chr);
+            },
+            NodeKind::Emit(ref string) => self.output += string,
+        }
+        true
+    }
+
+    /// returns the nodes recreating the state of the evaluation from an empty memory array
+    fn to_nodes(&self, span: Span) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        let mut ptr = 0;
+
+        if !self.output.is_empty() {
+            nodes.push(Node { kind: NodeKind::Emit(self.output.clone()), span });
+        }
+        for (idx, value) in self.cells.iter().enumerate() {
+            if *value == 0 {
+                continue;
+            }
+            let value = i64::from(*value);
+            let modulo = i64::from(self.max_cell_value) + 1;
+
+            #[allow(clippy::cast_possible_wrap)]
+            nodes.push(Node { kind: NodeKind::Move(idx as isize - ptr as isize), span });
+            // wrapping around is shorter for values above half the max value of a cell
+            nodes.push(Node {
+                kind: NodeKind::Inc(if value > modulo / 2 { value - modulo } else { value }),
+                span,
+            });
+            ptr = idx;
+        }
+        #[allow(clippy::cast_possible_wrap)]
+        nodes.push(Node { kind: NodeKind::Move(self.ptr as isize - ptr as isize), span });
+
+        nodes.retain(|node| !matches!(node.kind, NodeKind::Move(0)));
+        nodes
+    }
+}
+
+/// rewrites a [`Program`] into an equivalent one that executes fewer instructions
+///
+/// the optimized program behaves the same on a memory array of the size it was optimized for,
+/// other than the amount of instructions it takes;
+/// on a growable memory array, it can allocate fewer cells, so moving left of the first cell,
+/// which wraps around to the last cell allocated so far, can end up on another cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Optimizer {
+    /// how aggressively to rewrite the program, defaults to [`OptimizationLevel::Basic`]
+    pub level: OptimizationLevel,
+    /// the maximum value of a cell the program is executed with, defaults to [`DEFAULT_MAX_CELL_VALUE`]
+    pub max_cell_value: u32,
+    /// the fixed size of the memory array the program is executed with, if any, defaults to [`None`]
+    pub memory_size: Option<usize>,
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Optimizer {
+    /// creates a new optimizer eliminating dead code,
+    /// for programs executed with the default max value of a cell and a growable memory array
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            level: OptimizationLevel::Basic,
+            max_cell_value: DEFAULT_MAX_CELL_VALUE,
+            memory_size: None,
+        }
+    }
+
+    /// builder method to specify how aggressively to rewrite the program
+    #[must_use]
+    pub const fn with_level(mut self, level: OptimizationLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// builder method to specify the fixed size of the memory array the program is executed with
+    #[must_use]
+    pub const fn with_mem_size(mut self, mem_size: Option<usize>) -> Self {
+        self.memory_size = mem_size;
+        self
+    }
+
+    /// builder method to specify the max value of a cell the program is executed with
+    #[must_use]
+    pub const fn with_max_value(mut self, cell_value: u32) -> Self {
+        self.max_cell_value = cell_value;
+        self
+    }
+
+    /// optimizes the provided program, returning it along with what was removed
+    ///
+    /// [`OptimizationLevel::Basic`] eliminates dead code, removing:
+    /// - [`UNREACHABLE_CODE`]: code after a loop that is entered and never terminates
+    /// - [`DEAD_LOOP`]: loops that can never be entered, such as those at the start of the code or right after another loop
+    /// - [`DEAD_STORE`]: `+` and `-` right before the cell is overwritten by `,`, `[-]` or `[+]`
+    ///
+    /// [`OptimizationLevel::Aggressive`] also evaluates the start of the program up until it reads any input,
+    /// replacing it with its output folded into a single [`NodeKind::Emit`] and the code recreating its memory array,
+    /// unless that would take more instructions ([`PARTIAL_EVALUATION`])
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{ast::Program, optimizer::{self, Optimizer}};
+    ///
+    /// let optimized = Optimizer::new().optimize(Program::parse("[.]++[-]+[]>.")?);
+    ///
+    /// assert_eq!(optimized.program.to_string(), "+[]");
+    /// assert_eq!(optimized.removed.len(), 4);
+    /// assert_eq!(optimized.removed[0].pass, optimizer::DEAD_LOOP);
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn optimize(&self, program: Program) -> Optimized {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("optimize", level = ?self.level).entered();
+
+        if self.level == OptimizationLevel::None {
+            return Optimized { operations: operations(&program.nodes), program, removed: Vec::new() };
+        }
+        let operations = operations(&program.nodes);
+        let mut eliminator = Eliminator {
+            max_cell_value: self.max_cell_value,
+            removed: Vec::new(),
+        };
+        let mut nodes = program.nodes;
+
+        // removing code can make more code dead, such as a loop that was only entered after a dead store
+        loop {
+            let removed = eliminator.removed.len();
+            (nodes, _) = eliminator.sequence(nodes, Cell::Known(0), true);
+
+            if eliminator.removed.len() == removed {
+                break;
+            }
+        }
+
+        let mut removed = eliminator.removed;
+        if self.level >= OptimizationLevel::Aggressive {
+            nodes = self.partially_evaluate(nodes, &mut removed);
+        }
+        removed.sort_by_key(|removal| removal.span.start);
+
+        Optimized {
+            program: Program { nodes },
+            removed,
+            operations,
+        }
+    }
+}
+
+impl Optimizer {
+    /// evaluates the top-level nodes up until the first one that cannot be evaluated at compile time,
+    /// returning the state after them along with the amount of nodes evaluated, if any
+    pub(crate) fn evaluate_prefix(&self, nodes: &[Node]) -> Option<(Evaluator, usize)> {
+        let mut evaluator = Evaluator {
+            cells: vec![0; self.memory_size.unwrap_or(1)],
+            ptr: 0,
+            output: String::new(),
+            steps: 0,
+            max_cell_value: self.max_cell_value,
+            memory_size: self.memory_size,
+        };
+        let mut evaluated = 0;
+
+        for node in nodes {
+            let snapshot = evaluator.clone();
+            if !evaluator.eval(node) {
+                evaluator = snapshot;
+                break;
+            }
+            evaluated += 1;
+        }
+        (evaluated > 0).then_some((evaluator, evaluated))
+    }
+
+    /// replaces the top-level nodes that can be evaluated at compile time with the result of their evaluation,
+    /// if it is shorter
+    fn partially_evaluate(&self, nodes: Vec<Node>, removed: &mut Vec<Removal>) -> Vec<Node> {
+        let Some((evaluator, evaluated)) = self.evaluate_prefix(&nodes) else {
+            return nodes;
+        };
+
+        let span = span_of(&nodes[..evaluated]);
+        let folded = evaluator.to_nodes(span);
+        let len = instructions(&folded);
+
+        if len >= evaluator.steps {
+            return nodes;
+        }
+        removed.push(Removal {
+            pass: PARTIAL_EVALUATION,
+            span,
+            instructions: instructions(&nodes[..evaluated]).saturating_sub(len),
+        });
+
+        folded.into_iter()
+            .chain(nodes.into_iter().skip(evaluated))
+            .collect()
+    }
+}
+
+impl Brainfuck<'_> {
+    /// parses and optimizes the code for the configured max value of a cell and memory size,
+    /// at the configured optimization level, but at least [`OptimizationLevel::Basic`], see [`Optimizer::optimize`]
+    ///
+    /// # Errors
+    /// - [`crate::Error::MismatchedBrackets`]: the code contains an unmatched `[` or a stray `]`
+    pub fn optimize(&self) -> Result<Optimized> {
+        Program::parse(&self.code)
+            .map(|program| Optimizer::new()
+                .with_level(self.optimization.max(OptimizationLevel::Basic))
+                .with_max_value(self.max_cell_value)
+                .with_mem_size(self.memory_size)
+                .optimize(program)
+            )
+    }
+}
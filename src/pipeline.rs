@@ -0,0 +1,204 @@
+//! module containing [`Pipeline`], used to chain the output of programs into the input of others
+
+use std::{
+    io::{self, Read, Write},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+use crate::{Brainfuck, Error, ExecutionInfo, Result, HAS_THREADS};
+
+/// the writing end of an in-memory pipe, sending every write through a channel
+pub(crate) struct PipeWriter(Sender<Vec<u8>>);
+
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .send(buf.to_vec())
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// the reading end of an in-memory pipe
+///
+/// reaches EOF once every [`PipeWriter`] has been dropped
+pub(crate) struct PipeReader {
+    /// receives the chunks written into the pipe
+    receiver: Receiver<Vec<u8>>,
+    /// the chunk currently being read from
+    chunk: Vec<u8>,
+    /// the position of the next unread byte in `chunk`
+    pos: usize,
+}
+
+impl Read for PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos >= self.chunk.len() {
+            match self.receiver.recv() {
+                Ok(chunk) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                },
+                Err(_) => return Ok(0),
+            }
+        }
+        let amount = buf.len()
+            .min(self.chunk.len() - self.pos);
+
+        buf[..amount].copy_from_slice(&self.chunk[self.pos..self.pos + amount]);
+        self.pos += amount;
+        Ok(amount)
+    }
+}
+
+/// creates a new connected in-memory pipe
+pub(crate) fn pipe() -> (PipeWriter, PipeReader) {
+    let (sender, receiver) = mpsc::channel();
+
+    (
+        PipeWriter(sender),
+        PipeReader { receiver, chunk: Vec::new(), pos: 0 },
+    )
+}
+
+/// a chain of brainfuck programs, where the output of each stage's `.` operation
+/// is streamed into the input of the next stage's `,` operation
+///
+/// ```rust
+/// use std::io::Cursor;
+/// use brainfuck_exe::{Brainfuck, Pipeline, Result};
+///
+/// fn main() -> Result<()> {
+///     Pipeline::new()
+///         // increments every byte of the input
+///         .then(Brainfuck::new(",[+.,]"))
+///         .then(Brainfuck::new(",[+.,]")
+///             .with_output(Cursor::new(Vec::new()))
+///         )
+///         .execute(Cursor::new(b"abc".to_vec()))?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Default)]
+pub struct Pipeline {
+    /// the programs of the pipeline, in order
+    stages: Vec<Brainfuck<'static>>,
+}
+
+impl Pipeline {
+    /// creates a new, empty pipeline
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// builder method to append a program to the end of the pipeline
+    ///
+    /// the input stream of every stage but the first, and the output stream of every stage but the last
+    /// are replaced with the pipes connecting them
+    #[must_use]
+    pub fn then(mut self, stage: Brainfuck<'static>) -> Self {
+        self.stages.push(stage);
+        self
+    }
+
+    /// returns the amount of stages in the pipeline
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// returns whether or not the pipeline has no stages
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// executes every stage of the pipeline concurrently, each on its own thread,
+    /// feeding `input` into the first stage
+    ///
+    /// on targets that cannot spawn threads, such as WASI, the stages are executed one after the other instead,
+    /// each stage buffering its whole output for the next one
+    ///
+    /// the last stage writes into its own configured output stream,
+    /// defaulting to [`std::io::stdout`]
+    ///
+    /// returns the [`ExecutionInfo`] of every stage in order
+    ///
+    /// # Errors
+    /// if any of the stages fail, the error of the last failing stage is returned;
+    /// the stages before it usually only fail with a broken pipe as a consequence
+    pub fn execute<I>(self, input: I) -> Result<Vec<ExecutionInfo>>
+    where
+        I: Read + Send + 'static
+    {
+        let amount = self.stages.len();
+        let mut next_input = Some(Box::new(input) as Box<dyn Read + Send>);
+
+        if !HAS_THREADS {
+            return self.execute_sequentially(next_input);
+        }
+        let handles = self.stages
+            .into_iter()
+            .enumerate()
+            .map(|(idx, mut stage)| {
+                if let Some(input) = next_input.take() {
+                    stage.input = Some(crate::Reader::Value(input));
+                }
+                if idx + 1 < amount {
+                    let (writer, reader) = pipe();
+
+                    stage = stage.with_output(writer);
+                    next_input = Some(Box::new(reader));
+                }
+                thread::spawn(move || stage.execute())
+            })
+            .collect::<Vec<_>>();
+
+        let mut infos = Vec::with_capacity(amount);
+        let mut error: Option<Error> = None;
+
+        for handle in handles {
+            match handle
+                .join()
+                .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            {
+                Ok(info) => infos.push(info),
+                Err(err) => error = Some(err),
+            }
+        }
+
+        error.map_or(Ok(infos), Err)
+    }
+
+    /// executes every stage of the pipeline one after the other on the current thread,
+    /// which the unbounded pipes allow, as every stage finishes writing before the next one starts reading
+    fn execute_sequentially(self, mut next_input: Option<Box<dyn Read + Send>>) -> Result<Vec<ExecutionInfo>> {
+        let amount = self.stages.len();
+        let mut infos = Vec::with_capacity(amount);
+        let mut error: Option<Error> = None;
+
+        for (idx, mut stage) in self.stages.into_iter().enumerate() {
+            if let Some(input) = next_input.take() {
+                stage.input = Some(crate::Reader::Value(input));
+            }
+            if idx + 1 < amount {
+                let (writer, reader) = pipe();
+
+                stage = stage.with_output(writer);
+                next_input = Some(Box::new(reader));
+            }
+            // the stage, along with the writing end of its pipe, is dropped once it finishes
+            match stage.execute() {
+                Ok(info) => infos.push(info),
+                Err(err) => error = Some(err),
+            }
+        }
+
+        error.map_or(Ok(infos), Err)
+    }
+}
@@ -0,0 +1,71 @@
+//! module containing [`Position`] and [`Span`], locations within the brainfuck code
+
+use std::fmt;
+
+/// a location within the brainfuck code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// the byte offset from the start of the code
+    pub offset: usize,
+    /// the line, starting at `1`
+    pub line: usize,
+    /// the column in characters, starting at `1`
+    pub column: usize,
+}
+
+impl Position {
+    /// locates the character at the provided index of the code split into its characters
+    pub(crate) fn locate(code: &[char], idx: usize) -> Self {
+        code.iter()
+            .take(idx)
+            .fold(Self::START, |position, chr| position.advance(*chr))
+    }
+
+    /// locates every character of the code split into its characters,
+    /// including the position just past its end
+    pub(crate) fn locate_all(code: &[char]) -> Vec<Self> {
+        let mut positions = Vec::with_capacity(code.len() + 1);
+        positions.push(Self::START);
+
+        for chr in code {
+            let last = positions[positions.len() - 1];
+            positions.push(last.advance(*chr));
+        }
+        positions
+    }
+
+    /// the position of the start of the code
+    const START: Self = Self { offset: 0, line: 1, column: 1 };
+
+    /// returns the position right after the provided character at this position
+    const fn advance(self, chr: char) -> Self {
+        if chr == '\n' {
+            Self { offset: self.offset + 1, line: self.line + 1, column: 1 }
+        } else {
+            Self { offset: self.offset + chr.len_utf8(), line: self.line, column: self.column + 1 }
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// a range of characters within the brainfuck code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    /// the position of the first character of the range
+    pub start: Position,
+    /// the position just past the last character of the range
+    pub end: Position,
+}
+
+impl Span {
+    /// returns the byte range of the span, which can be used to slice the code
+    #[must_use]
+    pub const fn range(&self) -> std::ops::Range<usize> {
+        self.start.offset..self.end.offset
+    }
+}
@@ -0,0 +1,392 @@
+//! module containing [`Profiler`], an [`ExecutionObserver`] attributing the executed instructions to the loops of the code
+
+use std::{
+    fmt::{self, Write},
+    time::{Duration, Instant},
+};
+use crate::{ExecutionInfo, ExecutionObserver, HookCtx};
+
+/// the maximum amount of instructions of a loop's body shown when displaying a [`LoopProfile`]
+const DISPLAY_BODY_LEN: usize = 40;
+
+/// the profile of a single loop of the code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopProfile {
+    /// the code index of the loop's `[`
+    pub code_idx: usize,
+    /// the line of the loop's `[`, starting at `1`
+    pub line: usize,
+    /// the column of the loop's `[`, starting at `1`
+    pub column: usize,
+    /// the source text of the loop, from its `[` up to and including its `]`
+    pub body: String,
+    /// the amount of times the loop was entered from outside of it
+    pub entries: usize,
+    /// the amount of iterations of the loop across all of its entries
+    pub iterations: usize,
+    /// the amount of instructions executed within the loop, including those of its nested loops
+    pub instructions: usize,
+    /// the amount of instructions executed within the loop, excluding those of its nested loops
+    pub self_instructions: usize,
+    /// the time spent within the loop, including its nested loops
+    ///
+    /// it is [`None`] unless timing was enabled with [`Profiler::with_timing`]
+    pub time: Option<Duration>,
+}
+
+impl fmt::Display for LoopProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut body = self.body
+            .chars()
+            .filter(|chr| crate::machine::INSTRUCTIONS.contains(*chr))
+            .collect::<String>();
+
+        if let Some((idx, _)) = body.char_indices().nth(DISPLAY_BODY_LEN) {
+            body.truncate(idx);
+            body.push_str("...");
+        }
+        write!(
+            f, "{}:{} {} instructions ({} self), {} iterations",
+            self.line, self.column, self.instructions, self.self_instructions, self.iterations,
+        )?;
+        if let Some(time) = self.time {
+            write!(f, ", {time:?}")?;
+        }
+        write!(f, " {body}")
+    }
+}
+
+/// the static information and counters of a single loop
+#[derive(Debug, Clone)]
+struct LoopData {
+    /// the code index of the loop's `[`
+    open: usize,
+    /// the code index of the loop's `]`
+    close: usize,
+    /// the index of the loop directly enclosing this one, if any
+    parent: Option<usize>,
+    /// the amount of times the loop was entered from outside of it
+    entries: usize,
+    /// the amount of `[` operations of the loop executed
+    starts: usize,
+    /// the amount of `]` operations of the loop executed
+    iterations: usize,
+    /// the amount of instructions executed directly within the loop
+    self_instructions: usize,
+    /// the time spent directly within the loop
+    self_time: Duration,
+}
+
+/// an [`ExecutionObserver`] attributing the executed instructions (and optionally the time spent)
+/// to the loops of the code, used to find the hottest loops of a program
+///
+/// the brackets of a loop are attributed to the loop itself
+///
+/// ```rust
+/// use brainfuck_exe::{Brainfuck, Profiler};
+///
+/// let code = "++++[>++++[>+<-]<-]";
+/// let mut profiler = Profiler::new(code);
+///
+/// Brainfuck::new(code)
+///     .with_observer(&mut profiler)
+///     .execute()
+///     .unwrap();
+///
+/// let hottest = &profiler.hottest_loops(1)[0];
+/// assert_eq!((hottest.line, hottest.column), (1, 11));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    /// every loop of the code, in the order of their `[`
+    loops: Vec<LoopData>,
+    /// maps every code index to the innermost loop containing it, if any
+    owners: Vec<Option<usize>>,
+    /// the line and column of every code index
+    positions: Vec<(usize, usize)>,
+    /// the code split into its characters
+    code: Vec<char>,
+    /// the amount of instructions executed outside of any loop
+    top_level_instructions: usize,
+    /// whether or not to measure the time spent within each loop
+    timing: bool,
+    /// the time and owning loop of the last executed instruction, if timing
+    last: Option<(Instant, Option<usize>)>,
+}
+
+impl Profiler {
+    /// creates a new profiler for the provided brainfuck code
+    ///
+    /// the code must be the same as the code of the interpreter it observes;
+    /// unmatched brackets are ignored
+    #[must_use]
+    pub fn new<S: AsRef<str>>(code: S) -> Self {
+        let code = code.as_ref()
+            .chars()
+            .collect::<Vec<char>>();
+
+        let mut loops = Vec::new();
+        let mut owners = vec![None; code.len()];
+        let mut positions = Vec::with_capacity(code.len());
+        let mut stack: Vec<usize> = Vec::new();
+        let (mut line, mut column) = (1, 1);
+
+        for (idx, chr) in code.iter().enumerate() {
+            positions.push((line, column));
+            if *chr == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+
+            match chr {
+                '[' => {
+                    loops.push(LoopData {
+                        open: idx,
+                        close: idx,
+                        parent: stack.last().copied(),
+                        entries: 0,
+                        starts: 0,
+                        iterations: 0,
+                        self_instructions: 0,
+                        self_time: Duration::ZERO,
+                    });
+                    stack.push(loops.len() - 1);
+                },
+                ']' => if let Some(id) = stack.pop() {
+                    loops[id].close = idx;
+                    owners[idx] = Some(id);
+                },
+                _ => (),
+            }
+            owners[idx] = owners[idx].or_else(|| stack.last().copied());
+        }
+        // loops left open by unmatched brackets are never executed
+        for id in stack {
+            loops[id].close = code.len().saturating_sub(1);
+        }
+
+        Self {
+            loops,
+            owners,
+            positions,
+            code,
+            top_level_instructions: 0,
+            timing: false,
+            last: None,
+        }
+    }
+
+    /// builder method to enable measuring the time spent within each loop
+    ///
+    /// this reads the clock before every instruction, which slows down the execution considerably
+    #[must_use]
+    pub const fn with_timing(mut self, timing: bool) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// attributes the time elapsed since the last instruction to the loop it was executed in
+    fn record_time(&mut self, now: Instant, next: Option<usize>) {
+        if let Some((then, Some(id))) = self.last {
+            self.loops[id].self_time += now - then;
+        }
+        self.last = Some((now, next));
+    }
+
+    /// returns the profile of every loop of the code, in the order of their `[`
+    #[must_use]
+    pub fn loops(&self) -> Vec<LoopProfile> {
+        let mut totals = self.loops
+            .iter()
+            .map(|data| (data.self_instructions, data.self_time))
+            .collect::<Vec<_>>();
+
+        // nested loops always come after their parents, so propagating in reverse accumulates every descendant
+        for (id, data) in self.loops.iter().enumerate().rev() {
+            if let Some(parent) = data.parent {
+                let (instructions, time) = totals[id];
+                totals[parent].0 += instructions;
+                totals[parent].1 += time;
+            }
+        }
+
+        self.loops
+            .iter()
+            .zip(totals)
+            .map(|(data, (instructions, time))| {
+                let (line, column) = self.positions[data.open];
+
+                LoopProfile {
+                    code_idx: data.open,
+                    line,
+                    column,
+                    body: self.code[data.open..=data.close]
+                        .iter()
+                        .collect(),
+                    entries: data.entries,
+                    iterations: data.iterations,
+                    instructions,
+                    self_instructions: data.self_instructions,
+                    time: self.timing
+                        .then_some(time),
+                }
+            })
+            .collect()
+    }
+
+    /// returns the profiles of the `amount` loops that executed the most instructions,
+    /// excluding those of their nested loops, from hottest to coldest
+    #[must_use]
+    pub fn hottest_loops(&self, amount: usize) -> Vec<LoopProfile> {
+        let mut loops = self.loops();
+
+        loops.sort_by(|a, b| b.self_instructions
+            .cmp(&a.self_instructions)
+            .then(a.code_idx.cmp(&b.code_idx))
+        );
+        loops.truncate(amount);
+        loops
+    }
+
+    /// returns the name of the loop used by the exports, `main` being the code outside of any loop
+    fn frame_name(profile: Option<&LoopProfile>) -> String {
+        profile.map_or_else(
+            || String::from("main"),
+            |profile| format!("loop@{}:{}", profile.line, profile.column),
+        )
+    }
+
+    /// exports the profile in the folded stacks format,
+    /// consumable by [`inferno`](https://github.com/jonhoo/inferno) and `flamegraph.pl`
+    ///
+    /// every loop is a frame nested within the loops enclosing it, all under a `main` frame,
+    /// weighted by the amount of instructions executed directly within it
+    #[must_use]
+    pub fn to_folded(&self) -> String {
+        let profiles = self.loops();
+        let mut folded = String::new();
+
+        if self.top_level_instructions > 0 {
+            let _ = writeln!(folded, "main {}", self.top_level_instructions);
+        }
+        for (id, profile) in profiles.iter().enumerate() {
+            if profile.self_instructions == 0 {
+                continue;
+            }
+            let mut stack = vec![Self::frame_name(Some(profile))];
+            let mut parent = self.loops[id].parent;
+
+            while let Some(parent_id) = parent {
+                stack.push(Self::frame_name(Some(&profiles[parent_id])));
+                parent = self.loops[parent_id].parent;
+            }
+            stack.push(Self::frame_name(None));
+            stack.reverse();
+
+            let _ = writeln!(folded, "{} {}", stack.join(";"), profile.self_instructions);
+        }
+        folded
+    }
+
+    /// exports the profile in the callgrind format,
+    /// consumable by tools such as [`KCachegrind`](https://kcachegrind.github.io)
+    ///
+    /// every loop is a function called by the loop enclosing it, or by a `main` function,
+    /// costing the amount of instructions executed within it;
+    /// `file_name` is the name of the source file the costs are attributed to
+    #[must_use]
+    pub fn to_callgrind(&self, file_name: &str) -> String {
+        let profiles = self.loops();
+        let mut callgrind = format!(
+            "# callgrind format\nversion: 1\ncreator: brainfuck-exe\nevents: Instructions\n\nfl={file_name}\n"
+        );
+
+        let mut write_function = |
+            profile: Option<&LoopProfile>,
+            self_instructions: usize,
+            parent: Option<usize>,
+        | {
+            let line = profile.map_or(1, |profile| profile.line);
+            let _ = write!(
+                callgrind, "\nfn={}\n{line} {self_instructions}\n", Self::frame_name(profile),
+            );
+
+            for (id, child) in profiles.iter().enumerate() {
+                let data = &self.loops[id];
+                // every `[` evaluation that is not the start of another iteration is a call
+                let calls = data.starts - data.iterations;
+
+                if data.parent == parent && calls > 0 {
+                    let _ = write!(
+                        callgrind, "cfn={}\ncalls={calls} {}\n{} {}\n",
+                        Self::frame_name(Some(child)), child.line,
+                        child.line, child.instructions,
+                    );
+                }
+            }
+        };
+
+        write_function(None, self.top_level_instructions, None);
+        for (id, profile) in profiles.iter().enumerate() {
+            if profile.instructions > 0 {
+                write_function(Some(profile), profile.self_instructions, Some(id));
+            }
+        }
+        callgrind
+    }
+}
+
+impl ExecutionObserver for Profiler {
+    fn on_start(&mut self) {
+        for data in &mut self.loops {
+            data.entries = 0;
+            data.starts = 0;
+            data.iterations = 0;
+            data.self_instructions = 0;
+            data.self_time = Duration::ZERO;
+        }
+        self.top_level_instructions = 0;
+        self.last = None;
+    }
+
+    fn on_instruction(&mut self, ctx: &HookCtx) {
+        let owner = self.owners
+            .get(ctx.code_idx)
+            .copied()
+            .flatten();
+
+        if let Some(id) = owner {
+            let data = &mut self.loops[id];
+            data.self_instructions += 1;
+
+            match ctx.opcode {
+                '[' => data.starts += 1,
+                ']' => data.iterations += 1,
+                _ => (),
+            }
+        } else {
+            self.top_level_instructions += 1;
+        }
+        if self.timing {
+            self.record_time(Instant::now(), owner);
+        }
+    }
+
+    fn on_loop_enter(&mut self, code_idx: usize) {
+        if let Some(id) = self.owners
+            .get(code_idx)
+            .copied()
+            .flatten()
+        {
+            self.loops[id].entries += 1;
+        }
+    }
+
+    fn on_halt(&mut self, _info: &ExecutionInfo) {
+        if self.timing {
+            self.record_time(Instant::now(), None);
+        }
+    }
+}
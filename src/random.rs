@@ -0,0 +1,50 @@
+//! module containing the built-in generator of the random values of the `?` operation
+
+use std::hash::{BuildHasher, RandomState};
+use crate::RandomSource;
+
+/// how the built-in generator was seeded, so that a copy of the interpreter can seed its own generator
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Seed {
+    /// seeded with the provided seed, see [`crate::Brainfuck::with_random_seed`]
+    Fixed(u64),
+    /// seeded differently for every interpreter, see [`crate::Brainfuck::with_random`]
+    Entropy,
+}
+
+impl Seed {
+    /// returns a new built-in generator seeded this way
+    pub(crate) fn generator(self) -> RandomSource<'static> {
+        match self {
+            Self::Fixed(seed) => seeded(seed),
+            Self::Entropy => seeded(entropy()),
+        }
+    }
+}
+
+/// returns a generator of random values seeded with the provided seed, producing the same values for the same seed
+///
+/// it is the [SplitMix64](https://prng.di.unimi.it/splitmix64.c) generator,
+/// which is fast and good enough for programs rather than cryptography
+pub(crate) fn seeded(seed: u64) -> RandomSource<'static> {
+    let mut state = seed;
+
+    Box::new(move || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut value = state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^= value >> 31;
+
+        // the upper bits are the most random ones
+        #[allow(clippy::cast_possible_truncation)]
+        let value = (value >> 32) as u32;
+        value
+    })
+}
+
+/// returns a seed that differs between every call, taken from the randomly seeded hasher of the standard library
+pub(crate) fn entropy() -> u64 {
+    RandomState::new().hash_one(std::time::SystemTime::now())
+}
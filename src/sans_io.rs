@@ -0,0 +1,137 @@
+//! module containing the sans-IO execution mode of the interpreter
+//!
+//! see [`Brainfuck::start`] for more information
+
+use crate::{Action, Brainfuck, ExecutionInfo, Machine, Result};
+
+/// an event returned by [`Execution::resume`]
+#[derive(Debug, Clone)]
+pub enum Step {
+    /// the program executed a `,` operation
+    ///
+    /// the input must be provided with [`Execution::feed`] before resuming
+    NeedsInput,
+    /// the program executed a `.` operation, producing these UTF-8 encoded bytes
+    Output(Vec<u8>),
+    /// the end of the code has been reached
+    Halted(ExecutionInfo),
+}
+
+/// an in-progress program execution that never touches any input or output streams
+///
+/// created with [`Brainfuck::start`]
+pub struct Execution<'b, 'a> {
+    /// the interpreter holding the configuration of this execution
+    interp: &'b mut Brainfuck<'a>,
+    /// the state of the execution
+    machine: Machine,
+    /// whether or not the end of the code has been reached
+    halted: bool,
+}
+
+impl Execution<'_, '_> {
+    /// runs the program until it needs input, has produced output or halts
+    ///
+    /// once halted, it keeps returning [`Step::Halted`] without executing anything
+    ///
+    /// # Errors
+    /// - [`crate::Error::MaxInstructionsExceeded`]: the set instructions limit was exceeded
+    /// - [`crate::Error::TimedOut`]: the set timeout has elapsed
+    /// - [`crate::Error::InfiniteLoop`]: the program is stuck in an infinite loop, if they are being detected
+    /// - [`crate::Error::UndefinedProcedure`]: the program called a procedure that was never defined,
+    ///   in [`crate::Dialect::Pbrain`]
+    /// - [`crate::Error::CellOverflow`]: `+` or `-` would have wrapped the current cell around,
+    ///   while `wrapping` is disabled
+    /// - [`crate::Error::Cancelled`]: the set `cancel_token` was set to `true`
+    pub fn resume(&mut self) -> Result<Step> {
+        if self.halted {
+            return Ok(Step::Halted(self.machine.info()));
+        }
+        if self.machine.is_awaiting_input() {
+            return Ok(Step::NeedsInput);
+        }
+
+        loop {
+            match self.interp.advance(&mut self.machine)? {
+                Action::Continue => (),
+                Action::Output(_) if self.interp.discard_output => (),
+                Action::Output(chr) => {
+                    let mut buf = [0; 4];
+                    let bytes = chr.encode_utf8(&mut buf).as_bytes();
+                    self.interp.notify_output(bytes);
+
+                    return Ok(Step::Output(bytes.to_vec()));
+                },
+                Action::Input => return Ok(Step::NeedsInput),
+                Action::Halt => {
+                    self.halted = true;
+                    return Ok(Step::Halted(self.machine.info()));
+                },
+            }
+        }
+    }
+
+    /// provides the input byte for a pending `,` operation
+    ///
+    /// [`None`] signifies EOF, in which case the interpreter's `eof` behavior is followed;
+    /// this does nothing if the program is not waiting on input
+    pub fn feed(&mut self, byte: Option<u8>) {
+        if self.machine.is_awaiting_input() {
+            let value = self.interp.resolve_input(byte.map(u32::from), &self.machine);
+            self.machine.write_input(value);
+        }
+    }
+
+    /// returns whether or not the program is waiting on [`Execution::feed`]
+    #[must_use]
+    pub const fn needs_input(&self) -> bool {
+        self.machine.is_awaiting_input()
+    }
+
+    /// returns an [`ExecutionInfo`] snapshot of the execution thus far
+    #[must_use]
+    pub fn info(&self) -> ExecutionInfo {
+        self.machine.info()
+    }
+}
+
+impl<'a> Brainfuck<'a> {
+    /// starts a sans-IO execution of the provided brainfuck code
+    ///
+    /// unlike [`Brainfuck::execute`], the configured input and output streams are never touched;
+    /// instead [`Execution::resume`] hands back a [`Step`] whenever the program wants to read or write,
+    /// and the caller feeds input back in with [`Execution::feed`]
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{Brainfuck, Result, Step};
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut interp = Brainfuck::new(",+.");
+    ///     let mut execution = interp.start()?;
+    ///
+    ///     loop {
+    ///         match execution.resume()? {
+    ///             Step::NeedsInput => execution.feed(Some(b'a')),
+    ///             Step::Output(bytes) => assert_eq!(bytes, b"b"),
+    ///             Step::Halted(_) => break,
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// - [`crate::Error::ZeroMemorySize`] and [`crate::Error::ZeroMaxCellValue`]: the configuration is invalid, see [`crate::Brainfuck::validate`]
+    /// - [`crate::Error::MismatchedBrackets`]: the code contains an unmatched `[` or a stray `]`
+    /// - [`crate::Error::UnexpectedCharacter`]: the code contains a character that is neither an instruction
+    ///   nor whitespace, while `strict_syntax` is enabled
+    pub fn start(&mut self) -> Result<Execution<'_, 'a>> {
+        let machine = self.begin()?;
+
+        Ok(Execution {
+            interp: self,
+            machine,
+            halted: false,
+        })
+    }
+}
@@ -0,0 +1,240 @@
+//! module containing [`Scheduler`], interleaving the executions of several programs on a single thread
+
+use std::collections::VecDeque;
+use crate::{Action, Brainfuck, Error, ExecutionInfo, Machine, Result};
+
+/// the state of a program of a [`Scheduler`]
+#[derive(Debug)]
+pub enum ProgramState {
+    /// the program can keep executing on the next round
+    Runnable,
+    /// the program executed a `,` operation, and waits for input pushed with [`Scheduler::push_input`]
+    /// or for its input to be closed with [`Scheduler::close_input`]
+    WaitingForInput,
+    /// the end of the code has been reached
+    Halted(ExecutionInfo),
+    /// the execution failed, such as by exceeding the instructions limit of the program
+    Failed(Error),
+}
+
+impl ProgramState {
+    /// returns whether or not the program halted or failed, never executing again
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        matches!(self, Self::Halted(_) | Self::Failed(_))
+    }
+}
+
+/// a program of a [`Scheduler`], along with the state of its execution
+struct Program<'a> {
+    /// the interpreter holding the configuration of this execution
+    interp: Brainfuck<'a>,
+    /// the state of the execution
+    machine: Machine,
+    /// the bytes pushed for `,` that have not been read yet
+    input: VecDeque<u8>,
+    /// whether or not no more input will be pushed
+    input_closed: bool,
+    /// the bytes produced by `.` that have not been taken yet
+    output: Vec<u8>,
+    /// the state of the program
+    state: ProgramState,
+}
+
+impl Program<'_> {
+    /// executes up to `budget` instructions, returning whether or not the program made any progress
+    fn run_slice(&mut self, budget: usize) -> bool {
+        if self.state.is_finished() {
+            return false;
+        }
+        let start = self.machine.instructions;
+        let mut progressed = false;
+
+        loop {
+            if self.machine.is_awaiting_input() {
+                let byte = self.input.pop_front();
+                if byte.is_none() && !self.input_closed {
+                    self.state = ProgramState::WaitingForInput;
+                    return progressed;
+                }
+                let value = self.interp.resolve_input(byte.map(u32::from), &self.machine);
+                self.machine.write_input(value);
+                progressed = true;
+            }
+            self.state = ProgramState::Runnable;
+
+            if self.machine.instructions - start >= budget {
+                return progressed;
+            }
+            progressed = true;
+
+            match self.interp.advance(&mut self.machine) {
+                Ok(Action::Continue | Action::Input) => (),
+                Ok(Action::Output(_)) if self.interp.discard_output => (),
+                Ok(Action::Output(chr)) => {
+                    let mut buf = [0; 4];
+                    let bytes = chr.encode_utf8(&mut buf).as_bytes();
+                    self.interp.notify_output(bytes);
+
+                    self.output.extend_from_slice(bytes);
+                },
+                Ok(Action::Halt) => {
+                    self.state = ProgramState::Halted(self.machine.info());
+                    return true;
+                },
+                Err(err) => {
+                    self.state = ProgramState::Failed(err);
+                    return true;
+                },
+            }
+        }
+    }
+}
+
+/// a cooperative scheduler interleaving the executions of several programs round-robin on a single thread,
+/// every program executing up to a slice of instructions per round
+///
+/// the configured input and output streams of the programs are not used:
+/// the input of every program is pushed with [`Scheduler::push_input`],
+/// while its output is buffered until it is taken with [`Scheduler::take_output`];
+/// the limits of every program, such as its instructions limit, still apply to it
+///
+/// ```rust
+/// use brainfuck_exe::{Brainfuck, ProgramState, Scheduler};
+///
+/// let mut scheduler = Scheduler::new(100);
+/// let writer = scheduler.spawn(Brainfuck::new("++++++++[>++++++++<-]>+.+.+."))?;
+/// let echo = scheduler.spawn(Brainfuck::new(",[.,]"))?;
+///
+/// scheduler.run();
+/// assert!(matches!(scheduler.state(echo), ProgramState::WaitingForInput));
+///
+/// // the output of a program can be fed into the input of another one
+/// let output = scheduler.take_output(writer);
+/// scheduler.push_input(echo, output);
+/// scheduler.close_input(echo);
+/// scheduler.run();
+///
+/// assert!(matches!(scheduler.state(echo), ProgramState::Halted(_)));
+/// assert_eq!(scheduler.output(echo), b"ABC");
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+pub struct Scheduler<'a> {
+    /// the programs, indexed by the id returned by [`Scheduler::spawn`]
+    programs: Vec<Program<'a>>,
+    /// the max amount of instructions every program executes per round
+    slice: usize,
+}
+
+impl<'a> Scheduler<'a> {
+    /// creates a scheduler without any program, every program executing up to `slice` instructions per round,
+    /// at least `1`
+    #[must_use]
+    pub fn new(slice: usize) -> Self {
+        Self {
+            programs: Vec::new(),
+            slice: slice.max(1),
+        }
+    }
+
+    /// adds the program of the provided interpreter, returning its id
+    ///
+    /// # Errors
+    /// the same as [`Brainfuck::start`], the program being compiled right away
+    pub fn spawn(&mut self, mut interp: Brainfuck<'a>) -> Result<usize> {
+        let machine = interp.begin()?;
+
+        self.programs.push(Program {
+            interp,
+            machine,
+            input: VecDeque::new(),
+            input_closed: false,
+            output: Vec::new(),
+            state: ProgramState::Runnable,
+        });
+        Ok(self.programs.len() - 1)
+    }
+
+    /// returns the amount of programs, including the finished ones
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.programs.len()
+    }
+
+    /// returns whether or not no program was spawned
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty()
+    }
+
+    /// queues the provided bytes for `,` of the program to read
+    ///
+    /// # Panics
+    /// the id is not one returned by [`Scheduler::spawn`]
+    pub fn push_input<B: AsRef<[u8]>>(&mut self, id: usize, input: B) {
+        self.programs[id].input.extend(input.as_ref());
+    }
+
+    /// marks the end of the input of the program, making `,` reach EOF once it read the queued input
+    ///
+    /// # Panics
+    /// the id is not one returned by [`Scheduler::spawn`]
+    pub fn close_input(&mut self, id: usize) {
+        self.programs[id].input_closed = true;
+    }
+
+    /// returns the state of the program
+    ///
+    /// # Panics
+    /// the id is not one returned by [`Scheduler::spawn`]
+    #[must_use]
+    pub fn state(&self, id: usize) -> &ProgramState {
+        &self.programs[id].state
+    }
+
+    /// returns the output of the program that has not been taken yet
+    ///
+    /// # Panics
+    /// the id is not one returned by [`Scheduler::spawn`]
+    #[must_use]
+    pub fn output(&self, id: usize) -> &[u8] {
+        &self.programs[id].output
+    }
+
+    /// takes the output of the program that has not been taken yet
+    ///
+    /// # Panics
+    /// the id is not one returned by [`Scheduler::spawn`]
+    pub fn take_output(&mut self, id: usize) -> Vec<u8> {
+        std::mem::take(&mut self.programs[id].output)
+    }
+
+    /// returns an [`ExecutionInfo`] snapshot of the execution of the program thus far
+    ///
+    /// # Panics
+    /// the id is not one returned by [`Scheduler::spawn`]
+    #[must_use]
+    pub fn info(&self, id: usize) -> ExecutionInfo {
+        self.programs[id].machine.info()
+    }
+
+    /// gives every program that is not finished one slice of instructions, in the order they were spawned in,
+    /// returning whether or not any of them made progress
+    pub fn run_round(&mut self) -> bool {
+        let mut progressed = false;
+        // every program runs its slice, rather than stopping at the first one that made progress
+        for program in &mut self.programs {
+            progressed |= program.run_slice(self.slice);
+        }
+        progressed
+    }
+
+    /// runs rounds until every program is either finished or waiting for input, returning the amount of rounds
+    pub fn run(&mut self) -> usize {
+        let mut rounds = 0;
+        while self.run_round() {
+            rounds += 1;
+        }
+        rounds
+    }
+}
@@ -0,0 +1,216 @@
+//! module containing [`Session`], the engine of the REPL keeping the memory array across the lines evaluated
+
+use std::collections::VecDeque;
+use crate::{Brainfuck, Error, ExecutionInfo, Result};
+
+/// the meta-commands understood by [`Session::eval`], returned by `:help`
+const HELP: &str = "\
+:cells       prints the memory array, with the current cell in brackets
+:ptr         prints the pointer
+:reset       resets the memory array and the pointer
+:load FILE   executes the program of the provided file
+:help        prints this help
+:quit        exits the REPL";
+
+/// the outcome of a line evaluated by [`Session::eval`]
+#[derive(Debug, Clone)]
+pub enum EvalOutcome {
+    /// the line was empty
+    Empty,
+    /// the line was code, or a `:load` of a file, that was executed
+    Executed {
+        /// everything the code wrote with the `.` operation
+        output: Vec<u8>,
+        /// information on the execution, its final memory array and pointer being kept by the session
+        info: ExecutionInfo,
+    },
+    /// the line was a meta-command printing the provided text, such as `:cells`, `:ptr` or `:help`
+    Printed(String),
+    /// the line was `:reset`, which reset the memory array and the pointer
+    Reset,
+    /// the line was `:quit` or `:q`, which the host is expected to end the session on
+    Quit,
+}
+
+/// an interactive session executing every line evaluated against the memory array and pointer
+/// left by the last successful execution, the engine of the `repl` subcommand
+///
+/// every line is either brainfuck code or one of the meta-commands of the REPL, see [`Self::eval`];
+/// by default, `,` reads from the input queued with [`Self::push_input`], reaching EOF once it runs out
+///
+/// ```rust
+/// use brainfuck_exe::{Brainfuck, EvalOutcome, Session};
+///
+/// let mut session = Session::new(Brainfuck::new("").with_mem_size(4));
+///
+/// session.eval("+++>++")?;
+/// session.push_input("A");
+/// let EvalOutcome::Executed { output, .. } = session.eval("[-<+>]<,.")? else {
+///     unreachable!();
+/// };
+///
+/// assert_eq!(output, b"A");
+/// assert!(matches!(session.eval(":cells")?, EvalOutcome::Printed(cells) if cells == "[65] 0 0 0"));
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+pub struct Session<'a> {
+    /// the interpreter whose configuration every line is executed with
+    config: Brainfuck<'a>,
+    /// the memory array left by the last successful execution
+    cells: Vec<u32>,
+    /// the pointer left by the last successful execution
+    pointer: usize,
+    /// the input queued for `,`, or [`None`] if it reads from [`std::io::stdin`]
+    input: Option<VecDeque<u8>>,
+}
+
+impl Default for Session<'_> {
+    fn default() -> Self {
+        Self::new(Brainfuck::new(""))
+    }
+}
+
+impl<'a> Session<'a> {
+    /// creates a new session executing every line with the configuration of the provided interpreter,
+    /// such as its memory size, max cell value and instructions limit
+    ///
+    /// its code, streams and callbacks such as hooks are ignored, see [`Brainfuck::clone_config`],
+    /// except for its generator of the `?` operation and its custom instructions, which are kept across lines
+    #[must_use]
+    pub fn new(config: Brainfuck<'a>) -> Self {
+        let mut session = Self {
+            config,
+            cells: Vec::new(),
+            pointer: 0,
+            input: Some(VecDeque::new()),
+        };
+        session.reset();
+        session
+    }
+
+    /// builder method to indicate whether or not `,` reads from [`std::io::stdin`] instead of the queued input
+    #[must_use]
+    pub fn with_stdin(mut self, stdin: bool) -> Self {
+        self.input = (!stdin).then(VecDeque::new);
+        self
+    }
+
+    /// queues the provided bytes for `,` to read, across as many lines as it takes,
+    /// doing nothing if `,` reads from [`std::io::stdin`]
+    pub fn push_input<B: AsRef<[u8]>>(&mut self, input: B) {
+        if let Some(ref mut queue) = self.input {
+            queue.extend(input.as_ref());
+        }
+    }
+
+    /// returns the interpreter whose configuration every line is executed with
+    #[must_use]
+    pub const fn config(&self) -> &Brainfuck<'a> {
+        &self.config
+    }
+
+    /// returns a mutable reference to the interpreter whose configuration every line is executed with,
+    /// the changes applying from the next line on
+    pub fn config_mut(&mut self) -> &mut Brainfuck<'a> {
+        &mut self.config
+    }
+
+    /// returns the memory array left by the last successful execution
+    #[must_use]
+    pub fn cells(&self) -> &[u32] {
+        &self.cells
+    }
+
+    /// returns the pointer left by the last successful execution
+    #[must_use]
+    pub const fn pointer(&self) -> usize {
+        self.pointer
+    }
+
+    /// resets the memory array and the pointer
+    pub fn reset(&mut self) {
+        self.cells = vec![0; self.config.memory_size.unwrap_or(1)];
+        self.pointer = 0;
+    }
+
+    /// formats the memory array, with the current cell in brackets
+    fn format_cells(&self) -> String {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(idx, cell)| if idx == self.pointer {
+                format!("[{cell}]")
+            } else {
+                cell.to_string()
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// executes the provided code against the memory array and pointer,
+    /// leaving them unchanged if the execution fails
+    ///
+    /// # Errors
+    /// any error [`Brainfuck::execute`] can return, the output written before it being discarded
+    pub fn execute<S: AsRef<str>>(&mut self, code: S) -> Result<EvalOutcome> {
+        let mut output = Vec::new();
+        let info = {
+            let mut interp = self.config
+                .clone_config()
+                .with_initial_state(self.cells.clone(), self.pointer)
+                .with_output_ref(&mut output);
+            interp.code = code.as_ref().to_string();
+
+            if let Some(ref mut queue) = self.input {
+                interp = interp.with_input_ref(queue);
+            }
+            // the generator is borrowed rather than copied, so that `?` keeps producing new values across lines
+            if let Some(ref mut random) = self.config.random {
+                interp = interp.with_rng(random);
+            }
+            for (opcode, op) in &mut self.config.custom_ops {
+                interp = interp.with_custom_op(*opcode, op);
+            }
+            interp.execute()?
+        };
+        self.cells.clone_from(&info.cells);
+        self.pointer = info.pointer;
+
+        Ok(EvalOutcome::Executed { output, info })
+    }
+
+    /// evaluates the provided line, either executing it as code against the memory array and pointer,
+    /// or running the meta-command it is:
+    /// - `:cells` prints the memory array, with the current cell in brackets
+    /// - `:ptr` prints the pointer
+    /// - `:reset` resets the memory array and the pointer
+    /// - `:load FILE` executes the program of the provided file, see [`Brainfuck::from_file`]
+    /// - `:help` prints the meta-commands
+    /// - `:quit` or `:q` asks the host to end the session
+    ///
+    /// # Errors
+    /// - [`Error::UnknownCommand`]: the line starts with `:` but is none of the meta-commands
+    /// - [`Error::FileReadError`]: the file of `:load` could not be read
+    /// - any error [`Brainfuck::execute`] can return, leaving the memory array and pointer unchanged
+    pub fn eval(&mut self, line: &str) -> Result<EvalOutcome> {
+        let line = line.trim();
+
+        Ok(match line.split_once(char::is_whitespace).unwrap_or((line, "")) {
+            ("", _) => EvalOutcome::Empty,
+            (":quit" | ":q", _) => EvalOutcome::Quit,
+            (":help", _) => EvalOutcome::Printed(String::from(HELP)),
+            (":cells", _) => EvalOutcome::Printed(self.format_cells()),
+            (":ptr", _) => EvalOutcome::Printed(self.pointer.to_string()),
+            (":reset", _) => {
+                self.reset();
+                EvalOutcome::Reset
+            },
+            (":load", path) => {
+                let code = Brainfuck::from_file(path.trim())?.code;
+                return self.execute(code);
+            },
+            (command, _) if command.starts_with(':') => return Err(Error::UnknownCommand(command.to_string())),
+            _ => return self.execute(line),
+        })
+    }
+}
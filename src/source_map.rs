@@ -0,0 +1,94 @@
+//! module containing [`SourceMap`], mapping code written back from a [`Program`] to the code it was parsed from
+
+use crate::{
+    ast::{Node, NodeKind, Program},
+    Position,
+    Span,
+};
+
+/// maps every character of code written back from a [`Program`], such as an optimized one,
+/// to the range of the original code it stems from
+///
+/// see [`Program::to_code_with_map`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SourceMap {
+    /// the span of every character of the written code
+    spans: Vec<Span>,
+}
+
+impl SourceMap {
+    /// returns the range of the original code the character at the provided index stems from, if any
+    #[must_use]
+    pub fn span(&self, idx: usize) -> Option<Span> {
+        self.spans
+            .get(idx)
+            .copied()
+    }
+
+    /// returns the amount of characters mapped
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// returns whether or not no characters are mapped
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// returns an iterator over the span of every character, in order
+    pub fn iter(&self) -> impl Iterator<Item = Span> + '_ {
+        self.spans
+            .iter()
+            .copied()
+    }
+
+    /// writes the provided nodes into `code`, mapping every character written
+    fn write(&mut self, nodes: &[Node], code: &mut String) {
+        for node in nodes {
+            if let NodeKind::Loop(ref body) = node.kind {
+                let Span { start, end } = node.span;
+
+                // the brackets only span themselves rather than the whole loop
+                code.push('[');
+                self.spans.push(Span { start, end: Position { offset: start.offset + 1, column: start.column + 1, ..start } });
+
+                self.write(body, code);
+
+                code.push(']');
+                self.spans.push(Span { start: Position { offset: end.offset - 1, column: end.column - 1, ..end }, end });
+            } else {
+                let written = node.to_string();
+
+                self.spans.extend(
+                    std::iter::repeat_n(node.span, written.len())
+                );
+                *code += &written;
+            }
+        }
+    }
+}
+
+impl Program {
+    /// writes the program back as code like its [`std::fmt::Display`] implementation,
+    /// along with the map from every character of that code to the range of the code the program was parsed from
+    ///
+    /// ```rust
+    /// use brainfuck_exe::ast::Program;
+    ///
+    /// let (code, map) = Program::parse("++ add two\n[>+<-]")?.to_code_with_map();
+    ///
+    /// assert_eq!(code, "++[>+<-]");
+    /// assert_eq!(map.span(2).unwrap().start.line, 2);
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn to_code_with_map(&self) -> (String, SourceMap) {
+        let mut code = String::new();
+        let mut map = SourceMap::default();
+        map.write(&self.nodes, &mut code);
+
+        (code, map)
+    }
+}
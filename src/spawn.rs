@@ -0,0 +1,174 @@
+//! module containing [`ExecutionHandle`], used to control a program running on a background thread
+//!
+//! see [`Brainfuck::spawn`] for more information
+
+use std::{
+    io::{self, Write},
+    sync::{
+        Arc, Condvar, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread::{self, JoinHandle},
+};
+use crate::{Brainfuck, ExecutionInfo, Result};
+
+/// the state shared between an [`ExecutionHandle`] and its running interpreter
+#[derive(Debug, Default)]
+pub(crate) struct Control {
+    /// whether or not the execution is currently paused
+    paused: AtomicBool,
+    /// whether or not the execution has been killed
+    killed: AtomicBool,
+    /// the lock that is held while modifying or waiting on `paused`
+    lock: Mutex<()>,
+    /// notified whenever the execution is resumed or killed
+    resumed: Condvar,
+}
+
+impl Control {
+    /// blocks the current thread for as long as the execution is paused
+    pub fn wait_while_paused(&self) {
+        if !self.paused.load(Ordering::Acquire) {
+            return;
+        }
+        let mut guard = self.lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        while self.paused.load(Ordering::Acquire)
+            && !self.is_killed()
+        {
+            guard = self.resumed
+                .wait(guard)
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+        }
+    }
+
+    /// returns whether or not the execution has been killed
+    pub fn is_killed(&self) -> bool {
+        self.killed.load(Ordering::Acquire)
+    }
+
+    /// sets `paused` while holding the lock, waking up the execution
+    fn set_paused(&self, paused: bool) {
+        let _guard = self.lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        self.paused.store(paused, Ordering::Release);
+        self.resumed.notify_all();
+    }
+}
+
+/// an output stream that sends every write through a channel as a chunk of bytes
+struct ChannelWriter(Sender<Vec<u8>>);
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // the receiving end being dropped just means nobody is interested in the output anymore
+        self.0.send(buf.to_vec()).ok();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// a handle to a brainfuck program running on a background thread
+///
+/// created with [`Brainfuck::spawn`]
+#[derive(Debug)]
+pub struct ExecutionHandle {
+    /// the state shared with the running interpreter
+    control: Arc<Control>,
+    /// receives the chunks of bytes written by the `.` operation
+    output: Receiver<Vec<u8>>,
+    /// the handle to the background thread
+    thread: JoinHandle<Result<ExecutionInfo>>,
+}
+
+impl ExecutionHandle {
+    /// pauses the execution before its next step, until [`Self::resume`] is called
+    pub fn pause(&self) {
+        self.control.set_paused(true);
+    }
+
+    /// resumes a previously paused execution
+    pub fn resume(&self) {
+        self.control.set_paused(false);
+    }
+
+    /// stops the execution before its next step,
+    /// making [`Self::join`] return [`crate::Error::Cancelled`]
+    pub fn kill(&self) {
+        self.control.killed.store(true, Ordering::Release);
+        self.control.set_paused(false);
+    }
+
+    /// returns whether or not the execution is currently paused
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.control.paused.load(Ordering::Acquire)
+    }
+
+    /// returns whether or not the background thread has finished
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// returns the channel that receives the chunks of bytes written by the `.` operation
+    #[must_use]
+    pub const fn output(&self) -> &Receiver<Vec<u8>> {
+        &self.output
+    }
+
+    /// waits for the execution to finish, returning its result
+    ///
+    /// # Errors
+    /// propagates any error returned by [`Brainfuck::execute`]
+    ///
+    /// # Panics
+    /// resumes the panic if the background thread panicked
+    pub fn join(self) -> Result<ExecutionInfo> {
+        self.thread
+            .join()
+            .unwrap_or_else(|err| std::panic::resume_unwind(err))
+    }
+}
+
+impl Brainfuck<'static> {
+    /// moves the interpreter onto a background thread and executes its code there,
+    /// returning an [`ExecutionHandle`] to control it
+    ///
+    /// the `.` operation's output is redirected into [`ExecutionHandle::output`]
+    /// instead of the configured output stream
+    ///
+    /// ```rust
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// let handle = Brainfuck::new("++++++++[>++++++++<-]>+.").spawn();
+    /// let info = handle.join()?;
+    /// assert_eq!(info.cells[1], 65);
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    #[must_use]
+    pub fn spawn(self) -> ExecutionHandle {
+        let control = Arc::new(Control::default());
+        let (sender, output) = mpsc::channel();
+
+        let thread = {
+            let control = Arc::clone(&control);
+
+            thread::spawn(move || {
+                let mut interp = self.with_output(ChannelWriter(sender));
+                interp.control = Some(control);
+                interp.execute()
+            })
+        };
+
+        ExecutionHandle { control, output, thread }
+    }
+}
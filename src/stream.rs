@@ -0,0 +1,142 @@
+//! module containing [`BrainfuckStream`], exposing a running program as a [`Read`] / [`Write`] stream transform
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+};
+use crate::{Action, Brainfuck, ExecutionInfo, Machine, Result};
+
+/// a running brainfuck program exposed as a stream transform
+///
+/// bytes written into it with [`Write`] become the input of the `,` operation,
+/// while the bytes produced by the `.` operation can be read back out with [`Read`];
+/// the program only executes while it is being read from
+///
+/// when the program needs input that has not been written yet, reading returns
+/// [`io::ErrorKind::WouldBlock`] unless [`BrainfuckStream::close_input`] was called,
+/// in which case the input has reached EOF, and `,` follows the interpreter's [`crate::EofBehavior`]
+///
+/// created with [`Brainfuck::into_stream`]
+///
+/// ```rust
+/// use std::io::{Read, Write};
+/// use brainfuck_exe::Brainfuck;
+///
+/// let mut stream = Brainfuck::new(",[+.,]")
+///     .into_stream()
+///     .unwrap();
+///
+/// stream.write_all(b"HAL").unwrap();
+/// stream.close_input();
+///
+/// let mut output = String::new();
+/// stream.read_to_string(&mut output).unwrap();
+/// assert_eq!(output, "IBM");
+/// ```
+pub struct BrainfuckStream {
+    /// the interpreter holding the configuration of this execution
+    interp: Brainfuck<'static>,
+    /// the state of the execution
+    machine: Machine,
+    /// the bytes written into the stream that have not been read by `,` yet
+    input: VecDeque<u8>,
+    /// the bytes produced by `.` that have not been read out of the stream yet
+    output: VecDeque<u8>,
+    /// whether or not no more input will be written into the stream
+    input_closed: bool,
+    /// whether or not the end of the code has been reached
+    halted: bool,
+}
+
+impl BrainfuckStream {
+    /// marks the end of the input, making any further `,` operations follow the [`crate::EofBehavior`] of the interpreter
+    /// once the input written before is read
+    pub fn close_input(&mut self) {
+        self.input_closed = true;
+    }
+
+    /// returns whether or not the end of the code has been reached
+    #[must_use]
+    pub const fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// returns an [`ExecutionInfo`] snapshot of the execution thus far
+    #[must_use]
+    pub fn info(&self) -> ExecutionInfo {
+        self.machine.info()
+    }
+
+    /// executes the program until it produces output, halts, or runs out of input
+    fn run(&mut self) -> io::Result<()> {
+        while self.output.is_empty() && !self.halted {
+            if self.machine.is_awaiting_input() {
+                let byte = self.input.pop_front();
+                if byte.is_none() && !self.input_closed {
+                    return Err(io::ErrorKind::WouldBlock.into());
+                }
+                let value = self.interp.resolve_input(byte.map(u32::from), &self.machine);
+                self.machine.write_input(value);
+            }
+
+            let action = self.interp
+                .advance(&mut self.machine)
+                .map_err(io::Error::other)?;
+
+            match action {
+                Action::Continue | Action::Input => (),
+                Action::Output(_) if self.interp.discard_output => (),
+                Action::Output(chr) => {
+                    let mut buf = [0; 4];
+                    let bytes = chr.encode_utf8(&mut buf).as_bytes();
+                    self.interp.notify_output(bytes);
+
+                    self.output.extend(bytes);
+                },
+                Action::Halt => self.halted = true,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Read for BrainfuckStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.run()?;
+        self.output.read(buf)
+    }
+}
+
+impl Write for BrainfuckStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.input.extend(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Brainfuck<'static> {
+    /// consumes the interpreter, exposing its program as a [`BrainfuckStream`]
+    ///
+    /// the configured input and output streams are not used
+    ///
+    /// # Errors
+    /// - [`crate::Error::MismatchedBrackets`]: the code contains an unmatched `[` or a stray `]`
+    /// - [`crate::Error::UnexpectedCharacter`]: the code contains a character that is neither an instruction
+    ///   nor whitespace, while `strict_syntax` is enabled
+    pub fn into_stream(mut self) -> Result<BrainfuckStream> {
+        let machine = self.begin()?;
+
+        Ok(BrainfuckStream {
+            interp: self,
+            machine,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            input_closed: false,
+            halted: false,
+        })
+    }
+}
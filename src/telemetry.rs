@@ -0,0 +1,72 @@
+//! module publishing the interpreter's metrics through the [`metrics`](https://docs.rs/metrics) facade
+//!
+//! every function is a no-op unless the `metrics` feature is enabled
+
+
+#![cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+
+use crate::{machine::Machine, Error};
+
+/// returns the label of the kind of the provided error
+#[cfg(feature = "metrics")]
+const fn error_kind(err: &Error) -> &'static str {
+    match err {
+        Error::ZeroMemorySize => "zero_memory_size",
+        Error::InitialPointerOutOfBounds { .. } => "initial_pointer_out_of_bounds",
+        Error::ZeroMaxCellValue => "zero_max_cell_value",
+        Error::MismatchedBrackets { .. } => "mismatched_brackets",
+        Error::UnexpectedCharacter { .. } => "unexpected_character",
+        Error::InvalidToken { .. } => "invalid_token",
+        Error::InvalidTokenTable(_) => "invalid_token_table",
+        Error::FileReadError(_) => "file_read",
+        Error::FileWriteError(_) => "file_write",
+        Error::IoError(_) => "io",
+        Error::OutputError { .. } => "output",
+        Error::MaxInstructionsExceeded { .. } => "max_instructions_exceeded",
+        Error::TimedOut { .. } => "timed_out",
+        Error::MemoryLimitExceeded { .. } => "memory_limit_exceeded",
+        Error::OutputLimitExceeded { .. } => "output_limit_exceeded",
+        Error::LoopDepthExceeded { .. } => "loop_depth_exceeded",
+        Error::LoopIterationsExceeded { .. } => "loop_iterations_exceeded",
+        Error::InfiniteLoop(_) => "infinite_loop",
+        Error::MissingCallback { .. } => "missing_callback",
+        Error::UndefinedProcedure { .. } => "undefined_procedure",
+        Error::CellOverflow(_) => "cell_overflow",
+        Error::Cancelled(_) => "cancelled",
+        Error::UnknownCommand(_) => "unknown_command",
+    }
+}
+
+/// records the bytes read by a `,` operation
+#[inline]
+pub(crate) fn record_input(amount: usize) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!("brainfuck_input_bytes_total").increment(amount as u64);
+}
+
+/// records the bytes written by a `.` operation
+#[inline]
+pub(crate) fn record_output(amount: usize) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!("brainfuck_output_bytes_total").increment(amount as u64);
+}
+
+/// records an execution that reached the end of the code
+pub(crate) fn record_completion(machine: &Machine) {
+    #[cfg(feature = "metrics")]
+    {
+        metrics::counter!("brainfuck_instructions_total").increment(machine.instructions as u64);
+        metrics::counter!("brainfuck_runs_completed_total").increment(1);
+        #[allow(clippy::cast_precision_loss)]
+        metrics::gauge!("brainfuck_memory_cells").set(machine.cells.len() as f64);
+    }
+}
+
+/// records a failed execution, after having executed the provided amount of instructions
+pub(crate) fn record_failure(err: &Error, instructions: usize) {
+    #[cfg(feature = "metrics")]
+    {
+        metrics::counter!("brainfuck_instructions_total").increment(instructions as u64);
+        metrics::counter!("brainfuck_runs_failed_total", "kind" => error_kind(err)).increment(1);
+    }
+}
@@ -0,0 +1,99 @@
+//! module containing assertions on the output of a program for the tests of downstream crates,
+//! see [`Brainfuck::assert_output`]
+//!
+//! requires the `test-utils` feature
+
+use std::io::Cursor;
+use crate::{Brainfuck, ExecutionInfo};
+
+/// returns the lines of the expected and actual outputs that differ, in the format of a unified diff,
+/// or [`None`] if they are equal
+fn diff(expected: &[u8], actual: &[u8]) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    let offset = expected
+        .iter()
+        .zip(actual)
+        .position(|(expected, actual)| expected != actual)
+        .unwrap_or_else(|| expected.len().min(actual.len()));
+
+    let expected = String::from_utf8_lossy(expected);
+    let actual = String::from_utf8_lossy(actual);
+    let expected_lines = expected.split('\n').collect::<Vec<&str>>();
+    let actual_lines = actual.split('\n').collect::<Vec<&str>>();
+
+    let mut diff = format!("the output differs from the expected output, starting at byte offset {offset}\n--- expected\n+++ actual\n");
+    for idx in 0..expected_lines.len().max(actual_lines.len()) {
+        let (expected, actual) = (expected_lines.get(idx), actual_lines.get(idx));
+
+        if expected != actual {
+            if let Some(line) = expected {
+                diff += &format!("-{:>4} | {line:?}\n", idx + 1);
+            }
+            if let Some(line) = actual {
+                diff += &format!("+{:>4} | {line:?}\n", idx + 1);
+            }
+        }
+    }
+    Some(diff)
+}
+
+impl Brainfuck<'_> {
+    /// executes a copy of this interpreter's configuration (see [`Brainfuck::clone_config`]) with an empty input,
+    /// asserting that it writes exactly the expected output, see [`Brainfuck::assert_output_with_input`]
+    ///
+    /// # Panics
+    /// the program fails, or its output differs from the expected output
+    ///
+    /// ```rust
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// Brainfuck::new("++++++++[>++++++++<-]>+.+.").assert_output("AB");
+    /// ```
+    #[track_caller]
+    pub fn assert_output<E: AsRef<[u8]>>(&self, expected: E) -> ExecutionInfo {
+        self.assert_output_with_input("", expected)
+    }
+
+    /// executes a copy of this interpreter's configuration (see [`Brainfuck::clone_config`]) with the provided input,
+    /// asserting that it writes exactly the expected output
+    ///
+    /// returns the execution information for any further assertion on the final state
+    ///
+    /// # Panics
+    /// the program fails, or its output differs from the expected output,
+    /// the panic message then being a diff of the lines that differ
+    ///
+    /// ```rust
+    /// use brainfuck_exe::Brainfuck;
+    ///
+    /// let info = Brainfuck::new(",[+.,]").assert_output_with_input("HAL", "IBM");
+    /// assert_eq!(info.cells, [0]);
+    /// ```
+    #[track_caller]
+    pub fn assert_output_with_input<I, E>(&self, input: I, expected: E) -> ExecutionInfo
+    where
+        I: AsRef<[u8]>,
+        E: AsRef<[u8]>,
+    {
+        let mut output = Vec::new();
+        let result = self
+            .clone_config()
+            .with_input(Cursor::new(input.as_ref().to_vec()))
+            .with_output_ref(&mut output)
+            .execute();
+
+        let info = match result {
+            Ok(info) => info,
+            Err(err) => panic!(
+                "the program failed: {err}\noutput written before failing: {:?}",
+                String::from_utf8_lossy(&output),
+            ),
+        };
+        if let Some(diff) = diff(expected.as_ref(), &output) {
+            panic!("{diff}");
+        }
+        info
+    }
+}
@@ -0,0 +1,228 @@
+//! module containing a naive reference interpreter to cross-check the interpreter against,
+//! see [`Brainfuck::cross_check`]
+//!
+//! requires the `testing` feature
+
+use std::io::Cursor;
+use crate::{Brainfuck, EofBehavior, Position, Result};
+
+/// the amount of instructions the reference interpreter executes at most, unless an instruction limit is configured
+pub const REFERENCE_LIMIT: usize = 100_000_000;
+
+/// the state a program finished in when executed by [`reference()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceRun {
+    /// everything the program wrote with the `.` operation
+    pub output: Vec<u8>,
+    /// the index of the character of the `.` operation that wrote each byte of the output
+    pub output_sources: Vec<usize>,
+    /// the final memory array
+    pub cells: Vec<u32>,
+    /// the final pointer
+    pub pointer: usize,
+}
+
+/// executes plain brainfuck as naively as possible under the configuration of the provided interpreter,
+/// scanning for the matching bracket on every jump, with the provided input
+///
+/// only the max value of a cell, the memory size, the EOF behavior, the fallback input and the initial state
+/// of the configuration are followed, the cells always wrapping around;
+/// returns [`None`] if the brackets are mismatched or the program executes more than `limit` instructions
+#[must_use]
+pub fn reference(interp: &Brainfuck, input: &[u8], limit: usize) -> Option<ReferenceRun> {
+    let code = interp.code.chars().collect::<Vec<char>>();
+    let max = interp.max_cell_value;
+    let (mut cells, mut pointer) = interp.initial_state
+        .clone()
+        .unwrap_or_else(|| (vec![0; interp.memory_size.unwrap_or(1)], 0));
+    cells.resize(cells.len().max(pointer + 1), 0);
+
+    let mut input = input.iter();
+    let mut output = Vec::new();
+    let mut output_sources = Vec::new();
+    let mut idx = 0;
+    let mut executed = 0;
+
+    while idx < code.len() {
+        match code[idx] {
+            '+' => cells[pointer] = if cells[pointer] >= max { 0 } else { cells[pointer] + 1 },
+            '-' => cells[pointer] = if cells[pointer] == 0 { max } else { cells[pointer] - 1 },
+            '<' => pointer = if pointer == 0 { cells.len() - 1 } else { pointer - 1 },
+            '>' => {
+                pointer += 1;
+                if interp.memory_size.is_some_and(|size| pointer >= size) {
+                    pointer = 0;
+                } else if pointer >= cells.len() {
+                    cells.push(0);
+                }
+            },
+            '.' => if let Some(chr) = char::from_u32(cells[pointer]) {
+                let mut buf = [0; 4];
+                let bytes = chr.encode_utf8(&mut buf).as_bytes();
+                output.extend_from_slice(bytes);
+                output_sources.extend(std::iter::repeat_n(idx, bytes.len()));
+            },
+            ',' => cells[pointer] = match input.next() {
+                Some(&byte) => u32::from(byte),
+                None => match interp.eof {
+                    EofBehavior::Fallback => interp.fallback_input.map_or(0, u32::from),
+                    EofBehavior::Max => max,
+                    EofBehavior::Unchanged => cells[pointer],
+                },
+            },
+            '[' if cells[pointer] == 0 => {
+                let mut depth = 0;
+                loop {
+                    match code.get(idx)? {
+                        '[' => depth += 1,
+                        ']' => depth -= 1,
+                        _ => (),
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                    idx += 1;
+                }
+            },
+            ']' if cells[pointer] != 0 => {
+                let mut depth = 0;
+                loop {
+                    match code[idx] {
+                        ']' => depth += 1,
+                        '[' => depth -= 1,
+                        _ => (),
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                    idx = idx.checked_sub(1)?;
+                }
+            },
+            _ => {
+                idx += 1;
+                continue;
+            },
+        }
+        idx += 1;
+        executed += 1;
+
+        if executed > limit {
+            return None;
+        }
+    }
+    Some(ReferenceRun { output, output_sources, cells, pointer })
+}
+
+/// how the interpreter diverged from the reference interpreter, see [`Brainfuck::cross_check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MismatchKind {
+    /// the outputs first differ at the byte `offset`, [`None`] standing for the end of an output
+    Output {
+        /// the index of the first byte that differs
+        offset: usize,
+        /// the byte the reference interpreter wrote
+        expected: Option<u8>,
+        /// the byte the interpreter wrote
+        actual: Option<u8>,
+    },
+    /// the final memory arrays first differ at the cell `index`, ignoring their trailing zeroed cells
+    Tape {
+        /// the index of the first cell that differs
+        index: usize,
+        /// the value of the cell left by the reference interpreter
+        expected: u32,
+        /// the value of the cell left by the interpreter
+        actual: u32,
+    },
+    /// the final pointers differ
+    Pointer {
+        /// the pointer left by the reference interpreter
+        expected: usize,
+        /// the pointer left by the interpreter
+        actual: usize,
+    },
+    /// the reference interpreter did not finish within its limit, or found the brackets mismatched,
+    /// while the interpreter finished
+    Unfinished,
+}
+
+/// a divergence of the interpreter from the reference interpreter, see [`Brainfuck::cross_check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// how the interpreter diverged
+    pub kind: MismatchKind,
+    /// the position of the `.` operation the reference interpreter wrote the first differing byte with,
+    /// if the outputs differ
+    pub position: Option<Position>,
+}
+
+impl Brainfuck<'_> {
+    /// executes a copy of this interpreter's configuration (see [`Brainfuck::clone_config`]) with the provided input,
+    /// comparing its output and final state against the naive [`reference()`] interpreter
+    ///
+    /// the reference interpreter only knows of plain brainfuck, and executes at most the configured instruction limit,
+    /// or [`REFERENCE_LIMIT`] instructions; returns [`None`] if both agree
+    ///
+    /// # Errors
+    /// any error of the interpreter is propagated, as there is nothing to compare
+    ///
+    /// ```rust
+    /// use brainfuck_exe::{Brainfuck, OptimizationLevel};
+    ///
+    /// let mismatch = Brainfuck::new("++++++++[>++++++++<-]>+.[-]<,[.,]")
+    ///     .with_optimization(OptimizationLevel::Aggressive)
+    ///     .cross_check(b"xyz")?;
+    /// assert_eq!(mismatch, None);
+    /// # Ok::<(), brainfuck_exe::Error>(())
+    /// ```
+    pub fn cross_check(&self, input: &[u8]) -> Result<Option<Mismatch>> {
+        let mut output = Vec::new();
+        let info = self
+            .clone_config()
+            .with_input(Cursor::new(input.to_vec()))
+            .with_output_ref(&mut output)
+            .execute()?;
+
+        let Some(expected) = reference(self, input, self.instructions_limit.unwrap_or(REFERENCE_LIMIT)) else {
+            return Ok(Some(Mismatch { kind: MismatchKind::Unfinished, position: None }));
+        };
+
+        if let Some(offset) = (0..expected.output.len().max(output.len()))
+            .find(|&idx| expected.output.get(idx) != output.get(idx))
+        {
+            let code = self.code.chars().collect::<Vec<char>>();
+            let source = expected.output_sources
+                .get(offset)
+                .or_else(|| expected.output_sources.last());
+
+            return Ok(Some(Mismatch {
+                kind: MismatchKind::Output {
+                    offset,
+                    expected: expected.output.get(offset).copied(),
+                    actual: output.get(offset).copied(),
+                },
+                position: source.map(|&idx| Position::locate(&code, idx)),
+            }));
+        }
+
+        if let Some(index) = (0..expected.cells.len().max(info.cells.len()))
+            .find(|&idx| expected.cells.get(idx).unwrap_or(&0) != info.cells.get(idx).unwrap_or(&0))
+        {
+            return Ok(Some(Mismatch {
+                kind: MismatchKind::Tape {
+                    index,
+                    expected: expected.cells.get(index).copied().unwrap_or_default(),
+                    actual: info.cells.get(index).copied().unwrap_or_default(),
+                },
+                position: None,
+            }));
+        }
+        if expected.pointer != info.pointer {
+            return Ok(Some(Mismatch {
+                kind: MismatchKind::Pointer { expected: expected.pointer, actual: info.pointer },
+                position: None,
+            }));
+        }
+        Ok(None)
+    }
+}
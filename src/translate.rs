@@ -0,0 +1,352 @@
+//! module containing translators between brainfuck and the languages derived from it,
+//! see [`translate`] and [`TokenTable`]
+
+use crate::{machine::INSTRUCTIONS, Brainfuck, Error, Position, Result};
+
+/// the tokens of Ook!, along with the brainfuck instructions they are equivalent to
+const OOK: [(&str, char); 8] = [
+    ("Ook. Ook?", '>'),
+    ("Ook? Ook.", '<'),
+    ("Ook. Ook.", '+'),
+    ("Ook! Ook!", '-'),
+    ("Ook! Ook.", '.'),
+    ("Ook. Ook!", ','),
+    ("Ook! Ook?", '['),
+    ("Ook? Ook!", ']'),
+];
+
+/// the tokens of Alphuck, in the order of `+ - < > . , [ ]`
+const ALPHUCK: [&str; 8] = ["e", "i", "c", "a", "j", "o", "p", "s"];
+
+/// the tokens of ReverseFuck, in the order of `+ - < > . , [ ]`
+const REVERSEFUCK: [&str; 8] = ["-", "+", ">", "<", ",", ".", "]", "["];
+
+/// the tokens of Spoon, in the order of `+ - < > . , [ ]`, none of which is the prefix of another
+const SPOON: [&str; 8] = ["1", "000", "011", "010", "001010", "0010110", "00100", "0011"];
+
+/// the keys of the tokens of a [`TokenTable`] in a TOML file, in the order of `+ - < > . , [ ]`
+#[cfg(feature = "toml")]
+const TOKEN_KEYS: [&str; 8] = [
+    "increment",
+    "decrement",
+    "move_left",
+    "move_right",
+    "output",
+    "input",
+    "loop_start",
+    "loop_end",
+];
+
+/// the tokens of a trivial brainfuck substitution, such as Blub or Alphuck,
+/// where every instruction is replaced by a token of its own
+///
+/// ```rust
+/// use brainfuck_exe::{translate::TokenTable, Brainfuck};
+///
+/// let table = TokenTable::new(["a", "b", "c", "d", "e", "f", "g", "h"])?;
+///
+/// assert_eq!(table.decode("aa g b d h"), "++[->]");
+/// assert_eq!(table.encode("++[->]"), "a a g b d h");
+///
+/// let interp = Brainfuck::new("aa g b d h").with_token_table(&table);
+/// assert_eq!(interp.code, "++[->]");
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TokenTable {
+    /// the token of every instruction, in the order of `+ - < > . , [ ]`
+    tokens: [String; 8],
+}
+
+impl TokenTable {
+    /// creates a table from the tokens of `+ - < > . , [ ]`, in that order
+    ///
+    /// # Errors
+    /// - [`Error::InvalidTokenTable`]: a token is empty, or two instructions share the same token
+    pub fn new<S: Into<String>>(tokens: [S; 8]) -> Result<Self> {
+        let tokens = tokens.map(Into::into);
+
+        if tokens.iter().any(String::is_empty) {
+            return Err(Error::InvalidTokenTable(String::from("a token cannot be empty")));
+        }
+        for (idx, token) in tokens.iter().enumerate() {
+            if tokens[..idx].contains(token) {
+                return Err(Error::InvalidTokenTable(format!("the token `{token}` is used more than once")));
+            }
+        }
+        Ok(Self { tokens })
+    }
+
+    /// parses a table from a TOML file with a string key for every instruction:
+    /// `increment`, `decrement`, `move_left`, `move_right`, `output`, `input`, `loop_start` and `loop_end`
+    ///
+    /// # Errors
+    /// - [`Error::InvalidTokenTable`]: the file is not valid TOML, a key is missing or unknown,
+    ///   or the tokens are invalid as in [`TokenTable::new`]
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let table = toml
+            .parse::<toml::Table>()
+            .map_err(|err| Error::InvalidTokenTable(err.message().to_string()))?;
+
+        if let Some(key) = table
+            .keys()
+            .find(|key| !TOKEN_KEYS.contains(&key.as_str()))
+        {
+            return Err(Error::InvalidTokenTable(format!("unknown key `{key}`")));
+        }
+        let tokens = TOKEN_KEYS.map(|key| table
+            .get(key)
+            .and_then(toml::Value::as_str)
+        );
+
+        if let Some(idx) = tokens.iter().position(Option::is_none) {
+            return Err(Error::InvalidTokenTable(format!("missing the `{}` string", TOKEN_KEYS[idx])));
+        }
+        Self::new(tokens.map(Option::unwrap_or_default))
+    }
+
+    /// returns the token of the provided instruction, or [`None`] if it is not one of `+ - < > . , [ ]`
+    #[must_use]
+    pub fn token(&self, instruction: char) -> Option<&str> {
+        INSTRUCTIONS
+            .chars()
+            .position(|chr| chr == instruction)
+            .map(|idx| self.tokens[idx].as_str())
+    }
+
+    /// translates the provided code into brainfuck, matching the longest token at every position
+    /// and treating anything else as a comment
+    #[must_use]
+    pub fn decode(&self, code: &str) -> String {
+        let mut out = String::new();
+        let mut rest = code;
+
+        while let Some(chr) = rest.chars().next() {
+            let matched = INSTRUCTIONS
+                .chars()
+                .zip(&self.tokens)
+                .filter(|(_, token)| rest.starts_with(token.as_str()))
+                .max_by_key(|(_, token)| token.len());
+
+            if let Some((instruction, token)) = matched {
+                out.push(instruction);
+                rest = &rest[token.len()..];
+            } else {
+                rest = &rest[chr.len_utf8()..];
+            }
+        }
+        out
+    }
+
+    /// translates the provided brainfuck code into the tokens, separated by spaces, dropping its comments
+    #[must_use]
+    pub fn encode(&self, code: &str) -> String {
+        self.join(code, " ")
+    }
+
+    /// creates a table from tokens known to be valid
+    fn builtin(tokens: [&str; 8]) -> Self {
+        Self { tokens: tokens.map(String::from) }
+    }
+
+    /// translates the provided brainfuck code into the tokens, separated by `separator`, dropping its comments
+    fn join(&self, code: &str, separator: &str) -> String {
+        code.chars()
+            .filter_map(|chr| self.token(chr))
+            .collect::<Vec<&str>>()
+            .join(separator)
+    }
+}
+
+impl Brainfuck<'_> {
+    /// builder method to translate the code set thus far from the provided substitution dialect into brainfuck,
+    /// see [`TokenTable::decode`]
+    #[must_use]
+    pub fn with_token_table(mut self, table: &TokenTable) -> Self {
+        self.code = table.decode(&self.code);
+        self
+    }
+}
+
+/// a language that can be translated into and from brainfuck
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Language {
+    /// brainfuck itself
+    Brainfuck,
+    /// [Ook!](https://esolangs.org/wiki/Ook!), where every instruction is a pair of `Ook.`, `Ook?` or `Ook!`,
+    /// such as `Ook. Ook?` for `>`
+    Ook,
+    /// [Alphuck](https://esolangs.org/wiki/Alphuck), where the instructions are the letters `a c e i j o p s`
+    Alphuck,
+    /// [ReverseFuck](https://esolangs.org/wiki/ReverseFuck), where every instruction is swapped with its opposite,
+    /// such as `+` with `-` and `[` with `]`
+    ReverseFuck,
+    /// [Spoon](https://esolangs.org/wiki/Spoon), where every instruction is a binary code such as `010` for `>`,
+    /// written without separators
+    ///
+    /// the debug and exit codes of Spoon have no brainfuck equivalent
+    Spoon,
+}
+
+impl Language {
+    /// returns the language of a source file with the provided extension, if it is known
+    ///
+    /// `.b` and `.bf` are brainfuck, `.ook` is Ook!, and `.spoon` is Spoon
+    #[must_use]
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "b" | "bf" => Some(Self::Brainfuck),
+            "ook" => Some(Self::Ook),
+            "spoon" => Some(Self::Spoon),
+            _ => None,
+        }
+    }
+
+    /// returns the table of a language that is a trivial substitution of brainfuck
+    fn table(self) -> Option<TokenTable> {
+        match self {
+            Self::Alphuck => Some(TokenTable::builtin(ALPHUCK)),
+            Self::ReverseFuck => Some(TokenTable::builtin(REVERSEFUCK)),
+            Self::Spoon => Some(TokenTable::builtin(SPOON)),
+            Self::Brainfuck | Self::Ook => None,
+        }
+    }
+
+    /// translates the provided code of this language into brainfuck
+    ///
+    /// # Errors
+    /// - [`Error::InvalidToken`]: the code contains a token with no brainfuck equivalent
+    pub fn decode(self, code: &str) -> Result<String> {
+        match self {
+            Self::Brainfuck => Ok(code.to_string()),
+            Self::Ook => ook_to_brainfuck(code),
+            Self::Spoon => spoon_to_brainfuck(code),
+            Self::Alphuck | Self::ReverseFuck => Ok(self
+                .table()
+                .map(|table| table.decode(code))
+                .unwrap_or_default()),
+        }
+    }
+
+    /// translates the provided brainfuck code into this language, dropping its comments
+    #[must_use]
+    pub fn encode(self, code: &str) -> String {
+        match self {
+            Self::Brainfuck => code.to_string(),
+            Self::Ook => code
+                .chars()
+                .filter_map(|chr| OOK
+                    .iter()
+                    .find(|(_, instruction)| *instruction == chr)
+                    .map(|(token, _)| *token)
+                )
+                .collect::<Vec<&str>>()
+                .join(" "),
+            // the tokens of the other languages never need to be separated
+            _ => self
+                .table()
+                .map(|table| table.join(code, ""))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// translates Ook! code into brainfuck, treating anything other than `Ook.`, `Ook?` and `Ook!` as a comment
+fn ook_to_brainfuck(code: &str) -> Result<String> {
+    let chars = code
+        .chars()
+        .collect::<Vec<char>>();
+    // the character index of every `Ook` word, along with its punctuation
+    let words = chars
+        .windows(4)
+        .enumerate()
+        .filter(|(_, window)| window[..3] == ['O', 'o', 'k'] && matches!(window[3], '.' | '?' | '!'))
+        .map(|(idx, window)| (idx, window[3]));
+
+    let mut out = String::new();
+    let mut first = None;
+
+    for (idx, punctuation) in words {
+        let Some((start, previous)) = first.take() else {
+            first = Some((idx, punctuation));
+            continue;
+        };
+        let token = format!("Ook{previous} Ook{punctuation}");
+
+        match OOK.iter().find(|(ook, _)| *ook == token) {
+            Some((_, instruction)) => out.push(*instruction),
+            None => return Err(Error::InvalidToken {
+                token,
+                position: Position::locate(&chars, start),
+            }),
+        }
+    }
+    if let Some((start, punctuation)) = first {
+        return Err(Error::InvalidToken {
+            token: format!("Ook{punctuation}"),
+            position: Position::locate(&chars, start),
+        });
+    }
+    Ok(out)
+}
+
+/// translates Spoon code into brainfuck, treating anything other than `0` and `1` as a comment
+fn spoon_to_brainfuck(code: &str) -> Result<String> {
+    let chars = code
+        .chars()
+        .collect::<Vec<char>>();
+    let mut out = String::new();
+    // the bits read since the last token, along with the character index of the first of them
+    let mut token = String::new();
+    let mut start = 0;
+
+    for (idx, &chr) in chars.iter().enumerate() {
+        if !matches!(chr, '0' | '1') {
+            continue;
+        }
+        if token.is_empty() {
+            start = idx;
+        }
+        token.push(chr);
+
+        if let Some(instruction) = INSTRUCTIONS
+            .chars()
+            .zip(SPOON)
+            .find_map(|(instruction, spoon)| (spoon == token).then_some(instruction))
+        {
+            out.push(instruction);
+            token.clear();
+        } else if !SPOON.iter().any(|spoon| spoon.starts_with(token.as_str())) {
+            break;
+        }
+    }
+    if token.is_empty() {
+        Ok(out)
+    } else {
+        Err(Error::InvalidToken {
+            token,
+            position: Position::locate(&chars, start),
+        })
+    }
+}
+
+/// translates the provided code from one language into another, through brainfuck
+///
+/// # Errors
+/// - [`Error::InvalidToken`]: the code contains a token with no brainfuck equivalent
+///
+/// ```rust
+/// use brainfuck_exe::translate::{translate, Language};
+///
+/// let ook = translate("+[-].", Language::Brainfuck, Language::Ook)?;
+/// assert_eq!(ook, "Ook. Ook. Ook! Ook? Ook! Ook! Ook? Ook! Ook! Ook.");
+///
+/// assert_eq!(translate(&ook, Language::Ook, Language::Brainfuck)?, "+[-].");
+/// # Ok::<(), brainfuck_exe::Error>(())
+/// ```
+pub fn translate(code: &str, from: Language, to: Language) -> Result<String> {
+    from.decode(code)
+        .map(|code| to.encode(&code))
+}
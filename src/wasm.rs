@@ -0,0 +1,247 @@
+//! module containing the [`wasm-bindgen`](https://docs.rs/wasm-bindgen) bindings for web playgrounds, see [`run`]
+//!
+//! requires the `wasm` feature
+
+use std::{io::Cursor, ops::ControlFlow};
+use js_sys::{Date, Object, Reflect, Uint32Array};
+use wasm_bindgen::prelude::*;
+use crate::{Brainfuck, Dialect, EofBehavior, Error, ExecutionInfo, OptimizationLevel};
+
+/// the amount of instructions executed between two checks of the timeout, as reading the clock calls into JS
+const TIMEOUT_CHECK_INTERVAL: usize = 4096;
+
+/// returns the property of the provided options object, or [`None`] if it is `undefined` or `null`
+fn option(options: &JsValue, key: &str) -> Option<JsValue> {
+    if !options.is_object() {
+        return None;
+    }
+    Reflect::get(options, &JsValue::from_str(key))
+        .ok()
+        .filter(|value| !value.is_undefined() && !value.is_null())
+}
+
+/// returns the numeric property of the provided options object, failing if it is not a non-negative integer
+fn number(options: &JsValue, key: &str) -> Result<Option<f64>, JsError> {
+    option(options, key)
+        .map(|value| value
+            .as_f64()
+            .filter(|number| number.fract() == 0.0 && *number >= 0.0)
+            .ok_or_else(|| JsError::new(&format!("`{key}` must be a non-negative integer")))
+        )
+        .transpose()
+}
+
+/// returns the string property of the provided options object, failing if it is not a string
+fn string(options: &JsValue, key: &str) -> Result<Option<String>, JsError> {
+    option(options, key)
+        .map(|value| value
+            .as_string()
+            .ok_or_else(|| JsError::new(&format!("`{key}` must be a string")))
+        )
+        .transpose()
+}
+
+/// the options of [`run`], read from its options object
+#[derive(Debug, Clone, Copy, Default)]
+struct Options {
+    max_cell_value: Option<u32>,
+    memory_size: Option<usize>,
+    instructions_limit: Option<usize>,
+    /// the limit on the wall-clock time of the execution, in milliseconds
+    timeout: Option<f64>,
+    fallback_input: Option<char>,
+    wrapping: Option<bool>,
+    optimization: Option<OptimizationLevel>,
+    eof: Option<EofBehavior>,
+    dialect: Option<Dialect>,
+}
+
+impl Options {
+    /// reads the options from the provided options object, failing if one of them is invalid
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn parse(options: &JsValue) -> Result<Self, JsError> {
+        let fallback_input = string(options, "fallbackInput")?
+            .map(|chr| chr
+                .chars()
+                .next()
+                .ok_or_else(|| JsError::new("`fallbackInput` must not be empty"))
+            )
+            .transpose()?;
+        let optimization = string(options, "optimization")?
+            .map(|level| OptimizationLevel::from_name(&level)
+                .ok_or_else(|| JsError::new("`optimization` must be one of `none`, `basic` or `aggressive`"))
+            )
+            .transpose()?;
+        let eof = string(options, "eof")?
+            .map(|eof| EofBehavior::from_name(&eof)
+                .ok_or_else(|| JsError::new("`eof` must be one of `fallback`, `max` or `unchanged`"))
+            )
+            .transpose()?;
+        let dialect = string(options, "dialect")?
+            .map(|dialect| Dialect::from_name(&dialect).ok_or_else(|| JsError::new(
+                "`dialect` must be one of `brainfuck`, `pbrain`, `brainfork`, `extended-type-i` or `boolfuck`"
+            )))
+            .transpose()?;
+
+        Ok(Self {
+            max_cell_value: number(options, "maxCellValue")?.map(|value| value as u32),
+            memory_size: number(options, "memorySize")?.map(|size| size as usize),
+            instructions_limit: number(options, "instructionsLimit")?.map(|limit| limit as usize),
+            timeout: number(options, "timeout")?,
+            fallback_input,
+            wrapping: option(options, "wrapping").map(|wrapping| wrapping.is_truthy()),
+            optimization,
+            eof,
+            dialect,
+        })
+    }
+
+    /// builds the interpreter of the provided code configured by these options,
+    /// `now` returning the current time in milliseconds to check the timeout against
+    fn configure<'a>(&self, code: &str, now: fn() -> f64) -> Brainfuck<'a> {
+        let mut interp = Brainfuck::new(code);
+
+        if let Some(value) = self.max_cell_value {
+            interp = interp.with_max_value(value);
+        }
+        if let Some(size) = self.memory_size {
+            interp = interp.with_mem_size(size);
+        }
+        if let Some(limit) = self.instructions_limit {
+            interp = interp.with_instructions_limit(limit);
+        }
+        if let Some(chr) = self.fallback_input {
+            interp = interp.with_fallback_input(chr);
+        }
+        if let Some(wrapping) = self.wrapping {
+            interp = interp.with_wrapping(wrapping);
+        }
+        if let Some(level) = self.optimization {
+            interp = interp.with_optimization(level);
+        }
+        if let Some(eof) = self.eof {
+            interp = interp.with_eof(eof);
+        }
+        if let Some(dialect) = self.dialect {
+            interp = interp.with_dialect(dialect);
+        }
+        if let Some(timeout) = self.timeout {
+            // the timeout of the interpreter reads the clock of the standard library as well, so it is checked with `now` instead
+            let deadline = now() + timeout;
+            interp = interp.with_instruction_hook(move |ctx|
+                if ctx.instructions.is_multiple_of(TIMEOUT_CHECK_INTERVAL) && now() >= deadline {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            );
+        }
+        interp
+    }
+}
+
+/// executes the provided code with the provided input, returning its output decoded as UTF-8 and its information,
+/// or the message of the error it failed with
+fn execute(code: &str, input: &str, options: &Options, now: fn() -> f64) -> Result<(String, ExecutionInfo), String> {
+    let mut output = Vec::new();
+    let result = options
+        .configure(code, now)
+        .with_input(Cursor::new(input.as_bytes().to_vec()))
+        .with_output_ref(&mut output)
+        .execute();
+
+    match result {
+        Ok(info) => Ok((String::from_utf8_lossy(&output).into_owned(), info)),
+        Err(Error::Cancelled(_)) => Err(String::from("timed out")),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// sets the provided property of the provided object
+fn set(object: &Object, key: &str, value: &JsValue) -> Result<(), JsError> {
+    Reflect::set(object, &JsValue::from_str(key), value)
+        .map(|_| ())
+        .map_err(|_| JsError::new(&format!("failed to set `{key}` on the result")))
+}
+
+/// executes the provided code with the provided input, returning `{ output, cells, pointer, instructions, time }`,
+/// the output being decoded as UTF-8, the cells being a `Uint32Array`, and the time being in milliseconds
+///
+/// the options object can be `undefined`, and takes the optional properties:
+/// - `maxCellValue`, `memorySize`, `instructionsLimit` and `timeout` (in milliseconds) as numbers
+/// - `fallbackInput` as a string of a single character
+/// - `wrapping` as a boolean
+/// - `optimization` as one of `none`, `basic` or `aggressive`
+/// - `eof` as one of `fallback`, `max` or `unchanged`
+/// - `dialect` as one of `brainfuck`, `pbrain`, `brainfork`, `extended-type-i` or `boolfuck`
+///
+/// # Errors
+/// throws an `Error` with the message of the error if the options are invalid or the execution fails,
+/// reaching the timeout failing with the message `timed out`
+///
+/// ```js
+/// import { run } from "brainfuck_exe";
+///
+/// const { output, instructions } = run(",[.,]", "hello", { instructionsLimit: 1_000_000 });
+/// ```
+#[wasm_bindgen]
+pub fn run(code: &str, input: &str, options: JsValue) -> Result<Object, JsError> {
+    let options = Options::parse(&options)?;
+    let start = Date::now();
+    let (output, info) = execute(code, input, &options, Date::now)
+        .map_err(|message| JsError::new(&message))?;
+    let time = Date::now() - start;
+
+    let object = Object::new();
+    set(&object, "output", &JsValue::from_str(&output))?;
+    set(&object, "cells", &Uint32Array::from(info.cells.as_slice()).into())?;
+    set(&object, "pointer", &JsValue::from_f64(info.pointer as f64))?;
+    set(&object, "instructions", &JsValue::from_f64(info.instructions as f64))?;
+    set(&object, "time", &JsValue::from_f64(time))?;
+    Ok(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use crate::{Dialect, OptimizationLevel};
+    use super::{execute, Options};
+
+    /// the clock of the standard library standing in for `Date::now`, which is only there in JS
+    fn now() -> f64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |time| time.as_secs_f64() * 1000.0)
+    }
+
+    #[test]
+    fn test_execute() {
+        let options = Options {
+            optimization: Some(OptimizationLevel::Aggressive),
+            instructions_limit: Some(1_000),
+            ..Options::default()
+        };
+        let (output, info) = execute(",[.,]", "hello", &options, now).unwrap();
+
+        assert_eq!(output, "hello");
+        assert_eq!(info.cells, [0]);
+
+        let options = Options { max_cell_value: Some(1), wrapping: Some(true), ..Options::default() };
+        let (_, info) = execute("+++", "", &options, now).unwrap();
+        assert_eq!(info.cells, [1]);
+
+        let options = Options { dialect: Some(Dialect::Brainfork), ..Options::default() };
+        assert!(execute("Y+", "", &options, now).is_ok());
+    }
+
+    #[test]
+    fn test_execute_errors() {
+        let options = Options { instructions_limit: Some(10), ..Options::default() };
+        assert!(execute("+[]", "", &options, now).unwrap_err().contains("10"));
+
+        let options = Options { timeout: Some(10.0), ..Options::default() };
+        assert_eq!(execute("+[]", "", &options, now).unwrap_err(), "timed out");
+
+        assert!(execute("+[", "", &Options::default(), now).is_err());
+    }
+}
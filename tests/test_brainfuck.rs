@@ -3,9 +3,43 @@
 mod tests {
     use std::{
         fs::File,
-        io::Cursor,
+        io::{Cursor, ErrorKind, Read, Write},
+        ops::ControlFlow,
+        sync::{
+            Arc, Mutex,
+            atomic::{AtomicBool, Ordering},
+        },
+        thread,
+        time::Duration,
+    };
+    use brainfuck_exe::{
+        lint,
+        Brainfuck,
+        CellAccess,
+        CellDiff,
+        Coverage,
+        Error,
+        EvalOutcome,
+        ExecutionEvent,
+        ExecutionInfo,
+        ExecutionLimits,
+        ExecutionObserver,
+        FlushPolicy,
+        HeatmapStyle,
+        HookCtx,
+        HtmlOptions,
+        OpenMode,
+        Pipeline,
+        Position,
+        Profiler,
+        ProgramState,
+        render_html,
+        Result,
+        Scheduler,
+        Session,
+        Severity,
+        Step,
     };
-    use brainfuck_exe::{Brainfuck, Result};
 
     #[test]
     fn test_hello_world_file() -> Result<()> {
@@ -85,4 +119,2172 @@ mod tests {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cancel_token() {
+        let token = Arc::new(AtomicBool::new(false));
+        let canceller = Arc::clone(&token);
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            canceller.store(true, Ordering::Relaxed);
+        });
+
+        let result = Brainfuck::new("+[]")
+            .with_cancel_token(token)
+            .execute();
+
+        assert!(matches!(
+            result,
+            Err(Error::Cancelled(context)) if context.info.cells[0] == 1
+        ));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let timeout = Duration::from_millis(50);
+        let result = Brainfuck::new("+[]")
+            .with_timeout(timeout)
+            .execute();
+
+        assert!(matches!(
+            result,
+            Err(Error::TimedOut { timeout: t, .. }) if t == timeout
+        ));
+
+        let info = Brainfuck::new("+[+]")
+            .with_timeout(Duration::MAX)
+            .execute()
+            .unwrap();
+        assert_eq!(info.cells, [0]);
+    }
+
+    #[test]
+    fn test_spawn() -> Result<()> {
+        let handle = Brainfuck::new("++++++++[>++++++++<-]>+.+.+.")
+            .spawn();
+        let output = handle.output()
+            .iter()
+            .flatten()
+            .collect::<Vec<u8>>();
+        assert_eq!(output, b"ABC");
+
+        let info = handle.join()?;
+        assert_eq!(info.cells[1], 67);
+        Ok(())
+    }
+
+    #[test]
+    fn test_spawn_pause_kill() {
+        let handle = Brainfuck::new("+[]").spawn();
+
+        handle.pause();
+        assert!(handle.is_paused());
+        handle.resume();
+        assert!(!handle.is_paused());
+
+        handle.kill();
+        assert!(matches!(handle.join(), Err(Error::Cancelled(_))));
+    }
+
+    #[test]
+    fn test_send() -> Result<()> {
+        let interp = Brainfuck::new(",+.")
+            .with_input(Cursor::new(b"a".to_vec()))
+            .with_output(Cursor::new(Vec::new()));
+
+        let info = thread::spawn(move || {
+            let mut interp = interp;
+            interp.execute()
+        })
+            .join()
+            .unwrap()?;
+
+        assert_eq!(info.cells[0], u32::from(b'b'));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sans_io() -> Result<()> {
+        let mut interp = Brainfuck::new(",+.,.");
+        let mut execution = interp.start()?;
+        let mut output = Vec::new();
+        let mut input = "a".bytes();
+
+        loop {
+            match execution.resume()? {
+                Step::NeedsInput => execution.feed(input.next()),
+                Step::Output(bytes) => output.extend(bytes),
+                Step::Halted(info) => {
+                    assert_eq!(info.instructions, 5);
+                    break;
+                },
+            }
+        }
+
+        assert_eq!(output, b"b\0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_sans_io_after_halt() -> Result<()> {
+        /// counts the executions that halted
+        #[derive(Default)]
+        struct Halts(usize);
+
+        impl ExecutionObserver for Halts {
+            fn on_halt(&mut self, _: &ExecutionInfo) {
+                self.0 += 1;
+            }
+        }
+
+        let mut halts = Halts::default();
+        let mut interp = Brainfuck::new("++").with_observer(&mut halts);
+        let mut execution = interp.start()?;
+
+        for _ in 0..3 {
+            assert!(matches!(execution.resume()?, Step::Halted(ref info) if info.instructions == 2));
+        }
+        drop(interp);
+        assert_eq!(halts.0, 1);
+        Ok(())
+    }
+
+    /// an output stream whose buffer can still be accessed after being moved into an interpreter
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_pipeline() -> Result<()> {
+        let output = SharedBuffer::default();
+        // increments every byte, then doubles every byte
+        let infos = Pipeline::new()
+            .then(Brainfuck::new(",[+.,]"))
+            .then(Brainfuck::new(",[..,]")
+                .with_output(output.clone())
+            )
+            .execute(Cursor::new(b"abc".to_vec()))?;
+
+        assert_eq!(infos.len(), 2);
+        assert_eq!(*output.0.lock().unwrap(), b"bbccdd");
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream() -> Result<()> {
+        let mut stream = Brainfuck::new(",[+.,]").into_stream()?;
+        let mut buf = [0; 8];
+
+        stream.write_all(b"ab").unwrap();
+        assert_eq!(stream.read(&mut buf).unwrap(), 1);
+        assert_eq!(stream.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], b'c');
+        assert_eq!(
+            stream.read(&mut buf).unwrap_err().kind(),
+            ErrorKind::WouldBlock,
+        );
+
+        stream.write_all(b"y").unwrap();
+        stream.close_input();
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).unwrap();
+
+        assert_eq!(rest, b"z");
+        assert!(stream.is_halted());
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_output_stream() -> Result<()> {
+        let mut stream = Brainfuck::new(",[+.,]")
+            .with_no_output()
+            .into_stream()?;
+
+        stream.write_all(b"HAL").unwrap();
+        stream.close_input();
+        let mut output = Vec::new();
+        stream.read_to_end(&mut output).unwrap();
+
+        assert!(output.is_empty());
+        assert!(stream.is_halted());
+        assert_eq!(stream.info().instructions, 17);
+        Ok(())
+    }
+
+    #[test]
+    fn test_instruction_hook() -> Result<()> {
+        let mut opcodes = String::new();
+
+        Brainfuck::new("+ comment >-")
+            .with_instruction_hook(|ctx| {
+                opcodes.push(ctx.opcode);
+                ControlFlow::Continue(())
+            })
+            .execute()?;
+        assert_eq!(opcodes, "+>-");
+
+        let result = Brainfuck::new("+[>+]")
+            .with_instruction_hook(|ctx|
+                if ctx.pointer >= 10 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            )
+            .execute();
+        assert!(matches!(
+            result,
+            Err(Error::Cancelled(context)) if context.pointer == 10
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_io_hooks() -> Result<()> {
+        let (mut read, mut written) = (Vec::new(), Vec::new());
+        let mut output = Cursor::new(Vec::new());
+
+        Brainfuck::new(",+.,+.,")
+            .with_input(Cursor::new(b"ab".to_vec()))
+            .with_output_ref(&mut output)
+            .on_input(|byte| read.push(byte))
+            .on_output(|byte| written.push(byte))
+            .execute()?;
+
+        assert_eq!(read, b"ab");
+        assert_eq!(written, b"bc");
+        assert_eq!(output.into_inner(), b"bc");
+        Ok(())
+    }
+
+    #[derive(Default)]
+    struct LoopCounter {
+        started: bool,
+        entered: usize,
+        exited: usize,
+        instructions: usize,
+        halted: bool,
+    }
+
+    impl ExecutionObserver for LoopCounter {
+        fn on_start(&mut self) {
+            self.started = true;
+        }
+
+        fn on_instruction(&mut self, _ctx: &HookCtx) {
+            self.instructions += 1;
+        }
+
+        fn on_loop_enter(&mut self, _code_idx: usize) {
+            self.entered += 1;
+        }
+
+        fn on_loop_exit(&mut self, _code_idx: usize) {
+            self.exited += 1;
+        }
+
+        fn on_halt(&mut self, _info: &ExecutionInfo) {
+            self.halted = true;
+        }
+    }
+
+    #[test]
+    fn test_observer() -> Result<()> {
+        let mut observer = LoopCounter::default();
+
+        // the first loop is entered twice, the last one is skipped
+        let info = Brainfuck::new("++[>++[-]<-][]")
+            .with_observer(&mut observer)
+            .execute()?;
+
+        assert!(observer.started && observer.halted);
+        assert_eq!((observer.entered, observer.exited), (3, 3));
+        assert_eq!(observer.instructions, info.instructions);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hook_ctx_attribution() -> Result<()> {
+        use brainfuck_exe::{Dialect, OptimizationLevel};
+
+        #[derive(Default)]
+        struct Recorder(Vec<HookCtx>);
+
+        impl ExecutionObserver for Recorder {
+            fn on_instruction(&mut self, ctx: &HookCtx) {
+                self.0.push(*ctx);
+            }
+        }
+
+        // the output folded by the optimizer is written before `,`, without being attributed to it
+        let (mut hooked, mut observer) = (Vec::new(), Recorder::default());
+        Brainfuck::new("++++++++[>++++++++<-]>+.+.,.")
+            .with_optimization(OptimizationLevel::Aggressive)
+            .with_mem_size(30_000)
+            .with_input(&b"z"[..])
+            .with_output(std::io::sink())
+            .with_instruction_hook(|ctx| {
+                hooked.push(*ctx);
+                ControlFlow::Continue(())
+            })
+            .with_observer(&mut observer)
+            .execute()?;
+
+        assert_eq!(hooked.iter().map(|ctx| ctx.opcode).collect::<String>(), ",.");
+        assert_eq!(hooked[0].instructions, 2);
+        assert_eq!(observer.0, hooked);
+
+        // the thread forked by `Y` is described by the context of the step it takes
+        let (mut hooked, mut observer) = (Vec::new(), Recorder::default());
+        Brainfuck::new("Y+")
+            .with_dialect(Dialect::Brainfork)
+            .with_instruction_hook(|ctx| {
+                hooked.push(*ctx);
+                ControlFlow::Continue(())
+            })
+            .with_observer(&mut observer)
+            .execute()?;
+
+        assert_eq!(
+            hooked.iter().map(|ctx| (ctx.opcode, ctx.pointer, ctx.cell)).collect::<Vec<_>>(),
+            [('Y', 0, 0), ('+', 1, 1), ('+', 0, 0)],
+        );
+        assert_eq!(observer.0, hooked);
+        Ok(())
+    }
+
+    #[test]
+    fn test_opcode_counts() -> Result<()> {
+        let info = Brainfuck::new("++[>+<-]>.")
+            .with_output(Cursor::new(Vec::new()))
+            .execute()?;
+
+        let counts = info.opcode_counts;
+        assert_eq!(
+            ['+', '-', '<', '>', '.', ',', '[', ']'].map(|opcode| counts.get(opcode)),
+            [4, 2, 2, 3, 1, 0, 3, 2].map(Some),
+        );
+        assert_eq!(counts.get('a'), None);
+        assert_eq!(counts.total(), info.instructions);
+        Ok(())
+    }
+
+    #[test]
+    fn test_profiler() -> Result<()> {
+        let code = "++++[>++++[>+<-]<-]\n+[-]";
+        let mut profiler = Profiler::new(code);
+
+        Brainfuck::new(code)
+            .with_observer(&mut profiler)
+            .execute()?;
+
+        let loops = profiler.loops();
+        assert_eq!(loops.len(), 3);
+        assert_eq!((loops[2].line, loops[2].column), (2, 2));
+        assert_eq!(loops[1].body, "[>+<-]");
+        assert_eq!((loops[1].entries, loops[1].iterations), (4, 16));
+        assert_eq!(
+            loops[0].instructions,
+            loops[0].self_instructions + loops[1].instructions,
+        );
+
+        let hottest = profiler.hottest_loops(2);
+        assert_eq!(hottest.len(), 2);
+        assert_eq!(hottest[0].code_idx, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_coverage() -> Result<()> {
+        let code = ",[[-]]\n[.]";
+        let mut coverage = Coverage::new(code);
+
+        for input in [&b"a"[..], b"\0"] {
+            Brainfuck::new(code)
+                .with_input(Cursor::new(input))
+                .with_observer(&mut coverage)
+                .execute()?;
+        }
+        assert_eq!(coverage.total(), 9);
+        assert_eq!(coverage.dead_instructions(), vec![8, 9]);
+        assert!(coverage.is_executed(2));
+        assert!((coverage.percentage() - 7.0 / 9.0 * 100.0).abs() < f64::EPSILON);
+        assert_eq!(coverage.annotate(), ",[[-]]\n[.]\n ^^\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_cell_heatmap() -> Result<()> {
+        let info = Brainfuck::new("++[>+++<-]>.>>")
+            .with_output(Cursor::new(Vec::new()))
+            .with_cell_tracking(true)
+            .execute()?;
+
+        let accesses = info.cell_accesses
+            .as_deref()
+            .unwrap();
+        assert_eq!(accesses.len(), info.cells.len());
+        assert_eq!(accesses[0], CellAccess { reads: 3, writes: 4 });
+        assert_eq!(accesses[1], CellAccess { reads: 1, writes: 6 });
+        assert_eq!(accesses[3].total(), 0);
+        assert_eq!(
+            info.heatmap(HeatmapStyle::Ascii).as_deref(),
+            Some("0 |@@  |\n"),
+        );
+
+        let untracked = Brainfuck::new("+")
+            .execute()?;
+        assert!(untracked.heatmap(HeatmapStyle::Ansi).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_export() -> Result<()> {
+        let code = "+[>++[-]<-]";
+        let mut profiler = Profiler::new(code);
+
+        Brainfuck::new(code)
+            .with_observer(&mut profiler)
+            .execute()?;
+
+        assert_eq!(
+            profiler.to_folded(),
+            "main 1\nmain;loop@1:2 8\nmain;loop@1:2;loop@1:6 7\n",
+        );
+
+        let callgrind = profiler.to_callgrind("test.bf");
+        assert!(callgrind.starts_with("# callgrind format\n"));
+        assert!(callgrind.contains("fl=test.bf\n"));
+        assert!(callgrind.contains("\nfn=main\n1 1\ncfn=loop@1:2\ncalls=1 1\n1 15\n"));
+        assert!(callgrind.contains("\nfn=loop@1:6\n1 7\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_mismatched_brackets() {
+        let position = |code: &str| match Brainfuck::new(code).execute() {
+            Err(Error::MismatchedBrackets { bracket, position, .. }) => Some((bracket, position)),
+            _ => None,
+        };
+
+        assert_eq!(
+            position("+[\n[-]\n  ]]["),
+            Some((']', Position { offset: 10, line: 3, column: 4 })),
+        );
+        assert_eq!(
+            position("é[[-]"),
+            Some(('[', Position { offset: 2, line: 1, column: 2 })),
+        );
+        assert_eq!(position("[[-]]"), None);
+    }
+
+    #[test]
+    fn test_error_source() {
+        use std::error::Error as _;
+
+        let err = Brainfuck::from_file("tests/missing.bf")
+            .err()
+            .unwrap();
+        let source = err.source()
+            .and_then(|source| source.downcast_ref::<std::io::Error>())
+            .unwrap();
+        assert_eq!(source.kind(), ErrorKind::NotFound);
+
+        let boxed: Box<dyn std::error::Error> = Brainfuck::new("[")
+            .execute()
+            .unwrap_err()
+            .into();
+        assert!(boxed.source().is_none());
+    }
+
+    #[test]
+    fn test_strict_syntax() -> Result<()> {
+        let code = "+ [-]\n\t+x+";
+
+        Brainfuck::new(code)
+            .execute()?;
+
+        match Brainfuck::new(code)
+            .with_strict_syntax(true)
+            .execute()
+        {
+            Err(Error::UnexpectedCharacter { character, position }) => {
+                assert_eq!(character, 'x');
+                assert_eq!(position, Position { offset: 8, line: 2, column: 3 });
+            },
+            other => panic!("expected an unexpected character error, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_runtime_context() {
+        struct FailingWriter;
+
+        impl Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(ErrorKind::BrokenPipe.into())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let err = Brainfuck::new("++\n>+.")
+            .with_output(FailingWriter)
+            .execute()
+            .unwrap_err();
+        assert!(matches!(err, Error::OutputError { ref source, .. } if source.kind() == ErrorKind::BrokenPipe));
+
+        let context = err.context().unwrap();
+        assert_eq!((context.code_idx, context.position.line, context.position.column), (5, 2, 3));
+        assert_eq!(context.pointer, 1);
+        assert_eq!(context.info.cells, vec![2, 1]);
+
+        let err = Brainfuck::new("+[>+<]")
+            .with_instructions_limit(10)
+            .execute()
+            .unwrap_err();
+        assert!(matches!(err, Error::MaxInstructionsExceeded { limit: 10, .. }));
+        assert_eq!(err.context().unwrap().info.instructions, 11);
+
+        assert_eq!(err.position(), Some(err.context().unwrap().position));
+
+        let err = Brainfuck::new("[").execute().unwrap_err();
+        assert!(err.context().is_none());
+        assert_eq!(err.position(), Some(Position { offset: 0, line: 1, column: 1 }));
+    }
+
+    #[test]
+    fn test_check() {
+        let code = "]+[[.]]\n[-]x[";
+        let diagnostics = Brainfuck::new(code)
+            .with_strict_syntax(true)
+            .check();
+
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| (diagnostic.severity, &code[diagnostic.span.range()]))
+                .collect::<Vec<_>>(),
+            vec![
+                (Severity::Error, "]"),
+                (Severity::Warning, "[[.]]"),
+                (Severity::Error, "x"),
+                (Severity::Error, "["),
+            ],
+        );
+        assert_eq!(diagnostics[2].span.start, Position { offset: 11, line: 2, column: 4 });
+        assert!(Brainfuck::new("+[-]").check().is_empty());
+        // the instructions of the dialect are not unexpected
+        assert!(Brainfuck::new("+(-):")
+            .with_dialect(brainfuck_exe::Dialect::Pbrain)
+            .with_strict_syntax(true)
+            .check()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_lint() {
+        let code = "+-[]>\n++[-]+[.]<";
+        let lints = Brainfuck::new(code)
+            .lint();
+
+        assert_eq!(
+            lints
+                .iter()
+                .map(|lint| (lint.lint.unwrap(), &code[lint.span.range()]))
+                .collect::<Vec<_>>(),
+            vec![
+                (lint::CANCELLING_PAIR, "+-"),
+                (lint::EMPTY_LOOP, "[]"),
+                (lint::CLEARED_WRITE, "++"),
+                (lint::UNREACHABLE_CODE, "<"),
+            ],
+        );
+        assert!(lints.iter().all(|lint| lint.severity == Severity::Warning));
+        assert_eq!(lints[0].suggestion.as_deref(), Some(""));
+        assert!(Brainfuck::new("++[>+<-]").lint().is_empty());
+    }
+
+    #[test]
+    fn test_infinite_loop_detection() -> Result<()> {
+        let result = Brainfuck::new("+>++[<+>-+]")
+            .with_infinite_loop_detection(true)
+            .execute();
+        assert!(matches!(
+            result,
+            Err(Error::InfiniteLoop(context)) if context.pointer == 1
+        ));
+
+        // the state never repeats, as every iteration produces output
+        let info = Brainfuck::new("+++[.-]")
+            .with_output(Cursor::new(Vec::new()))
+            .with_infinite_loop_detection(true)
+            .execute()?;
+        assert_eq!(info.cells, vec![0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let result = Brainfuck::new("+>+.")
+            .with_mem_size(0)
+            .execute();
+        assert!(matches!(result, Err(Error::ZeroMemorySize)));
+
+        let result = Brainfuck::new("+>+.")
+            .with_max_value(0)
+            .execute();
+        assert!(matches!(result, Err(Error::ZeroMaxCellValue)));
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        use brainfuck_exe::ast::{parse, NodeKind};
+
+        let nodes = parse("+++-\n>> comment <[,[-].]")?;
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[0].kind, NodeKind::Inc(2));
+        assert_eq!(nodes[1].kind, NodeKind::Move(1));
+        assert_eq!(nodes[1].span.start.line, 2);
+        assert_eq!(nodes[1].span.range(), 5..17);
+
+        let NodeKind::Loop(ref body) = nodes[2].kind else {
+            panic!("expected a loop");
+        };
+        assert_eq!(body.len(), 3);
+        assert_eq!(body[0].kind, NodeKind::Input);
+        let NodeKind::Loop(ref inner) = body[1].kind else {
+            panic!("expected a nested loop");
+        };
+        assert_eq!(inner[0].kind, NodeKind::Inc(-1));
+        assert_eq!(inner[0].span.range(), 20..21);
+        assert_eq!(body[1].span.range(), 19..22);
+        assert_eq!(body[2].kind, NodeKind::Output);
+
+        assert!(matches!(parse("+[.]]"), Err(Error::MismatchedBrackets { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ast_visitor_and_folder() -> Result<()> {
+        use brainfuck_exe::{ast::{parse, Folder, Node, NodeKind, Visitor}, Span};
+
+        /// records the maximum loop depth
+        #[derive(Default)]
+        struct Depth {
+            current: usize,
+            max: usize,
+        }
+
+        impl Visitor for Depth {
+            fn visit_loop(&mut self, body: &[Node], _span: Span) {
+                self.current += 1;
+                self.max = self.max.max(self.current);
+                self.visit_nodes(body);
+                self.current -= 1;
+            }
+        }
+
+        /// doubles every increment, and removes every input
+        struct Rewrite;
+
+        impl Folder for Rewrite {
+            fn fold_inc(&mut self, amount: i64, span: Span) -> Option<Node> {
+                Some(Node { kind: NodeKind::Inc(amount * 2), span })
+            }
+
+            fn fold_input(&mut self, _span: Span) -> Option<Node> {
+                None
+            }
+        }
+
+        let nodes = parse("+[>[-,]<[.]]")?;
+        let mut depth = Depth::default();
+        depth.visit_nodes(&nodes);
+        assert_eq!(depth.max, 2);
+
+        let folded = Rewrite.fold_nodes(nodes);
+        assert_eq!(folded[0].kind, NodeKind::Inc(2));
+
+        let NodeKind::Loop(ref body) = folded[1].kind else {
+            panic!("expected a loop");
+        };
+        let NodeKind::Loop(ref inner) = body[1].kind else {
+            panic!("expected a nested loop");
+        };
+        assert_eq!(inner.len(), 1);
+        assert_eq!(inner[0].kind, NodeKind::Inc(-2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_source() -> Result<()> {
+        use brainfuck_exe::{format_source, FormatOptions};
+
+        let code = "prints A: ++++++++[>++++ ++++<-]>+. done";
+        let formatted = format_source(code, FormatOptions::new().with_width(16))?;
+        assert_eq!(formatted, "prints A: ++++++\n++[>++++++++<-]>\n+. done\n");
+        assert_eq!(format_source(&formatted, FormatOptions::new().with_width(16))?, formatted);
+
+        let stripped = format_source(code, FormatOptions::new().with_comments(false))?;
+        assert_eq!(stripped, "++++++++[>++++++++<-]>+.\n");
+
+        let nested = format_source("+[>[-]<[->+<]]", FormatOptions::new().with_indent(2))?;
+        assert_eq!(nested, "+\n[\n  >[-]<[->+<]\n]\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_minify_source() -> Result<()> {
+        use brainfuck_exe::minify_source;
+
+        let code = std::fs::read_to_string("tests/hello_world.bf")?;
+        let minified = minify_source(&code)?;
+        assert!(minified.len() <= code.len());
+
+        let mut output = Vec::new();
+        Brainfuck::new(&minified)
+            .with_output_ref(&mut output)
+            .execute()?;
+        assert_eq!(output, b"Hello, World!");
+
+        assert_eq!(minify_source("[.] a+-b ,[>+<-<>][-]>>-<< .")?, ",[>+<-]>>-<<.");
+        Ok(())
+    }
+
+    #[test]
+    fn test_control_flow_graph() -> Result<()> {
+        use brainfuck_exe::ast::Program;
+
+        let program = Program::parse("++ [>+<-] >.")?;
+        assert_eq!(program.to_string(), "++[>+<-]>.");
+
+        let dot = program.to_dot();
+        assert_eq!(dot.matches("->").count(), 6);
+        assert!(dot.contains("n1 [label=\"++\\n1:1\"];"));
+        assert!(dot.contains("n2 [label=\"loop\\n1:4\", shape=diamond];"));
+        assert!(dot.contains("n3 -> n2;"));
+        assert!(dot.contains("n2 -> n4 [label=\"== 0\"];"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_static_analysis() -> Result<()> {
+        use brainfuck_exe::ast::Program;
+
+        let code = std::fs::read_to_string("tests/hello_world.bf")?;
+        let analysis = Program::parse(&code)?.analyze();
+        assert!(analysis.loops.iter().all(|analysis| analysis.balanced));
+
+        let size = analysis.memory_size().expect("the memory size should be determinable");
+        Brainfuck::new(&code)
+            .with_mem_size(size)
+            .with_output(Cursor::new(Vec::new()))
+            .execute()?;
+
+        let analysis = Program::parse("+[[>]<[-]+]>")?.analyze();
+        assert_eq!(analysis.loops.len(), 3);
+        assert_eq!(analysis.loops[0].shift, None);
+        assert_eq!(analysis.loops[1].shift, Some(1));
+        assert!(analysis.loops[2].balanced);
+        assert_eq!(analysis.loops[2].span.range(), 6..9);
+        assert_eq!(analysis.max_pointer, None);
+
+        assert_eq!(Program::parse("<+>")?.analyze().max_pointer, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dead_code_elimination() -> Result<()> {
+        use brainfuck_exe::optimizer;
+
+        let code = "[-] ++ , [>+<-] > -- [-] [>] ++++++[<++++++++>-] < . [-] + [.] >.";
+        let optimized = Brainfuck::new(code).optimize()?;
+        assert_eq!(optimized.program.to_string(), ",[>+<-]>[-]++++++[<++++++++>-]<.[-]+[.]");
+
+        let passes = optimized.removed
+            .iter()
+            .map(|removal| removal.pass)
+            .collect::<Vec<&str>>();
+        assert_eq!(passes, [
+            optimizer::DEAD_LOOP,
+            optimizer::DEAD_STORE,
+            optimizer::DEAD_STORE,
+            optimizer::DEAD_LOOP,
+            optimizer::UNREACHABLE_CODE,
+        ]);
+        assert_eq!(&code[optimized.removed[4].span.range()], ">.");
+
+        let mut output = Vec::new();
+        Brainfuck::new(optimized.program.to_string())
+            .with_input(Cursor::new(vec![1]))
+            .with_output_ref(&mut output)
+            .with_instructions_limit(1000)
+            .execute()
+            .ok();
+        assert!(output.starts_with(b"0"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_evaluation() -> Result<()> {
+        use brainfuck_exe::{ast::NodeKind, optimizer, OptimizationLevel};
+
+        let code = std::fs::read_to_string("tests/hello_world.bf")?;
+        let mut output = Vec::new();
+        let unoptimized = Brainfuck::new(&code)
+            .with_output_ref(&mut output)
+            .with_mem_size(30_000)
+            .execute()?;
+
+        let mut optimized_output = Vec::new();
+        let optimized = Brainfuck::new(&code)
+            .with_output_ref(&mut optimized_output)
+            .with_optimization(OptimizationLevel::Aggressive)
+            .with_mem_size(30_000)
+            .execute()?;
+        assert_eq!(optimized_output, output);
+        assert_eq!(optimized.pointer, unoptimized.pointer);
+        assert!(optimized.instructions * 2 < unoptimized.instructions);
+
+        // the code after the first input is left to the execution
+        let code = format!("++++++++++[>++++++++++[>++++++++++<-]<-]>>[-]{}.,.", "+".repeat(65));
+        let optimized = Brainfuck::new(code)
+            .with_optimization(OptimizationLevel::Aggressive)
+            .optimize()?;
+        let NodeKind::Emit(ref string) = optimized.program.nodes[0].kind else {
+            panic!("expected the output to be folded");
+        };
+        assert_eq!(string, "A");
+        assert!(optimized.program.to_string().ends_with(",."));
+        assert_eq!(optimized.removed[0].pass, optimizer::PARTIAL_EVALUATION);
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimized_source_map() -> Result<()> {
+        use brainfuck_exe::{ast::Program, OptimizationLevel, Profiler};
+
+        let (code, map) = Program::parse("+- [.] skipped\n+++[>+<-]")?
+            .to_code_with_map();
+        assert_eq!(code, "[.]+++[>+<-]");
+        assert_eq!(map.len(), code.len());
+        assert_eq!(map.span(3).map(|span| span.range()), Some(15..18));
+        assert_eq!(map.span(6).map(|span| span.range()), Some(18..19));
+
+        let code = "[.] removed\n++ [>+<] never ends";
+        let result = Brainfuck::new(code)
+            .with_optimization(OptimizationLevel::Aggressive)
+            .with_mem_size(30_000)
+            .with_instructions_limit(100)
+            .execute();
+        let Err(Error::MaxInstructionsExceeded { context, .. }) = result else {
+            panic!("expected the instructions limit to be exceeded");
+        };
+        assert_eq!(context.position.line, 2);
+        assert!(matches!(code.chars().nth(context.code_idx), Some('>' | '+' | '<' | '[' | ']')));
+
+        let code = "[-]\n++++[>++[>+<-]<-]";
+        let mut profiler = Profiler::new(code);
+        Brainfuck::new(code)
+            .with_optimization(OptimizationLevel::Basic)
+            .with_mem_size(30_000)
+            .with_observer(&mut profiler)
+            .execute()?;
+        let loops = profiler.hottest_loops(1);
+        assert_eq!((loops[0].line, loops[0].column), (2, 9));
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimization_report() -> Result<()> {
+        use brainfuck_exe::optimizer::{self, PassReport};
+
+        let code = "[-] ++ , [>+<-] > -- [-] [>] ++++++[<++++++++>-] < . [-] + [.] >.";
+        let report = Brainfuck::new(code)
+            .optimize()?
+            .report(code);
+
+        assert_eq!(report.passes, [
+            PassReport { pass: optimizer::UNREACHABLE_CODE, removals: 1, instructions: 2 },
+            PassReport { pass: optimizer::DEAD_LOOP, removals: 2, instructions: 6 },
+            PassReport { pass: optimizer::DEAD_STORE, removals: 2, instructions: 4 },
+        ]);
+        assert_eq!(report.instructions, 51);
+        assert_eq!(report.optimized_instructions, 39);
+        assert!(report.operations > report.optimized_operations);
+        assert!(report.to_string().starts_with("unreachable_code: 1 removed, 2 instructions eliminated\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ook_translation() -> Result<()> {
+        use brainfuck_exe::translate::{translate, Language};
+
+        let mut output = Vec::new();
+        Brainfuck::from_file("tests/hello_world.ook")?
+            .with_output_ref(&mut output)
+            .execute()?;
+        assert_eq!(output, b"Hello, World!");
+
+        let code = std::fs::read_to_string("tests/hello_world.bf")
+            .map_err(Error::FileReadError)?;
+        let ook = translate(&code, Language::Brainfuck, Language::Ook)?;
+        assert_eq!(
+            translate(&ook, Language::Ook, Language::Brainfuck)?,
+            code.replace('\n', ""),
+        );
+
+        let err = translate("Ook. Ook.\nOok? Ook?", Language::Ook, Language::Brainfuck).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidToken { ref token, position: Position { line: 2, column: 1, .. } } if token == "Ook? Ook?"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_table() -> Result<()> {
+        use brainfuck_exe::translate::TokenTable;
+
+        let blub = TokenTable::new([
+            "Blub. Blub.", "Blub! Blub!", "Blub? Blub.", "Blub. Blub?",
+            "Blub! Blub.", "Blub. Blub!", "Blub! Blub?", "Blub? Blub!",
+        ])?;
+        let code = blub.encode(">++++++[<+++++++++++>-]<.");
+
+        let mut output = Vec::new();
+        Brainfuck::new(format!("blub: {code}"))
+            .with_token_table(&blub)
+            .with_output_ref(&mut output)
+            .execute()?;
+        assert_eq!(output, b"B");
+
+        assert!(matches!(
+            TokenTable::new(["a", "b", "c", "d", "e", "f", "g", "a"]),
+            Err(Error::InvalidTokenTable(_)),
+        ));
+
+        #[cfg(feature = "toml")]
+        {
+            let table = TokenTable::from_toml(
+                "increment = 'a'\ndecrement = 'b'\nmove_left = 'c'\nmove_right = 'd'\n\
+                output = 'e'\ninput = 'f'\nloop_start = 'g'\nloop_end = 'h'"
+            )?;
+            assert_eq!(table.token('['), Some("g"));
+            assert!(matches!(TokenTable::from_toml("increment = 'a'"), Err(Error::InvalidTokenTable(_))));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_pbrain() -> Result<()> {
+        use brainfuck_exe::Dialect;
+
+        // defines procedure `0` printing the current cell after adding 33, then calls it twice
+        let mut output = Vec::new();
+        Brainfuck::new("(+++++++++++++++++++++++++++++++++.[-]) :>:")
+            .with_dialect(Dialect::Pbrain)
+            .with_output_ref(&mut output)
+            .execute()?;
+        assert_eq!(output, b"!!");
+
+        let err = Brainfuck::new("+++:")
+            .with_dialect(Dialect::Pbrain)
+            .execute()
+            .unwrap_err();
+        assert!(matches!(err, Error::UndefinedProcedure { id: 3, .. }));
+
+        // the procedure instructions are comments in plain brainfuck
+        assert!(Brainfuck::new("+(:").execute().is_ok());
+        assert!(matches!(
+            Brainfuck::new("+(:").with_dialect(Dialect::Pbrain).execute(),
+            Err(Error::MismatchedBrackets { bracket: '(', .. }),
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_brainfork() -> Result<()> {
+        use brainfuck_exe::{Dialect, ThreadStats};
+
+        // the child takes its first turn right after the `Y`,
+        // printing `1 + 49` one step before the parent prints `0 + 49`
+        let mut output = Vec::new();
+        let info = Brainfuck::new(format!("Y{}.", "+".repeat(49)))
+            .with_dialect(Dialect::Brainfork)
+            .with_output_ref(&mut output)
+            .execute()?;
+
+        assert_eq!(output, b"21");
+        assert_eq!(info.cells, [49]);
+        assert_eq!(info.threads, [
+            ThreadStats { parent: None, instructions: 51, halted: true },
+            ThreadStats { parent: Some(0), instructions: 50, halted: true },
+        ]);
+        assert_eq!(info.instructions, 101);
+        Ok(())
+    }
+
+    #[test]
+    fn test_extended_type_i() -> Result<()> {
+        use brainfuck_exe::Dialect;
+
+        // stores 6, then computes `(6 << 1) | 6`, `~0` and `6 ^ 6`, ending before the last `+`
+        let info = Brainfuck::new("++++++${|>~>!^@+")
+            .with_dialect(Dialect::ExtendedTypeI)
+            .execute()?;
+        assert_eq!(info.cells, [14, 255, 0]);
+        assert_eq!(info.pointer, 2);
+
+        // the instructions are comments in plain brainfuck
+        let info = Brainfuck::new("+++$!{").execute()?;
+        assert_eq!(info.cells, [3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_instruction() -> Result<()> {
+        let info = Brainfuck::new("?>?>?")
+            .with_rng(|| 258)
+            .with_max_value(255)
+            .execute()?;
+        assert_eq!(info.cells, [2, 2, 2]);
+        assert_eq!(info.instructions, 5);
+
+        let seeded = |seed| Brainfuck::new("?>?>?>?")
+            .with_random_seed(seed)
+            .execute()
+            .map(|info| info.cells);
+        assert_eq!(seeded(7)?, seeded(7)?);
+        assert_ne!(seeded(7)?, seeded(8)?);
+
+        // `?` is a comment unless a generator is set
+        assert_eq!(Brainfuck::new("?+").execute()?.cells, [1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone_config_callbacks() -> Result<()> {
+        // the built-in generator is carried over, starting over from the same seed
+        let interp = Brainfuck::new("?>?").with_random_seed(7);
+        let info = interp.clone_config().execute()?;
+        assert_eq!(info.instructions, 3);
+        assert_eq!(info.cells, interp.clone_config().execute()?.cells);
+
+        // a custom generator and custom instructions cannot be copied, and have to be set again
+        let interp = Brainfuck::new("+\n?").with_rng(|| 1);
+        let result = interp.clone_config().execute();
+        assert!(matches!(
+            result,
+            Err(Error::MissingCallback { opcode: '?', position }) if position.line == 2
+        ));
+        assert_eq!(interp.clone_config().with_rng(|| 5).execute()?.cells, [5]);
+
+        let interp = Brainfuck::new("+*").with_custom_op('*', |_| ControlFlow::Continue(()));
+        assert!(matches!(
+            interp.clone_config().clone_config().execute(),
+            Err(Error::MissingCallback { opcode: '*', .. })
+        ));
+        // code that does not use them still executes
+        assert!(interp.clone_config().with_code("+").execute().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_op() -> Result<()> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        // `^` pushes the current cell to the host, `=` jumps the pointer to the cell index in the current cell
+        let info = Brainfuck::new("+++^>+++++=^!")
+            .with_custom_op('^', move |ctx| {
+                sender.send(ctx.cells[*ctx.pointer]).ok();
+                ControlFlow::Continue(())
+            })
+            .with_custom_op('=', |ctx| {
+                *ctx.pointer = ctx.cells[*ctx.pointer] as usize;
+                ctx.cells[0] = 300;
+                ControlFlow::Continue(())
+            })
+            .with_custom_op('!', |_| ControlFlow::Break(()))
+            .with_strict_syntax(true)
+            .execute();
+
+        assert!(matches!(info, Err(Error::Cancelled(ref context)) if context.code_idx == 12));
+        assert_eq!(receiver.iter().collect::<Vec<u32>>(), [3, 0]);
+
+        let info = Brainfuck::new("+++=")
+            .with_custom_op('=', |ctx| {
+                *ctx.pointer = 5;
+                ctx.cells[0] = 300;
+                ControlFlow::Continue(())
+            })
+            .execute()?;
+        assert_eq!(info.cells, [44, 0, 0, 0, 0, 0]);
+        assert_eq!(info.pointer, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_boolfuck() -> Result<()> {
+        use brainfuck_exe::Dialect;
+
+        let run = |code: &str, input: &'static [u8]| {
+            let mut output = Vec::new();
+            Brainfuck::new(code)
+                .with_dialect(Dialect::Boolfuck)
+                .with_input(Cursor::new(input))
+                .with_output_ref(&mut output)
+                .execute()?;
+            Ok::<_, brainfuck_exe::Error>(output)
+        };
+
+        // writes the bits of `A` least significant first, then echoes a byte bit by bit
+        assert_eq!(run("+;+;;;;;+;+; -.", b"")?, b"A");
+        assert_eq!(run(&",;".repeat(8), b"Z")?, b"Z");
+        // the remaining bits are padded into a byte
+        assert_eq!(run("+;", b"")?, [1]);
+        // cells are single bits
+        assert_eq!(run("++++++++++++++++++++++++++++++++++++++++++++++++++;;;;;;;;", b"")?, [0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_derivative_translation() -> Result<()> {
+        use brainfuck_exe::translate::{translate, Language};
+
+        let code = "++[>+<-]>.,";
+        assert_eq!(translate(code, Language::Brainfuck, Language::Alphuck)?, "eepaecisajo");
+        assert_eq!(translate(code, Language::Brainfuck, Language::ReverseFuck)?, "--]<->+[<,.");
+        assert_eq!(translate(code, Language::Brainfuck, Language::Spoon)?, "1100100010101100000110100010100010110");
+
+        for language in [Language::Alphuck, Language::ReverseFuck, Language::Spoon] {
+            let translated = translate(code, Language::Brainfuck, language)?;
+            assert_eq!(translate(&translated, language, Language::Brainfuck)?, code);
+        }
+        // spoon ignores anything but its bits, but rejects incomplete and unknown codes
+        assert_eq!(translate("1 1\n010", Language::Spoon, Language::Brainfuck)?, "++>");
+        assert!(matches!(
+            translate("1 00", Language::Spoon, Language::Brainfuck),
+            Err(Error::InvalidToken { ref token, position: Position { column: 3, .. } }) if token == "00"
+        ));
+        assert!(matches!(
+            translate("00101111", Language::Spoon, Language::Brainfuck),
+            Err(Error::InvalidToken { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_shebang() -> Result<()> {
+        let interp = Brainfuck::from_file("tests/shebang.bf")?;
+        assert_eq!(interp.code, "\n++++++++[>++++++++<-]>+.\n");
+
+        let mut output = Vec::new();
+        interp
+            .with_custom_op('!', |_| std::ops::ControlFlow::Break(()))
+            .with_output_ref(&mut output)
+            .execute()?;
+        assert_eq!(output, b"A");
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_state() -> Result<()> {
+        let info = Brainfuck::new("+++>++")
+            .with_output(std::io::sink())
+            .execute()?;
+        let info = Brainfuck::new("[-<+>]<")
+            .with_initial_state(info.cells, info.pointer)
+            .with_output(std::io::sink())
+            .execute()?;
+        assert_eq!((info.cells, info.pointer), (vec![5, 0], 0));
+
+        // the cells are resized to the memory size and wrapped to the max value
+        let info = Brainfuck::new("")
+            .with_mem_size(3)
+            .with_max_value(9)
+            .with_initial_state(vec![12, 3, 4, 5], 4)
+            .execute()?;
+        assert_eq!((info.cells, info.pointer), (vec![2, 3, 4], 1));
+
+        // the growable memory array is only grown up to the pointer within the limits
+        let start = |pointer| Brainfuck::new("+")
+            .with_limits(ExecutionLimits::new().with_memory_bytes(16))
+            .with_initial_state(vec![1], pointer)
+            .execute();
+        assert_eq!(start(3)?.cells, [1, 0, 0, 1]);
+        assert!(matches!(start(4), Err(Error::InitialPointerOutOfBounds { pointer: 4 })));
+        for pointer in [usize::MAX, usize::MAX / 2] {
+            let result = Brainfuck::new("+")
+                .with_initial_state(Vec::new(), pointer)
+                .execute();
+            assert!(matches!(result, Err(Error::InitialPointerOutOfBounds { pointer: p }) if p == pointer));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_eof_and_wrapping() -> Result<()> {
+        use brainfuck_exe::{EofBehavior, OptimizationLevel};
+
+        let run = |eof| Brainfuck::new("+++,")
+            .with_input(Cursor::new(b""))
+            .with_eof(eof)
+            .execute()
+            .map(|info| info.cells[0]);
+        assert_eq!(run(EofBehavior::Fallback)?, 0);
+        assert_eq!(run(EofBehavior::Max)?, 255);
+        assert_eq!(run(EofBehavior::Unchanged)?, 3);
+
+        // the changes before `,` are not dead stores when EOF keeps the cell
+        for level in [OptimizationLevel::Basic, OptimizationLevel::Aggressive] {
+            let info = Brainfuck::new(">+,")
+                .with_input(Cursor::new(b""))
+                .with_eof(EofBehavior::Unchanged)
+                .with_mem_size(2)
+                .with_optimization(level)
+                .execute()?;
+            assert_eq!(info.cells, [0, 1]);
+        }
+
+        let err = Brainfuck::new("+\n-->")
+            .with_wrapping(false)
+            .execute()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::CellOverflow(ref context) if context.position.line == 2 && context.position.column == 2
+        ));
+        assert!(Brainfuck::new("-").with_wrapping(true).execute().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate() -> Result<()> {
+        use brainfuck_exe::codegen::generate;
+
+        for text in ["", "Hello, World!\n", "zzzz a", "\u{3b1}\u{3b2}"] {
+            let code = generate(text);
+            let mut output = Vec::new();
+            Brainfuck::new(&code)
+                .with_max_value(0xFFFF)
+                .with_output_ref(&mut output)
+                .execute()?;
+
+            assert_eq!(String::from_utf8_lossy(&output), text);
+        }
+        // multiplication loops are shorter than the naive runs
+        assert!(generate("Hello, World!").len() < "Hello, World!".chars().map(|chr| chr as usize).sum());
+        assert_eq!(generate("AA"), ">++++++++[<++++++++>-]<+..");
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_const() -> Result<()> {
+        use brainfuck_exe::codegen::shortest_const;
+
+        for (value, max) in [(0, 255), (1, 255), (65, 255), (200, 255), (255, 255), (1234, 65535), (4_000_000, u32::MAX)] {
+            let code = shortest_const(value, max);
+            let info = Brainfuck::new(&code)
+                .with_max_value(max)
+                .execute()?;
+
+            assert_eq!(info.cells[0], value, "{code}");
+            assert!(info.cells[1..].iter().all(|&cell| cell == 0));
+        }
+        assert_eq!(shortest_const(0, 255), "");
+        // wrapping around is shorter than counting up
+        assert_eq!(shortest_const(254, 255), "--");
+        assert!(shortest_const(4_000_000, u32::MAX).len() < 300);
+        Ok(())
+    }
+
+    #[test]
+    fn test_obfuscate() -> Result<()> {
+        use brainfuck_exe::codegen::obfuscate;
+
+        let code = std::fs::read_to_string("tests/hello_world.bf")?;
+        for (code, input) in [(code.as_str(), ""), (",[.,]", "echo"), ("+++++.>-----.<<", "")] {
+            let run = |code: &str| {
+                let mut output = Vec::new();
+                let result = Brainfuck::new(code)
+                    .with_input(Cursor::new(input.to_string()))
+                    .with_output_ref(&mut output)
+                    .execute()
+                    .is_ok();
+                (output, result)
+            };
+            let obfuscated = obfuscate(code, 7);
+
+            assert_eq!(run(code), run(&obfuscated));
+            assert_eq!(obfuscated, obfuscate(code, 7));
+            assert_ne!(obfuscated, obfuscate(code, 8));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_equivalence() -> Result<()> {
+        use brainfuck_exe::equivalence::{fuzz_inputs, DivergenceKind};
+
+        let inputs = fuzz_inputs(32, 8, 1);
+        assert_eq!(inputs, fuzz_inputs(32, 8, 1));
+        assert!(inputs[0].is_empty());
+
+        let interp = Brainfuck::new(",[+.,]");
+        let optimized = interp.optimize()?.program.to_string();
+        let checked = interp.check_equivalence(&optimized, &inputs);
+        assert!(checked.is_equivalent());
+        assert_eq!(checked.compared, 32);
+
+        let divergence = interp
+            .check_equivalence(",[++.,]", ["", "ab"])
+            .divergence
+            .unwrap();
+        assert_eq!(divergence.input, b"ab");
+        assert_eq!(divergence.kind, DivergenceKind::Output { offset: 0, left: b"bc".to_vec(), right: b"cd".to_vec() });
+
+        // an unbalanced loop never finishing is not a divergence
+        let checked = Brainfuck::new("+[]")
+            .with_instructions_limit(100)
+            .check_equivalence("+[>]", [""]);
+        assert_eq!((checked.compared, checked.inconclusive), (0, 1));
+
+        let divergence = interp.check_equivalence("[", [""]).divergence.unwrap();
+        assert!(matches!(divergence.kind, DivergenceKind::Result { left: None, right: Some(_) }));
+        Ok(())
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_cross_check() -> Result<()> {
+        use brainfuck_exe::{testing::{reference, Mismatch, MismatchKind, REFERENCE_LIMIT}, Dialect, OptimizationLevel};
+
+        let code = std::fs::read_to_string("tests/hello_world.bf")?;
+        for level in [OptimizationLevel::None, OptimizationLevel::Basic, OptimizationLevel::Aggressive] {
+            for (code, input) in [(code.as_str(), &b""[..]), (",[>,]<[.<]", b"olleh"), ("+[-->-[>>+>-----<<]<--<---]>-.>>>+.", b"")] {
+                let interp = Brainfuck::new(code).with_optimization(level);
+                assert_eq!(interp.cross_check(input)?, None);
+            }
+        }
+
+        let run = reference(&Brainfuck::new("+.>++."), b"", REFERENCE_LIMIT).unwrap();
+        assert_eq!((run.output, run.output_sources, run.cells, run.pointer), (vec![1, 2], vec![1, 5], vec![1, 2], 1));
+        assert_eq!(reference(&Brainfuck::new("+[]"), b"", 1000), None);
+
+        // errors of the interpreter leave nothing to compare
+        assert!(Brainfuck::new("+[]").with_instructions_limit(1000).cross_check(b"").is_err());
+
+        // the reference interpreter only knows of plain brainfuck, and does not end the program at `@`
+        let mismatch = Brainfuck::new("+@\n+.")
+            .with_dialect(Dialect::ExtendedTypeI)
+            .cross_check(b"")?;
+        assert_eq!(mismatch, Some(Mismatch {
+            kind: MismatchKind::Output { offset: 0, expected: Some(2), actual: None },
+            position: Some(Position { offset: 4, line: 2, column: 2 }),
+        }));
+        Ok(())
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_program_generator() -> Result<()> {
+        use arbitrary::{Arbitrary, Unstructured};
+        use brainfuck_exe::{
+            ast::Program,
+            generator::{InstructionMix, ProgramGenerator, ValidProgram},
+        };
+
+        let generator = ProgramGenerator::new()
+            .with_max_len(32)
+            .with_max_depth(2);
+        for seed in 0..64_u8 {
+            let bytes = (0..256).map(|idx: u32| (idx as u8).wrapping_mul(seed).wrapping_add(seed)).collect::<Vec<u8>>();
+            let code = generator
+                .generate(&mut Unstructured::new(&bytes))
+                .unwrap();
+
+            let program = Program::parse(&code)?;
+            assert!(code.chars().filter(|&chr| chr != ']').count() <= 32);
+            assert!(program.analyze().loops.len() <= 32);
+            let _ = ValidProgram::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            let _ = Program::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        }
+
+        let only_increments = ProgramGenerator::new()
+            .with_mix(InstructionMix { increment: 1, decrement: 0, move_right: 0, move_left: 0, output: 0, input: 0, loops: 0 })
+            .generate(&mut Unstructured::new(&[255; 64]))
+            .unwrap();
+        assert!(!only_increments.is_empty() && only_increments.chars().all(|chr| chr == '+'));
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_corpus() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("brainfuck-corpus-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested"))?;
+        std::fs::write(dir.join("a.bf"), "++++++++[>++++++++<-]>+.")?;
+        std::fs::write(dir.join("b.bf"), "[")?;
+        std::fs::write(dir.join("nested/c.b"), "+[]")?;
+        std::fs::write(dir.join("notes.txt"), "+.")?;
+
+        let report = Brainfuck::new("")
+            .with_instructions_limit(1000)
+            .execute_corpus(&dir, 2);
+        std::fs::remove_dir_all(&dir)?;
+        let report = report?;
+
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(report.finished().map(|entry| entry.output.as_slice()).collect::<Vec<&[u8]>>(), [b"A"]);
+        assert!(report.failed().all(|entry| entry.path.ends_with("b.bf")));
+        assert!(report.limited().all(|entry| entry.path.ends_with("nested/c.b")));
+        assert_eq!((report.failed().count(), report.limited().count()), (1, 1));
+
+        assert!(matches!(Brainfuck::new("").execute_corpus(dir, 0), Err(Error::FileReadError(_))));
+        Ok(())
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn test_assert_output() {
+        let info = Brainfuck::new(",[+.,]").assert_output_with_input("HAL", "IBM");
+        assert_eq!(info.cells, [0]);
+        Brainfuck::new("++++++++++[>+++++++<-]>.+.[-]++++++++++.").assert_output("FG\n");
+
+        let mismatch = std::panic::catch_unwind(|| Brainfuck::new(",[.,]").assert_output_with_input("ab\ncd", "ab\nce"));
+        let message = *mismatch.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("byte offset 4"));
+        assert!(message.contains("-   2 | \"ce\"\n+   2 | \"cd\""));
+
+        assert!(std::panic::catch_unwind(|| Brainfuck::new("[").assert_output("")).is_err());
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_ffi() {
+        use std::ffi::CStr;
+        use brainfuck_exe::ffi::{bf_execute, bf_free, bf_get_error, bf_get_output, bf_new, bf_set_option, BfStatus};
+
+        unsafe {
+            let interp = bf_new(c",[+.,]".as_ptr());
+            assert_eq!(bf_set_option(interp, c"opt-level".as_ptr(), c"2".as_ptr()), BfStatus::Ok);
+            assert_eq!(bf_set_option(interp, c"opt-level".as_ptr(), c"3".as_ptr()), BfStatus::InvalidValue);
+            assert_eq!(bf_set_option(interp, c"unknown".as_ptr(), c"1".as_ptr()), BfStatus::UnknownOption);
+            assert_eq!(CStr::from_ptr(bf_get_error(interp)), c"`unknown` is not an option");
+
+            assert_eq!(bf_execute(interp, b"HAL".as_ptr(), 3), BfStatus::Ok);
+            assert!(bf_get_error(interp).is_null());
+            let mut len = 0;
+            let output = bf_get_output(interp, &mut len);
+            assert_eq!(std::slice::from_raw_parts(output, len), b"IBM");
+            bf_free(interp);
+
+            let interp = bf_new(c"+[]".as_ptr());
+            assert_eq!(bf_set_option(interp, c"instructions-limit".as_ptr(), c"100".as_ptr()), BfStatus::Ok);
+            assert_eq!(bf_execute(interp, std::ptr::null(), 0), BfStatus::ExecutionFailed);
+            assert!(!bf_get_error(interp).is_null());
+            bf_free(interp);
+
+            assert!(bf_new(std::ptr::null()).is_null());
+            assert_eq!(bf_execute(std::ptr::null_mut(), std::ptr::null(), 0), BfStatus::InvalidArgument);
+            bf_free(std::ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_render_html() -> Result<()> {
+        let code = "+[->++[-]<]x<y\n[.]]";
+        let html = render_html(code, &HtmlOptions::new());
+        assert!(html.contains("<span class=\"bf-loop bf-depth-1\">"));
+        assert!(html.contains("<span class=\"bf-comment\">x</span><span class=\"bf-pointer\">&lt;</span>"));
+        assert!(html.contains("<span class=\"bf-bracket bf-unmatched\" title=\"unmatched bracket\">]</span></pre>"));
+
+        let mut profiler = Profiler::new(code);
+        Brainfuck::new("+[->++[-]<]x<y\n[.]")
+            .with_observer(&mut profiler)
+            .execute()?;
+        let html = render_html(code, &HtmlOptions::new()
+            .with_standalone(true)
+            .with_title("a & b")
+            .with_profile(&profiler)
+        );
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>a &amp; b</title>"));
+        assert!(html.contains("<span class=\"bf-count\">&times;2</span>"));
+        assert!(html.contains("bf-depth-0 bf-cold"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_session() -> Result<()> {
+        let mut session = Session::new(Brainfuck::new("").with_mem_size(3));
+        session.push_input("ab");
+
+        assert!(matches!(session.eval("++>,")?, EvalOutcome::Executed { .. }));
+        assert!(matches!(session.eval("  ")?, EvalOutcome::Empty));
+        assert!(matches!(session.eval(":ptr")?, EvalOutcome::Printed(pointer) if pointer == "1"));
+        assert!(matches!(session.eval(":foo"), Err(Error::UnknownCommand(command)) if command == ":foo"));
+        assert!(matches!(session.eval("<]"), Err(Error::MismatchedBrackets { .. })));
+
+        let EvalOutcome::Executed { output, .. } = session.eval("<.>.,.")? else {
+            panic!("the line was not executed");
+        };
+        assert_eq!(output, [2, b'a', b'b']);
+        assert_eq!((session.cells(), session.pointer()), (&[2, 98, 0][..], 1));
+
+        assert!(matches!(session.eval(":reset")?, EvalOutcome::Reset));
+        assert!(matches!(session.eval(":cells")?, EvalOutcome::Printed(cells) if cells == "[0] 0 0"));
+        assert!(matches!(session.eval(":q")?, EvalOutcome::Quit));
+
+        // the custom instructions are kept across lines
+        let mut session = Session::new(
+            Brainfuck::new("").with_custom_op('*', |ctx| {
+                ctx.cells[*ctx.pointer] *= 2;
+                ControlFlow::Continue(())
+            })
+        );
+        session.eval("+++")?;
+        session.eval("*")?;
+        assert_eq!(session.cells(), [6]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_with_io() -> Result<()> {
+        let mut configured = Vec::new();
+        let mut interp = Brainfuck::new(",[+.,]")
+            .with_output_ref(&mut configured);
+
+        for (input, expected) in [("abc", "bcd"), ("HAL", "IBM")] {
+            let mut output = Vec::new();
+            let info = interp.execute_with_io(&mut input.as_bytes(), &mut output)?;
+
+            assert_eq!(output, expected.as_bytes());
+            assert_eq!(info.cells, [0]);
+        }
+        interp
+            .with_input(Cursor::new("z"))
+            .execute()?;
+        assert_eq!(configured, b"{");
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_reader() -> Result<()> {
+        // latin-1 comments are not valid UTF-8, but are still only comments
+        let source = b"#!/usr/bin/env brainfuck\n\xE9t\xE9: ++++++++[>++++++++<-]>+.";
+        let mut output = Vec::new();
+
+        let mut interp = Brainfuck::from_reader(Cursor::new(source))?;
+        assert!(interp.code.starts_with('\n'));
+        interp.execute_with_io(&mut std::io::empty(), &mut output)?;
+        assert_eq!(output, b"A");
+
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(ErrorKind::ConnectionReset.into())
+            }
+        }
+        assert!(matches!(Brainfuck::from_reader(Failing), Err(Error::FileReadError(_))));
+        Ok(())
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_from_url() -> Result<()> {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let server = thread::spawn(move || {
+            for body in ["++++++++[>++++++++<-]>+.", "+".repeat(64).as_str()] {
+                let (mut stream, _) = listener.accept()?;
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request)?;
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len())?;
+            }
+            Ok::<(), std::io::Error>(())
+        });
+
+        let url = format!("http://{address}/program.bf?raw=1");
+        let mut output = Vec::new();
+        Brainfuck::from_url(&url, 1024, Duration::from_secs(5))?
+            .execute_with_io(&mut std::io::empty(), &mut output)?;
+        assert_eq!(output, b"A");
+
+        assert!(matches!(
+            Brainfuck::from_url(&url, 32, Duration::from_secs(5)),
+            Err(Error::FileReadError(err)) if err.kind() == ErrorKind::InvalidData,
+        ));
+        server.join().map_err(|_| Error::IoError(ErrorKind::Other.into()))??;
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_output() -> Result<()> {
+        let mut output = Vec::new();
+
+        let info = Brainfuck::new("++++++[>++++++++<-]>.+.")
+            .with_output_ref(&mut output)
+            .with_no_output()
+            .execute()?;
+        assert_eq!(info.cells[..2], [0, 49]);
+        assert!(output.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_output_sans_io() -> Result<()> {
+        let mut interp = Brainfuck::new(",.+.").with_no_output();
+        let mut execution = interp.start()?;
+
+        assert!(matches!(execution.resume()?, Step::NeedsInput));
+        execution.feed(Some(b'a'));
+        assert!(matches!(execution.resume()?, Step::Halted(ref info) if info.instructions == 4 && info.cells[0] == 98));
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_output_events() -> Result<()> {
+        let mut interp = Brainfuck::new("+.+.").with_no_output();
+        let events = interp
+            .iter_events()?
+            .collect::<Result<Vec<ExecutionEvent>>>()?;
+
+        assert!(!events.iter().any(|event| matches!(event, ExecutionEvent::Output(_))));
+        assert!(matches!(events.last(), Some(ExecutionEvent::Halted(info)) if info.instructions == 4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_output_scheduler() -> Result<()> {
+        let mut scheduler = Scheduler::new(2);
+        let discarded = scheduler.spawn(Brainfuck::new("+.+.").with_no_output())?;
+        let written = scheduler.spawn(Brainfuck::new("+.+."))?;
+
+        scheduler.run();
+        assert!(scheduler.output(discarded).is_empty());
+        assert_eq!(scheduler.output(written), [1, 2]);
+        assert!(matches!(scheduler.state(discarded), ProgramState::Halted(ref info) if info.instructions == 4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_io_paths() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("brainfuck-output-{}.txt", std::process::id()));
+        for mode in [OpenMode::Truncate, OpenMode::Append] {
+            Brainfuck::new(",.,.")
+                .with_input_path("tests/input.txt")?
+                .with_output_path(&path, mode)?
+                .execute()?;
+        }
+        let created = Brainfuck::new("").with_output_path(&path, OpenMode::CreateNew);
+        let output = std::fs::read(&path)?;
+        std::fs::remove_file(&path)?;
+
+        let input = std::fs::read("tests/input.txt")?;
+        assert_eq!(output, [&input[..2], &input[..2]].concat());
+        assert!(matches!(created, Err(Error::FileWriteError(err)) if err.kind() == ErrorKind::AlreadyExists));
+        assert!(matches!(
+            Brainfuck::new("").with_input_path("tests/missing.txt"),
+            Err(Error::FileReadError(_)),
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_flush_policy() -> Result<()> {
+        /// an output stream recording the amount of bytes written at every flush
+        #[derive(Default)]
+        struct FlushRecorder {
+            written: usize,
+            flushes: Vec<usize>,
+        }
+
+        impl Write for FlushRecorder {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.written += buf.len();
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                self.flushes.push(self.written);
+                Ok(())
+            }
+        }
+
+        // writes `AAA\nAAA\nAA`
+        let code = "++++++++++[>++++++>+<<-]>+++...>.<...>.<..";
+        for (policy, expected) in [
+            (FlushPolicy::EveryWrite, (1..=10).collect::<Vec<usize>>()),
+            (FlushPolicy::OnNewline, vec![4, 8, 10]),
+            (FlushPolicy::EveryNBytes(3), vec![3, 6, 9, 10]),
+            (FlushPolicy::AtEnd, vec![10]),
+        ] {
+            let mut output = FlushRecorder::default();
+            Brainfuck::new(code)
+                .with_flush_policy(policy)
+                .with_output_ref(&mut output)
+                .execute()?;
+            assert_eq!(output.flushes, expected, "{policy:?}");
+        }
+
+        // the output buffered before a failure is still flushed
+        let mut output = Vec::new();
+        let result = Brainfuck::new("++++++++[>++++++++<-]>+.[]")
+            .with_flush_policy(FlushPolicy::AtEnd)
+            .with_instructions_limit(1000)
+            .with_output_ref(&mut output)
+            .execute();
+        assert!(matches!(result, Err(Error::MaxInstructionsExceeded { .. })));
+        assert_eq!(output, b"A");
+        Ok(())
+    }
+
+    #[test]
+    fn test_execution_info_display() -> Result<()> {
+        let info = Brainfuck::new("+>".repeat(20))
+            .with_mem_size(30_000)
+            .with_bench_execution(false)
+            .execute()?;
+        let report = info.to_string();
+
+        assert!(report.starts_with("instructions: 40\npointer:      20\nmemory size:  30000\n"));
+        assert!(report.ends_with("[15] = 1, and 4 more"));
+        assert_eq!(info.summary(), "40 instructions, pointer at 20, 20 non-zero cells out of 30000");
+
+        let info = Brainfuck::new("")
+            .with_bench_execution(true)
+            .execute()?;
+        assert!(info.to_string().contains("\ntime:         "));
+        assert!(info.to_string().ends_with("cells:        all zero"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_execution_info_diff() -> Result<()> {
+        let code = "+[+>+<]>>+";
+        let wrapping = Brainfuck::new(code).execute()?;
+        let narrow = Brainfuck::new(code)
+            .with_max_value(15)
+            .execute()?;
+
+        let diff = wrapping.diff(&narrow);
+        assert_eq!(diff.cells, [CellDiff { index: 1, left: 255, right: 15 }]);
+        assert_eq!(diff.pointer, (2, 2));
+        assert_eq!(diff.instructions_delta(), -(255 - 15) * 6);
+        assert_eq!(diff.to_string().lines().count(), 2);
+
+        assert!(wrapping.diff(&wrapping).is_empty());
+        assert_eq!(wrapping.diff(&wrapping).to_string(), "no differences");
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_events() -> Result<()> {
+        let mut interp = Brainfuck::new(", .")
+            .with_input(Cursor::new(b"A".to_vec()));
+        let events = interp
+            .iter_events()?
+            .collect::<Result<Vec<ExecutionEvent>>>()?;
+
+        assert!(matches!(events[..], [
+            ExecutionEvent::Instruction(HookCtx { opcode: ',', code_idx: 0, .. }),
+            ExecutionEvent::InputRequested,
+            ExecutionEvent::Instruction(HookCtx { opcode: '.', code_idx: 2, cell: 65, .. }),
+            ExecutionEvent::Output(b'A'),
+            ExecutionEvent::Halted(ref info),
+        ] if info.instructions == 2));
+
+        let mut interp = Brainfuck::new("+[]")
+            .with_instructions_limit(10);
+        let mut events = interp.iter_events()?;
+        assert_eq!(events.by_ref().take_while(Result::is_ok).count(), 10);
+        assert!(events.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_swap_streams() -> Result<()> {
+        let mut interp = Brainfuck::new(",[+.,]")
+            .with_input(Cursor::new(b"abc".to_vec()));
+        let mut outputs = Vec::new();
+
+        for input in [&b"HAL"[..], b"uftu"] {
+            let output = SharedBuffer::default();
+            interp.set_input(Cursor::new(input.to_vec()));
+            interp.set_output(output.clone());
+            interp.execute()?;
+
+            outputs.push(output.0.lock().unwrap().clone());
+        }
+        assert_eq!(outputs, [&b"IBM"[..], b"vguv"]);
+
+        assert!(interp.take_output().is_some());
+        assert!(interp.take_output().is_none());
+        assert!(interp.take_input().is_some());
+        assert!(interp.take_input().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_execution_limits() {
+        let limits = ExecutionLimits::untrusted()
+            .with_memory_bytes(40)
+            .with_loop_depth(2);
+        let interp = Brainfuck::new("+[>+]").with_limits(limits);
+        assert_eq!(interp.limits(), limits);
+        assert_eq!(interp.instructions_limit, Some(10_000_000));
+
+        let err = Brainfuck::new("+[>+]")
+            .with_limits(limits)
+            .execute()
+            .unwrap_err();
+        assert!(matches!(err, Error::MemoryLimitExceeded { limit: 40, .. }));
+        assert_eq!(err.context().unwrap().info.cells.len(), 11);
+
+        let mut output = Vec::new();
+        let err = Brainfuck::new("++++++++[>++++++++<-]>+[.]")
+            .with_limits(ExecutionLimits::new().with_output_bytes(3))
+            .with_output_ref(&mut output)
+            .execute()
+            .unwrap_err();
+        assert!(matches!(err, Error::OutputLimitExceeded { limit: 3, .. }));
+        assert_eq!(output, b"AAA");
+
+        let err = Brainfuck::new("[[]\n [[[]]]]")
+            .with_limits(limits)
+            .execute()
+            .unwrap_err();
+        assert!(matches!(err, Error::LoopDepthExceeded { limit: 2, .. }));
+        assert_eq!(err.position().map(|position| (position.line, position.column)), Some((2, 3)));
+    }
+
+    #[test]
+    fn test_scheduler() -> Result<()> {
+        let mut scheduler = Scheduler::new(5);
+        let counter = scheduler.spawn(Brainfuck::new("+".repeat(12)))?;
+        let looping = scheduler.spawn(Brainfuck::new("+[]").with_instructions_limit(8))?;
+        let echo = scheduler.spawn(Brainfuck::new(",[.,]"))?;
+
+        // every program executes at most one slice per round
+        assert!(scheduler.run_round());
+        assert_eq!((scheduler.info(counter).instructions, scheduler.info(looping).instructions), (5, 5));
+        assert!(matches!(scheduler.state(echo), ProgramState::WaitingForInput));
+
+        assert_eq!(scheduler.run(), 2);
+        assert!(matches!(scheduler.state(counter), ProgramState::Halted(ref info) if info.cells[0] == 12));
+        assert!(matches!(scheduler.state(looping), ProgramState::Failed(Error::MaxInstructionsExceeded { .. })));
+        assert!(!scheduler.run_round());
+
+        scheduler.push_input(echo, "hi");
+        assert!(scheduler.run() > 0);
+        assert_eq!(scheduler.take_output(echo), b"hi");
+        assert!(matches!(scheduler.state(echo), ProgramState::WaitingForInput));
+
+        scheduler.close_input(echo);
+        scheduler.run();
+        assert!(scheduler.state(echo).is_finished());
+        assert!(scheduler.output(echo).is_empty());
+        assert_eq!(scheduler.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_loop_iterations() -> Result<()> {
+        use brainfuck_exe::OptimizationLevel;
+
+        // the inner loop iterates 200 times in total, but only 4 times every time it is entered
+        let nested = "++++++++++[>++++++++++[>++++<-]<-]";
+        let info = Brainfuck::new(nested)
+            .with_max_loop_iterations(10)
+            .execute()?;
+        assert_eq!(info.cells[2], 400 % 256);
+
+        for level in [OptimizationLevel::None, OptimizationLevel::Aggressive] {
+            let code = format!("{nested}\n,[>+<]");
+            let err = Brainfuck::new(&code)
+                .with_optimization(level)
+                .with_max_loop_iterations(10)
+                .with_input(Cursor::new(b"A".to_vec()))
+                .execute()
+                .unwrap_err();
+
+            let Error::LoopIterationsExceeded { limit, span, ref context } = err else {
+                panic!("unexpected error {err:?}");
+            };
+            assert_eq!(limit, 10);
+            assert_eq!(&code[span.range()], "[>+<]");
+            assert_eq!((span.start.line, span.start.column), (2, 2));
+            assert_eq!(context.position, span.start);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing() {
+        use std::{fmt, sync::atomic::AtomicU64};
+        use brainfuck_exe::OptimizationLevel;
+        use tracing::{
+            field::{Field, Visit},
+            span::{Attributes, Id, Record},
+            Event, Metadata, Subscriber,
+        };
+
+        /// records the name of every span and the message of every event
+        #[derive(Default)]
+        struct Captured {
+            spans: Mutex<Vec<&'static str>>,
+            events: Mutex<Vec<String>>,
+            ids: AtomicU64,
+        }
+
+        struct Capture(Arc<Captured>);
+
+        struct Message(String);
+
+        impl Visit for Message {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                if field.name() == "message" {
+                    self.0 = format!("{value:?}");
+                }
+            }
+        }
+
+        impl Subscriber for Capture {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                self.0.spans.lock().unwrap().push(span.metadata().name());
+                Id::from_u64(self.0.ids.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut message = Message(String::new());
+                event.record(&mut message);
+                self.0.events.lock().unwrap().push(message.0);
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        let captured = Arc::new(Captured::default());
+        let result = tracing::subscriber::with_default(Capture(Arc::clone(&captured)), || {
+            Brainfuck::new("+.+[.]")
+                .with_optimization(OptimizationLevel::Basic)
+                .with_mem_size(30_000)
+                .with_output(std::io::sink())
+                .with_instructions_limit(20)
+                .execute()
+        });
+        assert!(matches!(result, Err(Error::MaxInstructionsExceeded { .. })));
+
+        let spans = captured.spans.lock().unwrap();
+        assert_eq!(*spans, ["execute", "parse", "optimize"]);
+
+        let events = captured.events.lock().unwrap();
+        assert_eq!(events.first().map(String::as_str), Some("execution started"));
+        assert!(events.iter().any(|event| event == "wrote output"));
+        assert_eq!(events.last().map(String::as_str), Some("instructions limit exceeded"));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_metrics() -> Result<()> {
+        use std::collections::HashMap;
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        metrics::with_local_recorder(&recorder, || {
+            Brainfuck::new(",+.")
+                .with_input(&b"a"[..])
+                .with_output(std::io::sink())
+                .execute()?;
+
+            let result = Brainfuck::new("+[]")
+                .with_instructions_limit(10)
+                .execute();
+            assert!(matches!(result, Err(Error::MaxInstructionsExceeded { .. })));
+            Ok::<_, Error>(())
+        })?;
+
+        let metrics = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let key = key.key();
+                let labels = key.labels()
+                    .map(|label| format!("{{{}={}}}", label.key(), label.value()))
+                    .collect::<String>();
+                (format!("{}{labels}", key.name()), value)
+            })
+            .collect::<HashMap<String, DebugValue>>();
+
+        assert_eq!(metrics["brainfuck_input_bytes_total"], DebugValue::Counter(1));
+        assert_eq!(metrics["brainfuck_output_bytes_total"], DebugValue::Counter(1));
+        assert_eq!(metrics["brainfuck_instructions_total"], DebugValue::Counter(3 + 11));
+        assert_eq!(metrics["brainfuck_runs_completed_total"], DebugValue::Counter(1));
+        assert_eq!(
+            metrics["brainfuck_runs_failed_total{kind=max_instructions_exceeded}"],
+            DebugValue::Counter(1),
+        );
+        assert_eq!(metrics["brainfuck_memory_cells"], DebugValue::Gauge(1.0.into()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimization_differential() -> Result<()> {
+        use brainfuck_exe::OptimizationLevel;
+
+        let programs = [
+            ">><+<<+",
+            "<+<+>>+",
+            ">+[<+>-]<.",
+            ">+++[<++>-]<<<-.",
+            "++>+++[<[-]>-]<.>,.",
+            "++++++++[>++++++++<-]>+.+.<<.",
+            "+<<<[-]>>>[->+<]>.",
+        ];
+        for code in programs {
+            for mem_size in [None, Some(3), Some(4), Some(30_000)] {
+                let execute = |level| {
+                    let mut output = Vec::new();
+                    let mut interp = Brainfuck::new(code)
+                        .with_optimization(level)
+                        .with_instructions_limit(100_000)
+                        .with_input(&b"q"[..])
+                        .with_output_ref(&mut output);
+                    if let Some(size) = mem_size {
+                        interp = interp.with_mem_size(size);
+                    }
+                    let info = interp.execute()?;
+                    drop(interp);
+                    Ok::<_, Error>((output, info.cells, info.pointer))
+                };
+                let expected = execute(OptimizationLevel::None)?;
+
+                for level in [OptimizationLevel::Basic, OptimizationLevel::Aggressive] {
+                    assert_eq!(execute(level)?, expected, "`{code}` at {level:?} with a memory size of {mem_size:?}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_execute_batch() {
+        let results = Brainfuck::execute_batch([
+            Brainfuck::new("++++++++[>++++++++<-]>+."),
+            Brainfuck::new("["),
+        ]);
+
+        assert_eq!(results[0].output, b"A");
+        assert!(results[1].result.is_err());
+
+        let results = Brainfuck::new(",+.,+.")
+            .execute_inputs(["ab", "xy"]);
+
+        assert_eq!(results[0].output, b"bc");
+        assert_eq!(results[1].output, b"yz");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_execute_async() -> Result<()> {
+        let mut input = "ab".as_bytes();
+        let mut output = Vec::new();
+
+        let info = Brainfuck::new(",+.>,+.")
+            .execute_async(&mut input, &mut output)
+            .await?;
+
+        assert_eq!(output, b"bc");
+        assert_eq!(info.instructions, 7);
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_execute_async_no_output() -> Result<()> {
+        let mut output = Vec::new();
+
+        let info = Brainfuck::new("+.+.")
+            .with_no_output()
+            .execute_async(&mut tokio::io::empty(), &mut output)
+            .await?;
+
+        assert!(output.is_empty());
+        assert_eq!(info.instructions, 4);
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_execute_async_flush() {
+        use std::{pin::Pin, task::{Context, Poll}};
+        use tokio::io::{AsyncWrite, BufWriter};
+
+        /// accepts every write, but fails to flush
+        struct Unflushable;
+
+        impl AsyncWrite for Unflushable {
+            fn poll_write(self: Pin<&mut Self>, _: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+                Poll::Ready(Ok(buf.len()))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                Poll::Ready(Err(std::io::Error::other("flush failed")))
+            }
+
+            fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        let result = Brainfuck::new("+++.")
+            .with_flush_policy(FlushPolicy::AtEnd)
+            .execute_async(&mut &b""[..], &mut Unflushable)
+            .await;
+        assert!(matches!(result, Err(Error::OutputError { .. })));
+
+        // the output written before the failure is still flushed
+        let mut output = BufWriter::new(Vec::new());
+        let result = Brainfuck::new("++++++++[>++++++++<-]>+.[.]")
+            .with_flush_policy(FlushPolicy::AtEnd)
+            .with_instructions_limit(200)
+            .execute_async(&mut &b""[..], &mut output)
+            .await;
+
+        assert!(matches!(result, Err(Error::MaxInstructionsExceeded { .. })));
+        assert!(output.get_ref().starts_with(b"AA"));
+    }
+}